@@ -0,0 +1,393 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use egui_dock::egui;
+use space_prefab::save::{SaveOverride, SaveState, SceneWriteFailed};
+use space_shared::*;
+use space_undo::ChangeCommitted;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
+
+use crate::{journal::ChangeJournal, load::SceneLoaded};
+
+const SCENE_EXTENSION: &str = ".scn.ron";
+const AUTOSAVE_EXTENSION: &str = ".autosave";
+/// Autosave target while scene was never saved or loaded
+const UNTITLED_SCENE: &str = "assets/scenes/untitled.scn.ron";
+
+/// Plugin which periodically saves unsaved scene to `.autosave.scn.ron` file next to it
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autosave>()
+            .init_resource::<AutosaveSettings>()
+            .register_type::<AutosaveSettings>()
+            .add_event::<AutosaveAction>();
+
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<AutosaveSettings>()
+            .persistence_layer::<AutosaveSettings>(PersistenceLayer::User);
+
+        app.add_systems(
+            Update,
+            (
+                autosave_scene_events,
+                autosave_write_failed,
+                autosave_actions,
+                autosave_timer.run_if(in_state(EditorState::Editor)),
+            )
+                .chain()
+                .after(crate::EditorLoadSet),
+        );
+        app.add_systems(OnEnter(EditorState::GamePrepare), autosave_before_play);
+    }
+}
+
+/// Plugin with dialog which offers to restore newer autosave of opened scene
+pub struct AutosaveUiPlugin;
+
+impl Plugin for AutosaveUiPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, show_autosave_offer.in_set(EditorSet::Editor));
+    }
+}
+
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource, Default)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    /// Seconds between first unsaved change and autosave
+    pub interval: f32,
+    /// Number of rotating autosave files for each scene
+    pub copies: usize,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 300.0,
+            copies: 3,
+        }
+    }
+}
+
+/// Autosave state of current scene
+#[derive(Resource, Default)]
+pub struct Autosave {
+    /// Scene file, next to which autosave files are written
+    scene: Option<String>,
+    dirty: bool,
+    elapsed: f32,
+    offer: Option<AutosaveOffer>,
+    restoring: bool,
+}
+
+impl Autosave {
+    /// Newer autosave of opened scene, which can be restored or ignored with [`AutosaveAction`]
+    pub const fn offer(&self) -> Option<&AutosaveOffer> {
+        self.offer.as_ref()
+    }
+
+    fn scene_file(&self) -> &str {
+        self.scene.as_deref().unwrap_or(UNTITLED_SCENE)
+    }
+}
+
+/// Autosave which is newer than opened scene file
+#[derive(Clone, Debug)]
+pub struct AutosaveOffer {
+    /// Scene path relative to assets folder
+    pub scene: String,
+    /// Autosave path relative to assets folder
+    pub autosave: String,
+}
+
+/// User decision about found autosave
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutosaveAction {
+    /// Load autosave instead of scene
+    Restore,
+    /// Keep opened scene
+    Ignore,
+}
+
+/// Path of autosave file with given index (0 is the most recent) next to scene file
+pub fn autosave_path(scene_file: &str, index: usize) -> String {
+    let stem = scene_file
+        .strip_suffix(SCENE_EXTENSION)
+        .unwrap_or(scene_file);
+    if index == 0 {
+        format!("{}{}{}", stem, AUTOSAVE_EXTENSION, SCENE_EXTENSION)
+    } else {
+        format!(
+            "{}{}.{}{}",
+            stem, AUTOSAVE_EXTENSION, index, SCENE_EXTENSION
+        )
+    }
+}
+
+pub fn is_autosave_path(path: &str) -> bool {
+    path.strip_suffix(SCENE_EXTENSION).is_some_and(|stem| {
+        stem.ends_with(AUTOSAVE_EXTENSION)
+            || stem.rsplit_once('.').is_some_and(|(stem, index)| {
+                stem.ends_with(AUTOSAVE_EXTENSION) && index.parse::<usize>().is_ok()
+            })
+    })
+}
+
+/// Shift autosave files by one, so the most recent autosave can be written.
+/// The oldest copy is removed. Nothing is shifted while the most recent autosave is missing
+/// (previous autosave failed), so older copies are kept
+fn rotate_autosaves(scene_file: &str, copies: usize) -> std::io::Result<()> {
+    let copies = copies.max(1);
+    if let Some(dir) = Path::new(scene_file)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+    {
+        fs::create_dir_all(dir)?;
+    }
+    if !Path::new(&autosave_path(scene_file, 0)).exists() {
+        return Ok(());
+    }
+    let oldest = autosave_path(scene_file, copies - 1);
+    if Path::new(&oldest).exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for index in (0..copies - 1).rev() {
+        let path = autosave_path(scene_file, index);
+        if Path::new(&path).exists() {
+            fs::rename(&path, autosave_path(scene_file, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Autosave of scene (relative to assets folder), if it was written after the scene file
+fn newer_autosave(scene: &str) -> Option<AutosaveOffer> {
+    let modified = |path: &str| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let scene_modified = modified(&asset_file_path(scene))?;
+    let autosave = autosave_path(scene, 0);
+    let autosave_modified = modified(&asset_file_path(&autosave))?;
+    (autosave_modified > scene_modified).then(|| AutosaveOffer {
+        scene: scene.to_string(),
+        autosave,
+    })
+}
+
+/// Add autosave file to next save. Returns false if autosave files could not be rotated
+fn request_autosave(
+    autosave: &mut Autosave,
+    settings: &AutosaveSettings,
+    save_override: &mut SaveOverride,
+) -> bool {
+    autosave.dirty = false;
+    autosave.elapsed = 0.0;
+
+    let scene_file = autosave.scene_file().to_string();
+    if let Err(err) = rotate_autosaves(&scene_file, settings.copies) {
+        error!("Failed to rotate autosaves of {}: {}", scene_file, err);
+        return false;
+    }
+    save_override.files.push(autosave_path(&scene_file, 0));
+    info!("Autosaving scene {}", scene_file);
+    true
+}
+
+fn autosave_scene_events(mut events: EventReader<EditorEvent>, mut autosave: ResMut<Autosave>) {
+    for event in events.read() {
+        match event {
+            EditorEvent::Load(EditorPrefabPath::File(path)) => {
+                if autosave.restoring && is_autosave_path(path) {
+                    // Restored autosave keeps path of its scene and is not saved yet
+                    autosave.restoring = false;
+                    autosave.dirty = true;
+                    continue;
+                }
+                autosave.scene = Some(asset_file_path(path));
+                autosave.dirty = false;
+                autosave.elapsed = 0.0;
+                autosave.offer = if is_autosave_path(path) {
+                    None
+                } else {
+                    newer_autosave(path)
+                };
+            }
+            EditorEvent::Save(EditorPrefabPath::File(path)) => {
+                autosave.scene = Some(path.clone());
+                autosave.dirty = false;
+                autosave.elapsed = 0.0;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Failed autosave is retried after next interval
+fn autosave_write_failed(
+    mut failed: EventReader<SceneWriteFailed>,
+    mut autosave: ResMut<Autosave>,
+) {
+    let autosave_file = autosave_path(autosave.scene_file(), 0);
+    if failed.read().any(|failed| failed.path == autosave_file) {
+        autosave.dirty = true;
+        autosave.elapsed = 0.0;
+    }
+}
+
+fn autosave_actions(
+    mut actions: EventReader<AutosaveAction>,
+    mut autosave: ResMut<Autosave>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    for action in actions.read() {
+        let Some(offer) = autosave.offer.take() else {
+            continue;
+        };
+        if *action == AutosaveAction::Restore {
+            autosave.restoring = true;
+            editor_events.send(EditorEvent::Load(EditorPrefabPath::File(offer.autosave)));
+        }
+    }
+}
+
+fn autosave_timer(
+    mut autosave: ResMut<Autosave>,
+    settings: Res<AutosaveSettings>,
+    time: Res<Time<Real>>,
+    mut changes: EventReader<ChangeCommitted>,
+    mut loaded: EventReader<SceneLoaded>,
+    save_state: Res<State<SaveState>>,
+    mut next_save_state: ResMut<NextState<SaveState>>,
+    mut save_override: ResMut<SaveOverride>,
+) {
+    // Despawn of previous scene on load is not a user change
+    if loaded.read().count() > 0 {
+        changes.clear();
+    } else if changes.read().count() > 0 {
+        autosave.dirty = true;
+    }
+
+    if !settings.enabled || !autosave.dirty {
+        return;
+    }
+    autosave.elapsed += time.delta_seconds();
+    if autosave.elapsed < settings.interval || *save_state.get() != SaveState::Idle {
+        return;
+    }
+
+    if request_autosave(&mut autosave, &settings, &mut save_override) {
+        save_override.skip_config_path = true;
+        next_save_state.set(SaveState::Save);
+    }
+}
+
+/// Scene is saved to memory cache on play mode start, autosave is written by the same save
+fn autosave_before_play(
+    mut autosave: ResMut<Autosave>,
+    settings: Res<AutosaveSettings>,
+    mut save_override: ResMut<SaveOverride>,
+) {
+    if settings.enabled && autosave.dirty {
+        request_autosave(&mut autosave, &settings, &mut save_override);
+    }
+}
+
+fn show_autosave_offer(
+    mut ctxs: EguiContexts,
+    autosave: Res<Autosave>,
+    journal: Option<Res<ChangeJournal>>,
+    mut actions: EventWriter<AutosaveAction>,
+) {
+    let Some(offer) = autosave.offer() else {
+        return;
+    };
+    // Journal restores every change, so it is offered first
+    if journal.is_some_and(|journal| journal.offer().is_some()) {
+        return;
+    }
+    egui::Window::new("Restore autosave")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(format!(
+                "Found autosave of scene {} which is newer than the scene file",
+                offer.scene
+            ));
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    actions.send(AutosaveAction::Restore);
+                }
+                if ui.button("Ignore").clicked() {
+                    actions.send(AutosaveAction::Ignore);
+                }
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autosave_path_is_next_to_scene() {
+        assert_eq!(
+            autosave_path("assets/scenes/level.scn.ron", 0),
+            "assets/scenes/level.autosave.scn.ron"
+        );
+        assert_eq!(
+            autosave_path("assets/scenes/level.scn.ron", 2),
+            "assets/scenes/level.autosave.2.scn.ron"
+        );
+        assert!(is_autosave_path("scenes/level.autosave.scn.ron"));
+        assert!(is_autosave_path("scenes/level.autosave.2.scn.ron"));
+        assert!(!is_autosave_path("scenes/level.scn.ron"));
+        assert!(!is_autosave_path("scenes/level.v2.scn.ron"));
+    }
+
+    #[test]
+    fn rotates_autosave_copies() {
+        let dir = std::env::temp_dir().join("space_editor_autosave_rotation");
+        let _ = fs::remove_dir_all(&dir);
+        let scene = dir.join("level.scn.ron").to_str().unwrap().to_string();
+
+        for save in 0..4 {
+            rotate_autosaves(&scene, 3).unwrap();
+            fs::write(autosave_path(&scene, 0), save.to_string()).unwrap();
+        }
+
+        let read = |index| fs::read_to_string(autosave_path(&scene, index)).unwrap();
+        assert_eq!(read(0), "3");
+        assert_eq!(read(1), "2");
+        assert_eq!(read(2), "1");
+        assert!(!Path::new(&autosave_path(&scene, 3)).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_autosave_does_not_rotate_copies() {
+        let dir = std::env::temp_dir().join("space_editor_autosave_failed");
+        let _ = fs::remove_dir_all(&dir);
+        let scene = dir.join("level.scn.ron").to_str().unwrap().to_string();
+
+        rotate_autosaves(&scene, 3).unwrap();
+        fs::write(autosave_path(&scene, 0), "0").unwrap();
+        rotate_autosaves(&scene, 3).unwrap();
+        fs::write(autosave_path(&scene, 0), "1").unwrap();
+        // Next autosaves are not written
+        for _ in 0..3 {
+            rotate_autosaves(&scene, 3).unwrap();
+        }
+
+        let read = |index| fs::read_to_string(autosave_path(&scene, index)).unwrap();
+        assert_eq!(read(1), "1");
+        assert_eq!(read(2), "0");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
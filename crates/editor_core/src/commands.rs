@@ -0,0 +1,446 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bevy::{ecs::event::ManualEventReader, prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
+use space_prefab::save::SaveConfig;
+use space_shared::{toast::ToastMessage, EditorEvent, EditorPrefabPath, EditorSet};
+use space_undo::{LabeledChange, NewChange, RemovedEntity, UndoRedo};
+
+use crate::{
+    hotkeys::{Hotkey, HotkeyAppExt, HotkeyInput},
+    selected::Selected,
+};
+
+/// Built-in editor commands
+pub struct EditorCommandsPlugin;
+
+impl Plugin for EditorCommandsPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.editor_command("edit.undo", "Undo", "Edit", |world| {
+            world.send_event(UndoRedo::Undo);
+        });
+        app.editor_command("edit.redo", "Redo", "Edit", |world| {
+            world.send_event(UndoRedo::Redo);
+        });
+        app.editor_command(
+            "edit.delete_selected",
+            "Delete selected",
+            "Edit",
+            delete_selected,
+        );
+        app.editor_command("scene.save", "Save scene", "Scene", save_scene);
+        app.editor_command("game.start", "Start game", "Game", |world| {
+            world.send_event(EditorEvent::StartGame);
+        });
+    }
+}
+
+/// Editor action which can be found in command palette and bound to hotkey
+#[derive(Clone, Debug)]
+pub struct EditorCommand {
+    /// Unique id, for example `scene.save`
+    pub id: &'static str,
+    pub label: String,
+    pub category: String,
+    pub run: fn(&mut World),
+    /// Command runs only while game is running, other commands run only in editor
+    pub playmode: bool,
+}
+
+/// All registered editor commands
+#[derive(Resource, Default, Clone, Debug)]
+pub struct EditorCommands {
+    commands: Vec<EditorCommand>,
+}
+
+impl EditorCommands {
+    pub fn get(&self, id: &str) -> Option<&EditorCommand> {
+        self.commands.iter().find(|command| command.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EditorCommand> {
+        self.commands.iter()
+    }
+
+    /// Add command. Command with the same id is replaced
+    pub fn register(&mut self, command: EditorCommand) {
+        if let Some(existing) = self.commands.iter_mut().find(|c| c.id == command.id) {
+            warn!("Editor command {} is registered twice", command.id);
+            *existing = command;
+        } else {
+            self.commands.push(command);
+        }
+    }
+
+    /// Commands matching fuzzy query, best match first
+    pub fn search(&self, query: &str) -> Vec<&EditorCommand> {
+        let mut found = self
+            .commands
+            .iter()
+            .filter_map(|command| {
+                let text = format!("{}: {}", command.category, command.label);
+                let score = fuzzy_score(query, &text).or_else(|| fuzzy_score(query, command.id))?;
+                Some((score, command))
+            })
+            .collect::<Vec<_>>();
+        found.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| a.category.cmp(&b.category))
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        found.into_iter().map(|(_, command)| command).collect()
+    }
+}
+
+/// Score of `text` if all chars of `query` are found in it in the same order.
+/// Consecutive chars and chars at word start score higher
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut text_chars = text.chars().map(|c| c.to_ascii_lowercase()).enumerate();
+    let mut previous_match = None;
+    let mut previous_char = None;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_ascii_lowercase();
+        loop {
+            let (index, c) = text_chars.next()?;
+            let word_start = !previous_char.is_some_and(char::is_alphanumeric);
+            previous_char = Some(c);
+            if c != query_char {
+                continue;
+            }
+            score += 1;
+            if word_start {
+                score += 3;
+            }
+            if previous_match.is_some_and(|previous| previous + 1 == index) {
+                score += 2;
+            }
+            previous_match = Some(index);
+            break;
+        }
+    }
+    Some(score)
+}
+
+/// Event to run registered command by id
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct RunEditorCommand(pub String);
+
+/// Hotkey of editor command. Every registered command has hotkey without binding,
+/// which can be bound in settings or keymap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize)]
+#[reflect_value(Debug, Hash, Serialize, Deserialize)]
+pub struct CommandHotkey(pub &'static str);
+
+/// Ids of registered commands and ids loaded from keymaps.
+/// Loaded id reuses string of registered command, so it is allocated only once
+static COMMAND_IDS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+fn intern_command_id(id: &str) -> &'static str {
+    let mut ids = COMMAND_IDS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(existing) = ids.iter().find(|existing| **existing == id) {
+        return existing;
+    }
+    // Binding of command which is not registered (yet) is kept for later registration
+    let id: &'static str = Box::leak(id.to_string().into_boxed_str());
+    ids.push(id);
+    id
+}
+
+impl<'de> Deserialize<'de> for CommandHotkey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(Self(intern_command_id(&id)))
+    }
+}
+
+impl Hotkey for CommandHotkey {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+pub trait EditorCommandAppExt {
+    /// Register command for command palette and hotkey bindings
+    fn editor_command(
+        &mut self,
+        id: &'static str,
+        label: &str,
+        category: &str,
+        run: fn(&mut World),
+    ) -> &mut Self;
+
+    /// Register command which runs only while game is running, for example pause
+    fn playmode_command(
+        &mut self,
+        id: &'static str,
+        label: &str,
+        category: &str,
+        run: fn(&mut World),
+    ) -> &mut Self;
+}
+
+impl EditorCommandAppExt for App {
+    fn editor_command(
+        &mut self,
+        id: &'static str,
+        label: &str,
+        category: &str,
+        run: fn(&mut World),
+    ) -> &mut Self {
+        register_command(self, id, label, category, run, false)
+    }
+
+    fn playmode_command(
+        &mut self,
+        id: &'static str,
+        label: &str,
+        category: &str,
+        run: fn(&mut World),
+    ) -> &mut Self {
+        register_command(self, id, label, category, run, true)
+    }
+}
+
+fn register_command<'a>(
+    app: &'a mut App,
+    id: &'static str,
+    label: &str,
+    category: &str,
+    run: fn(&mut World),
+    playmode: bool,
+) -> &'a mut App {
+    {
+        let mut ids = COMMAND_IDS.lock().unwrap_or_else(PoisonError::into_inner);
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if !app.world().contains_resource::<EditorCommands>() {
+        app.init_resource::<EditorCommands>();
+        app.add_event::<RunEditorCommand>();
+        app.add_systems(
+            Update,
+            (
+                run_editor_commands::<false>.in_set(EditorSet::Editor),
+                run_editor_commands::<true>.in_set(EditorSet::Game),
+            ),
+        );
+    }
+    app.world_mut()
+        .resource_mut::<EditorCommands>()
+        .register(EditorCommand {
+            id,
+            label: label.to_string(),
+            category: category.to_string(),
+            run,
+            playmode,
+        });
+    app.editor_hotkey(CommandHotkey(id), Vec::<HotkeyInput>::new())
+}
+
+/// Run commands of editor or of play mode. Both systems read all requested commands
+fn run_editor_commands<const PLAYMODE: bool>(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<RunEditorCommand>>,
+) {
+    let mut ids = world
+        .get_resource::<ButtonInput<CommandHotkey>>()
+        .map(|hotkeys| {
+            hotkeys
+                .get_just_pressed()
+                .map(|hotkey| hotkey.0.to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    ids.extend(
+        reader
+            .read(world.resource::<Events<RunEditorCommand>>())
+            .map(|event| event.0.clone()),
+    );
+
+    for id in ids {
+        let Some(command) = world.resource::<EditorCommands>().get(&id).cloned() else {
+            if !PLAYMODE {
+                warn!("Editor command {} is not registered", id);
+            }
+            continue;
+        };
+        if command.playmode != PLAYMODE {
+            continue;
+        }
+        info!("Run editor command {}", id);
+        (command.run)(world);
+    }
+}
+
+/// Delete selected entities with children as it is done from hierarchy
+fn delete_selected(world: &mut World) {
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<HashSet<_>>();
+    for &entity in selected.iter() {
+        // Selected children are deleted with their selected ancestor
+        let mut ancestor = world.get::<Parent>(entity).map(Parent::get);
+        while let Some(parent) = ancestor {
+            if selected.contains(&parent) {
+                break;
+            }
+            ancestor = world.get::<Parent>(parent).map(Parent::get);
+        }
+        if ancestor.is_some() {
+            continue;
+        }
+        // Name is not available after despawn, so label is stored with change
+        let label = world.get::<Name>(entity).map_or_else(
+            || format!("Delete Entity {}", entity.index()),
+            |name| format!("Delete {}", name),
+        );
+        if let Some(entity_mut) = world.get_entity_mut(entity) {
+            entity_mut.despawn_recursive();
+            world.send_event(NewChange {
+                change: Arc::new(LabeledChange::new(label, RemovedEntity { entity })),
+            });
+        }
+    }
+}
+
+fn save_scene(world: &mut World) {
+    match world.resource::<SaveConfig>().path.clone() {
+        Some(path @ EditorPrefabPath::File(_)) => {
+            world.send_event(EditorEvent::Save(path));
+        }
+        _ => {
+            world.send_event(ToastMessage::new(
+                "Scene has no file yet, save it from menu first",
+                space_shared::toast::ToastKind::Warning,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkeys::HotkeySet;
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts() {
+        assert!(fuzzy_score("sv", "Scene: Save scene").is_some());
+        assert!(fuzzy_score("xyz", "Scene: Save scene").is_none());
+        assert!(fuzzy_score("", "Edit: Undo").is_some());
+        assert!(
+            fuzzy_score("undo", "Edit: Undo").unwrap()
+                > fuzzy_score("undo", "Edit: Unused do").unwrap()
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[test]
+    fn registered_commands_are_searched_and_run() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Counter>()
+            .editor_command("test.increment", "Increment counter", "Test", |world| {
+                world.resource_mut::<Counter>().0 += 1;
+            })
+            .editor_command("test.reset", "Reset counter", "Test", |world| {
+                world.resource_mut::<Counter>().0 = 0;
+            });
+
+        let commands = app.world().resource::<EditorCommands>();
+        let found = commands.search("inc");
+        assert_eq!(found[0].id, "test.increment");
+        assert_eq!(commands.search("reset")[0].id, "test.reset");
+
+        // every command can be bound to hotkey
+        let set = app.world().resource::<HotkeySet<CommandHotkey>>();
+        assert!(set.bindings[&CommandHotkey("test.reset")].is_empty());
+
+        app.world_mut()
+            .send_event(RunEditorCommand("test.increment".to_string()));
+        app.world_mut()
+            .send_event(RunEditorCommand("test.missing".to_string()));
+        app.update();
+        assert_eq!(app.world().resource::<Counter>().0, 1);
+    }
+
+    #[test]
+    fn playmode_commands_run_only_in_game() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Counter>()
+            .configure_sets(Update, EditorSet::Editor.run_if(|| false))
+            .editor_command("test.editor", "Editor", "Test", |world| {
+                world.resource_mut::<Counter>().0 += 1;
+            })
+            .playmode_command("test.game", "Game", "Test", |world| {
+                world.resource_mut::<Counter>().0 += 10;
+            });
+        assert!(app.world().resource::<EditorCommands>().get("test.game").unwrap().playmode);
+
+        app.world_mut()
+            .send_event(RunEditorCommand("test.editor".to_string()));
+        app.world_mut()
+            .send_event(RunEditorCommand("test.game".to_string()));
+        app.update();
+        assert_eq!(app.world().resource::<Counter>().0, 10);
+    }
+
+    #[test]
+    fn delete_selected_records_changes() {
+        let mut app = App::new();
+        app.add_event::<NewChange>();
+        let parent = app.world_mut().spawn((Selected, Name::new("Parent"))).id();
+        let child = app.world_mut().spawn(Selected).set_parent(parent).id();
+        let other = app.world_mut().spawn(Selected).id();
+        let kept = app.world_mut().spawn_empty().id();
+
+        delete_selected(app.world_mut());
+        for entity in [parent, child, other] {
+            assert!(app.world().get_entity(entity).is_none());
+        }
+        assert!(app.world().get_entity(kept).is_some());
+
+        // Child is deleted with its parent, so it has no own change
+        let events = app.world().resource::<Events<NewChange>>();
+        let mut labels = events
+            .get_reader()
+            .read(events)
+            .map(|event| event.change.label(&|_| None))
+            .collect::<Vec<_>>();
+        labels.sort();
+        assert_eq!(
+            labels,
+            vec![
+                format!("Delete Entity {}", other.index()),
+                "Delete Parent".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn loaded_command_hotkey_reuses_registered_id() {
+        let mut app = App::new();
+        app.editor_command("test.loaded", "Loaded", "Test", |_| {});
+        let registered = app
+            .world()
+            .resource::<EditorCommands>()
+            .get("test.loaded")
+            .unwrap()
+            .id;
+
+        let loaded: CommandHotkey = ron::from_str("\"test.loaded\"").unwrap();
+        assert!(std::ptr::eq(loaded.0, registered));
+
+        // Unknown id is allocated once for every load
+        let first: CommandHotkey = ron::from_str("\"test.unknown\"").unwrap();
+        let second: CommandHotkey = ron::from_str("\"test.unknown\"").unwrap();
+        assert!(std::ptr::eq(first.0, second.0));
+    }
+}
@@ -1,24 +1,105 @@
+use std::{
+    f32::consts::FRAC_PI_2,
+    sync::{Arc, Mutex, PoisonError},
+};
+
 use bevy::{
+    animation::AnimationTarget,
     asset::{AssetPath, LoadState},
-    ecs::world::CommandQueue,
+    ecs::{system::EntityCommands, world::CommandQueue},
     gltf::{Gltf, GltfMesh, GltfNode},
     prelude::*,
+    render::mesh::skinning::SkinnedMesh,
     utils::HashMap,
 };
 
-use space_prefab::component::{AssetMaterial, AssetMesh, MaterialPrefab};
-use space_shared::PrefabMarker;
+use space_prefab::component::{
+    AssetMaterial, AssetMesh, GltfAnimationClip, GltfAnimationPrefab, GltfAnimationTargetPrefab,
+    GltfPrefab, MaterialPrefab, MergedAssetMesh, MergedMeshPart, MeshLayout, PlaymodeCamera,
+    PlaymodeLight,
+};
+use space_shared::{EditorSet, LightAreaToggle, PrefabMarker};
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
 
-use super::{BackgroundTask, BackgroundTaskStorage};
+use super::BackgroundTaskStorage;
 
 #[derive(Event)]
 /// Event to handle GLTF path
 pub struct EditorUnpackGltf {
     pub path: String,
+    pub options: GltfImportOptions,
+}
+
+/// How materials of unpacked gltf are stored
+#[derive(Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum GltfMaterialImport {
+    /// Keep [`AssetMaterial`] references to gltf materials
+    #[default]
+    AssetReference,
+    /// Extract materials into editable [`MaterialPrefab`] values
+    Prefab,
+}
+
+/// Up axis of unpacked gltf
+#[derive(Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum GltfUpAxis {
+    #[default]
+    Y,
+    /// Z up, for example files exported from Blender without +Y up conversion
+    Z,
+}
+
+/// Options of gltf unpacking. Last used options are kept as resource
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, Default)]
+pub struct GltfImportOptions {
+    pub materials: GltfMaterialImport,
+    /// Import KHR lights as light prefabs
+    pub import_lights: bool,
+    /// Import cameras as [`PlaymodeCamera`]
+    pub import_cameras: bool,
+    /// Add [`GltfAnimationPrefab`] to animation roots
+    pub import_animations: bool,
+    /// Index of scene to unpack. All scenes are unpacked if not set
+    pub scene: Option<usize>,
+    /// Scale applied to root nodes
+    pub scale: f32,
+    pub up_axis: GltfUpAxis,
+    /// Merge primitives of static mesh nodes into their nearest non static parent.
+    /// Primitives which share material and vertex attributes become one [`MergedAssetMesh`]
+    pub merge_static_meshes: bool,
+}
+
+impl Default for GltfImportOptions {
+    fn default() -> Self {
+        Self {
+            materials: GltfMaterialImport::AssetReference,
+            import_lights: true,
+            import_cameras: true,
+            import_animations: true,
+            scene: None,
+            scale: 1.0,
+            up_axis: GltfUpAxis::Y,
+            merge_static_meshes: false,
+        }
+    }
+}
+
+impl GltfImportOptions {
+    /// Transform applied to root nodes of unpacked scene
+    pub fn root_transform(&self) -> Transform {
+        let rotation = match self.up_axis {
+            GltfUpAxis::Y => Quat::IDENTITY,
+            GltfUpAxis::Z => Quat::from_rotation_x(-FRAC_PI_2),
+        };
+        Transform::from_rotation(rotation).with_scale(Vec3::splat(self.scale))
+    }
 }
 
 #[derive(Event, Clone)]
-struct GltfLoaded(Handle<Gltf>);
+struct GltfLoaded(Handle<Gltf>, GltfImportOptions);
 
 pub struct UnpackGltfPlugin;
 
@@ -27,10 +108,17 @@ impl Plugin for UnpackGltfPlugin {
         app.add_event::<EditorUnpackGltf>();
         app.add_event::<GltfLoaded>();
         app.add_systems(PreUpdate, (unpack_gltf_event, queue_push, unpack_gltf));
+        app.add_systems(Update, fill_gltf_animation_clips.in_set(EditorSet::Editor));
 
         app.init_resource::<GltfSceneQueue>();
 
         app.register_type::<GltfHolder>();
+
+        app.init_resource::<GltfImportOptions>()
+            .register_type::<GltfImportOptions>();
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<GltfImportOptions>()
+            .persistence_layer::<GltfImportOptions>(PersistenceLayer::User);
     }
 }
 
@@ -38,34 +126,56 @@ impl Plugin for UnpackGltfPlugin {
 #[reflect(Component)]
 struct GltfHolder(Handle<Gltf>);
 
+/// Gltf files which are loaded by background tasks and wait to be unpacked
 #[derive(Resource, Default)]
-struct GltfSceneQueue(Vec<Handle<Gltf>>);
+struct GltfSceneQueue(Arc<Mutex<Vec<QueuedGltf>>>);
+
+type QueuedGltf = (Handle<Gltf>, GltfImportOptions);
 
 fn unpack_gltf_event(
     mut events: EventReader<EditorUnpackGltf>,
     assets: Res<AssetServer>,
-    mut queue: ResMut<GltfSceneQueue>,
+    queue: Res<GltfSceneQueue>,
     mut background_tasks: ResMut<BackgroundTaskStorage>,
 ) {
     for event in events.read() {
-        let handle = assets.load(event.path.clone());
-        background_tasks.tasks.push(BackgroundTask::AssetLoading(
-            event.path.clone(),
-            handle.clone().untyped(),
-        ));
-        queue.0.push(handle);
+        let assets = assets.clone();
+        let queue = queue.0.clone();
+        let path = event.path.clone();
+        let options = event.options.clone();
+        // Cancelled task is dropped before its gltf is queued, so it is never unpacked
+        background_tasks.spawn(
+            &format!("Loading {}", event.path),
+            move |context| async move {
+                context.set_progress(0.0, "Loading gltf");
+                let handle = assets
+                    .load_untyped_async(path)
+                    .await
+                    .map_err(|err| err.to_string())?
+                    .try_typed::<Gltf>()
+                    .map_err(|err| err.to_string())?;
+                context.set_progress(1.0, "Unpacking");
+                queue
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((handle, options));
+                Ok(())
+            },
+        );
     }
     events.clear();
 }
 
 // separated from unpack_gltf for reduce arguments count and ordered unpack
 fn queue_push(
-    mut queue: ResMut<GltfSceneQueue>,
+    queue: Res<GltfSceneQueue>,
     mut events: EventWriter<GltfLoaded>,
     assets: Res<AssetServer>,
 ) {
-    if !queue.0.is_empty() && assets.get_load_state(&queue.0[0]) == Some(LoadState::Loaded) {
-        events.send(GltfLoaded(queue.0.remove(0)));
+    let mut queue = queue.0.lock().unwrap_or_else(PoisonError::into_inner);
+    if !queue.is_empty() && assets.get_load_state(&queue[0].0) == Some(LoadState::Loaded) {
+        let (handle, options) = queue.remove(0);
+        events.send(GltfLoaded(handle, options));
     }
 }
 
@@ -73,7 +183,13 @@ struct UnpackContext<'a> {
     material_map: &'a HashMap<Handle<StandardMaterial>, usize>,
     mesh_map: &'a HashMap<Handle<GltfMesh>, usize>,
     gltf_meshs: &'a Assets<GltfMesh>,
+    gltf_nodes: &'a Assets<GltfNode>,
+    meshes: Option<&'a Assets<Mesh>>,
+    materials: Option<&'a Assets<StandardMaterial>>,
+    gltf: &'a Gltf,
     gltf_path: &'a AssetPath<'a>,
+    scene_world: &'a World,
+    options: &'a GltfImportOptions,
 }
 
 fn unpack_gltf(world: &mut World) {
@@ -88,8 +204,9 @@ fn unpack_gltf(world: &mut World) {
     };
 
     let mut command_queue = CommandQueue::default();
-    for gltf in loaded_scenes.iter() {
-        let handle: Handle<Gltf> = gltf.0.clone();
+    for loaded in loaded_scenes.iter() {
+        let handle: Handle<Gltf> = loaded.0.clone();
+        let options = &loaded.1;
         let gltf_path = if let Some(path) = handle.path() {
             path.clone()
         } else {
@@ -99,7 +216,7 @@ fn unpack_gltf(world: &mut World) {
 
         let Some(gltf) = world
             .get_resource::<Assets<Gltf>>()
-            .and_then(|gltfs| gltfs.get(&gltf.0))
+            .and_then(|gltfs| gltfs.get(&loaded.0))
         else {
             world.send_event(space_shared::toast::ToastMessage::new(
                 "Gltf asset not found or empty",
@@ -132,6 +249,17 @@ fn unpack_gltf(world: &mut World) {
             continue;
         };
 
+        let scene_indices = match scene_indices(gltf, options) {
+            Ok(indices) => indices,
+            Err(err) => {
+                world.send_event(space_shared::toast::ToastMessage::new(
+                    &err,
+                    space_shared::toast::ToastKind::Error,
+                ));
+                continue;
+            }
+        };
+
         let mut mesh_map = HashMap::new();
         for idx in 0..gltf.meshes.len() {
             mesh_map.insert(gltf.meshes[idx].clone(), idx);
@@ -143,43 +271,25 @@ fn unpack_gltf(world: &mut World) {
             material_map.insert(gltf.materials[idx].clone(), idx);
         }
 
-        for idx in 0..gltf.scenes.len() {
+        for idx in scene_indices {
             let Some(scene) = scenes.get(&gltf.scenes[idx]) else {
                 continue;
             };
 
-            //find roots nodes
-            let mut roots = vec![];
-            for e in scene.world.iter_entities() {
-                if !e.contains::<Parent>() && e.contains::<Children>() {
-                    let Some(children) = e.get::<Children>() else {
-                        continue;
-                    };
-                    for child in children.iter() {
-                        if let Some(name) = scene.world.entity(*child).get::<Name>() {
-                            info!("Name: {:?}", &name);
-                            if let Some(node_handle) = gltf.named_nodes.get(name.as_str()) {
-                                if let Some(node) = gltf_nodes.get(node_handle) {
-                                    roots.push(node.clone());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            info!("Roots: {:?}", &roots);
-
             let ctx = UnpackContext {
                 material_map: &material_map,
                 mesh_map: &mesh_map,
                 gltf_meshs,
+                gltf_nodes,
+                meshes: world.get_resource::<Assets<Mesh>>(),
+                materials: world.get_resource::<Assets<StandardMaterial>>(),
+                gltf,
                 gltf_path: &gltf_path,
+                scene_world: &scene.world,
+                options,
             };
 
-            for root in roots.iter() {
-                spawn_node(&mut commands, root, gltf, &ctx);
-            }
+            spawn_scene(&mut commands, &ctx);
         }
 
         break;
@@ -188,86 +298,765 @@ fn unpack_gltf(world: &mut World) {
     command_queue.apply(world);
 }
 
+/// Indices of gltf scenes which are unpacked with these options
+fn scene_indices(gltf: &Gltf, options: &GltfImportOptions) -> Result<Vec<usize>, String> {
+    match options.scene {
+        Some(idx) if idx < gltf.scenes.len() => Ok(vec![idx]),
+        Some(idx) => Err(format!("Gltf has no scene with index {}", idx)),
+        None => Ok((0..gltf.scenes.len()).collect()),
+    }
+}
+
+/// Spawn root nodes of gltf scene with their children
+fn spawn_scene(commands: &mut Commands, ctx: &UnpackContext<'_>) {
+    //find roots nodes
+    let mut roots = vec![];
+    for e in ctx.scene_world.iter_entities() {
+        if !e.contains::<Parent>() && e.contains::<Children>() {
+            roots.extend(child_nodes(ctx, e.id()));
+        }
+    }
+
+    info!("Roots: {:?}", &roots);
+
+    let root_transform = ctx.options.root_transform();
+    for (root, scene_entity) in roots.iter() {
+        let id = spawn_node(commands, root, *scene_entity, ctx);
+        commands
+            .entity(id)
+            .insert(root_transform.mul_transform(root.transform));
+    }
+}
+
+fn is_light(world: &World, entity: Entity) -> bool {
+    let entity = world.entity(entity);
+    entity.contains::<PointLight>()
+        || entity.contains::<SpotLight>()
+        || entity.contains::<DirectionalLight>()
+}
+
+/// Gltf nodes which are children of scene entity
+fn child_nodes(ctx: &UnpackContext<'_>, scene_entity: Entity) -> Vec<(GltfNode, Entity)> {
+    let Some(children) = ctx.scene_world.get::<Children>(scene_entity) else {
+        return vec![];
+    };
+    children
+        .iter()
+        .filter(|child| !is_light(ctx.scene_world, **child))
+        .filter_map(|child| {
+            let name = ctx.scene_world.get::<Name>(*child)?;
+            let node = ctx
+                .gltf_nodes
+                .get(ctx.gltf.named_nodes.get(name.as_str())?)?;
+            Some((node.clone(), *child))
+        })
+        .collect()
+}
+
+/// Node without lights, cameras, skins and animations, whose children are static too
+fn is_static(ctx: &UnpackContext<'_>, scene_entity: Entity) -> bool {
+    let entity = ctx.scene_world.entity(scene_entity);
+    if entity.contains::<Projection>()
+        || entity.contains::<AnimationPlayer>()
+        || entity.contains::<AnimationTarget>()
+    {
+        return false;
+    }
+    let has_dynamic_children = entity.get::<Children>().is_some_and(|children| {
+        children.iter().any(|child| {
+            is_light(ctx.scene_world, *child)
+                || ctx.scene_world.get::<SkinnedMesh>(*child).is_some()
+        })
+    });
+    !has_dynamic_children
+        && child_nodes(ctx, scene_entity)
+            .iter()
+            .all(|(_, child)| is_static(ctx, *child))
+}
+
 fn spawn_node(
     commands: &mut Commands,
     node: &GltfNode,
-    _gltf: &Gltf,
+    scene_entity: Entity,
     ctx: &UnpackContext<'_>,
 ) -> Entity {
-    let id = commands
-        .spawn((
-            SpatialBundle {
-                transform: node.transform,
-                ..default()
-            },
-            PrefabMarker,
-        ))
-        .id();
+    let mut entity = commands.spawn((
+        SpatialBundle {
+            transform: node.transform,
+            ..default()
+        },
+        PrefabMarker,
+    ));
+    if let Some(name) = ctx.scene_world.get::<Name>(scene_entity) {
+        entity.insert(name.clone());
+    }
+    let id = entity.id();
 
     if let Some(handle) = &node.mesh {
         if let Some(mesh) = ctx.gltf_meshs.get(handle) {
             if mesh.primitives.len() == 1 {
-                commands.entity(id).insert(AssetMesh {
-                    path: format!(
-                        "{}#Mesh{}/Primitive{}",
-                        ctx.gltf_path.path().display(),
-                        ctx.mesh_map.get(handle).unwrap(),
-                        0
-                    ),
+                insert_primitive(&mut commands.entity(id), ctx, handle, mesh, 0);
+            } else {
+                commands.entity(id).with_children(|parent| {
+                    for idx in 0..mesh.primitives.len() {
+                        let mut primitive = parent.spawn((SpatialBundle::default(), PrefabMarker));
+                        insert_primitive(&mut primitive, ctx, handle, mesh, idx);
+                    }
                 });
+            }
+        }
+    }
 
-                if let Some(material_handle) = &mesh.primitives[0].material {
-                    if let Some(idx) = ctx.material_map.get(material_handle) {
-                        commands.entity(id).insert(AssetMaterial {
-                            path: format!("{}#Material{}", ctx.gltf_path.path().display(), idx),
-                        });
-                    } else {
-                        commands.entity(id).insert(MaterialPrefab::default());
-                    }
+    if ctx.options.import_cameras {
+        if let Some(projection) = ctx.scene_world.get::<Projection>(scene_entity) {
+            commands.entity(id).insert((
+                Camera3d::default(),
+                Camera::default(),
+                projection.clone(),
+                PlaymodeCamera::default(),
+            ));
+        }
+    }
+
+    if ctx.options.import_lights {
+        spawn_lights(commands, id, scene_entity, ctx);
+    }
+
+    if ctx.options.import_animations {
+        if ctx
+            .scene_world
+            .get::<AnimationPlayer>(scene_entity)
+            .is_some()
+        {
+            let clips = gltf_animation_clips(ctx.gltf);
+            commands.entity(id).insert(GltfAnimationPrefab {
+                path: ctx.gltf_path.path().display().to_string(),
+                autoplay: clips
+                    .first()
+                    .map(|clip| clip.name.clone())
+                    .unwrap_or_default(),
+                clips,
+                ..default()
+            });
+        }
+        if let Some(target) = ctx.scene_world.get::<AnimationTarget>(scene_entity) {
+            commands.entity(id).insert(GltfAnimationTargetPrefab {
+                names: target_names(ctx.scene_world, scene_entity, target.player),
+            });
+        }
+    }
+
+    let mut merged = vec![];
+    for (child, child_entity) in child_nodes(ctx, scene_entity) {
+        if ctx.options.merge_static_meshes && is_static(ctx, child_entity) {
+            collect_static_primitives(&mut merged, &child, child_entity, child.transform, ctx);
+        } else {
+            let child_id = spawn_node(commands, &child, child_entity, ctx);
+            commands.entity(id).add_child(child_id);
+        }
+    }
+    spawn_merged_primitives(commands, id, merged, ctx);
+
+    id
+}
+
+/// Static primitives with the same material and mesh layout, which are merged into one mesh
+struct MergedPrimitives {
+    name: Name,
+    material: Option<Handle<StandardMaterial>>,
+    /// Primitives without layout (not loaded or strip meshes) are not merged
+    layout: Option<MeshLayout>,
+    parts: Vec<MergedMeshPart>,
+}
+
+/// Collect primitives of static node and its children with transforms relative to merge parent
+fn collect_static_primitives(
+    merged: &mut Vec<MergedPrimitives>,
+    node: &GltfNode,
+    scene_entity: Entity,
+    transform: Transform,
+    ctx: &UnpackContext<'_>,
+) {
+    if let Some(handle) = &node.mesh {
+        if let Some(mesh) = ctx.gltf_meshs.get(handle) {
+            for (idx, primitive) in mesh.primitives.iter().enumerate() {
+                let layout = ctx
+                    .meshes
+                    .and_then(|meshes| meshes.get(&primitive.mesh))
+                    .and_then(MeshLayout::of);
+                let part = MergedMeshPart {
+                    path: primitive_path(ctx, handle, idx),
+                    transform,
+                };
+                let group = merged.iter_mut().find(|group| {
+                    layout.is_some()
+                        && group.layout == layout
+                        && group.material == primitive.material
+                });
+                if let Some(group) = group {
+                    group.parts.push(part);
                 } else {
-                    commands.entity(id).insert(MaterialPrefab::default());
+                    merged.push(MergedPrimitives {
+                        name: ctx
+                            .scene_world
+                            .get::<Name>(scene_entity)
+                            .cloned()
+                            .unwrap_or_else(|| Name::new("Mesh")),
+                        material: primitive.material.clone(),
+                        layout,
+                        parts: vec![part],
+                    });
                 }
+            }
+        }
+    }
+
+    for (child, child_entity) in child_nodes(ctx, scene_entity) {
+        let child_transform = transform.mul_transform(child.transform);
+        collect_static_primitives(merged, &child, child_entity, child_transform, ctx);
+    }
+}
+
+/// Spawn merged primitives under `parent`. Single primitive keeps its mesh asset
+fn spawn_merged_primitives(
+    commands: &mut Commands,
+    parent: Entity,
+    merged: Vec<MergedPrimitives>,
+    ctx: &UnpackContext<'_>,
+) {
+    if merged.is_empty() {
+        return;
+    }
+    commands.entity(parent).with_children(|parent| {
+        for mut group in merged {
+            let mut entity = parent.spawn((SpatialBundle::default(), group.name, PrefabMarker));
+            if group.parts.len() == 1 {
+                let part = group.parts.remove(0);
+                entity.insert((part.transform, AssetMesh { path: part.path }));
             } else {
-                commands.entity(id).with_children(|parent| {
-                    for idx in 0..mesh.primitives.len() {
-                        let mut id = parent.spawn((
-                            SpatialBundle::default(),
-                            AssetMesh {
-                                path: format!(
-                                    "{}#Mesh{}/Primitive{}",
-                                    ctx.gltf_path.path().display(),
-                                    ctx.mesh_map.get(handle).unwrap(),
-                                    idx
-                                ),
+                entity.insert(MergedAssetMesh { parts: group.parts });
+            }
+            insert_material(&mut entity, ctx, group.material.as_ref());
+        }
+    });
+}
+
+fn spawn_lights(
+    commands: &mut Commands,
+    parent: Entity,
+    scene_entity: Entity,
+    ctx: &UnpackContext<'_>,
+) {
+    let Some(children) = ctx.scene_world.get::<Children>(scene_entity) else {
+        return;
+    };
+    for light in children.iter() {
+        if !is_light(ctx.scene_world, *light) {
+            continue;
+        }
+        let light = ctx.scene_world.entity(*light);
+        let mut entity = commands.spawn((
+            SpatialBundle {
+                transform: light.get::<Transform>().copied().unwrap_or_default(),
+                ..default()
+            },
+            light
+                .get::<Name>()
+                .cloned()
+                .unwrap_or_else(|| Name::new("Light")),
+            LightAreaToggle::default(),
+            PlaymodeLight::default(),
+            PrefabMarker,
+        ));
+        if let Some(point) = light.get::<PointLight>() {
+            entity.insert(*point);
+        } else if let Some(spot) = light.get::<SpotLight>() {
+            entity.insert(*spot);
+        } else if let Some(directional) = light.get::<DirectionalLight>() {
+            entity.insert(directional.clone());
+        }
+        let id = entity.id();
+        commands.entity(parent).add_child(id);
+    }
+}
+
+/// Names of nodes from animation root to target, which identify target in clips
+fn target_names(world: &World, target: Entity, root: Entity) -> Vec<String> {
+    let mut names = vec![];
+    let mut current = Some(target);
+    while let Some(entity) = current {
+        if let Some(name) = world.get::<Name>(entity) {
+            names.push(name.to_string());
+        }
+        if entity == root {
+            break;
+        }
+        current = world.get::<Parent>(entity).map(Parent::get);
+    }
+    names.reverse();
+    names
+}
+
+/// Clips of all animations in gltf
+pub fn gltf_animation_clips(gltf: &Gltf) -> Vec<GltfAnimationClip> {
+    gltf.animations
+        .iter()
+        .enumerate()
+        .map(|(idx, handle)| {
+            let label = handle
+                .path()
+                .and_then(|path| path.label())
+                .map_or_else(|| format!("Animation{}", idx), ToString::to_string);
+            let name = gltf
+                .named_animations
+                .iter()
+                .find(|(_, named)| *named == handle)
+                .map_or_else(|| label.clone(), |(name, _)| name.to_string());
+            GltfAnimationClip { name, label }
+        })
+        .collect()
+}
+
+/// Fill empty clips of [`GltfAnimationPrefab`] from its gltf file
+fn fill_gltf_animation_clips(
+    mut prefabs: Query<(&mut GltfAnimationPrefab, Option<&GltfPrefab>)>,
+    mut handles: Local<HashMap<String, Handle<Gltf>>>,
+    assets: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+) {
+    for (mut prefab, gltf_prefab) in prefabs.iter_mut() {
+        if !prefab.clips.is_empty() {
+            continue;
+        }
+        let path = if prefab.path.is_empty() {
+            gltf_prefab
+                .map(|gltf| gltf.path.clone())
+                .unwrap_or_default()
+        } else {
+            prefab.path.clone()
+        };
+        if path.is_empty() {
+            continue;
+        }
+        let handle = handles
+            .entry(path.clone())
+            .or_insert_with(|| assets.load(path));
+        let Some(gltf) = gltfs.get(&*handle) else {
+            continue;
+        };
+        let clips = gltf_animation_clips(gltf);
+        if clips.is_empty() {
+            continue;
+        }
+        if prefab.autoplay.is_empty() {
+            prefab.autoplay.clone_from(&clips[0].name);
+        }
+        prefab.clips = clips;
+    }
+}
+
+fn insert_primitive(
+    entity: &mut EntityCommands,
+    ctx: &UnpackContext<'_>,
+    handle: &Handle<GltfMesh>,
+    mesh: &GltfMesh,
+    idx: usize,
+) {
+    entity.insert(AssetMesh {
+        path: primitive_path(ctx, handle, idx),
+    });
+    insert_material(entity, ctx, mesh.primitives[idx].material.as_ref());
+}
+
+/// Asset path of mesh primitive
+fn primitive_path(ctx: &UnpackContext<'_>, handle: &Handle<GltfMesh>, idx: usize) -> String {
+    format!(
+        "{}#Mesh{}/Primitive{}",
+        ctx.gltf_path.path().display(),
+        ctx.mesh_map.get(handle).unwrap(),
+        idx
+    )
+}
+
+fn insert_material(
+    entity: &mut EntityCommands,
+    ctx: &UnpackContext<'_>,
+    material: Option<&Handle<StandardMaterial>>,
+) {
+    let Some(material_handle) = material else {
+        entity.insert(MaterialPrefab::default());
+        return;
+    };
+    match ctx.options.materials {
+        GltfMaterialImport::AssetReference => {
+            if let Some(idx) = ctx.material_map.get(material_handle) {
+                entity.insert(AssetMaterial {
+                    path: format!("{}#Material{}", ctx.gltf_path.path().display(), idx),
+                });
+            } else {
+                entity.insert(MaterialPrefab::default());
+            }
+        }
+        GltfMaterialImport::Prefab => {
+            let material = ctx
+                .materials
+                .and_then(|materials| materials.get(material_handle))
+                .map(MaterialPrefab::from_material)
+                .unwrap_or_default();
+            entity.insert(material);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        gltf::{GltfAssetLabel, GltfPrimitive},
+        render::{
+            mesh::{Indices, PrimitiveTopology},
+            render_asset::RenderAssetUsages,
+        },
+    };
+
+    use super::*;
+    use crate::task_storage::{
+        BackgroundTaskFinished, BackgroundTaskResult, BackgroundTaskStoragePlugin,
+    };
+
+    /// Gltf with "Root" node, which has two static "Box" nodes sharing material,
+    /// "Camera" node and point light
+    struct TestGltf {
+        gltf: Gltf,
+        gltf_path: AssetPath<'static>,
+        gltf_nodes: Assets<GltfNode>,
+        gltf_meshs: Assets<GltfMesh>,
+        meshes: Assets<Mesh>,
+        materials: Assets<StandardMaterial>,
+        scene_world: World,
+    }
+
+    impl TestGltf {
+        fn new() -> Self {
+            let mut meshes = Assets::<Mesh>::default();
+            let mut materials = Assets::<StandardMaterial>::default();
+            let mut gltf_meshs = Assets::<GltfMesh>::default();
+            let mut gltf_nodes = Assets::<GltfNode>::default();
+
+            let material = materials.add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.0, 0.0),
+                ..default()
+            });
+            let gltf_mesh_handles = (0..2)
+                .map(|idx| {
+                    let mesh = Mesh::new(
+                        PrimitiveTopology::TriangleList,
+                        RenderAssetUsages::default(),
+                    )
+                    .with_inserted_attribute(
+                        Mesh::ATTRIBUTE_POSITION,
+                        vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+                    )
+                    .with_inserted_indices(Indices::U16(vec![0, 1, 2]));
+                    gltf_meshs.add(GltfMesh {
+                        index: idx,
+                        name: format!("Mesh{}", idx),
+                        asset_label: GltfAssetLabel::Mesh(idx),
+                        primitives: vec![GltfPrimitive {
+                            index: 0,
+                            name: "Primitive0".to_string(),
+                            asset_label: GltfAssetLabel::Primitive {
+                                mesh: idx,
+                                primitive: 0,
                             },
-                            PrefabMarker,
-                        ));
-
-                        if let Some(material_handle) = &mesh.primitives[idx].material {
-                            if let Some(idx) = ctx.material_map.get(material_handle) {
-                                id.insert(AssetMaterial {
-                                    path: format!(
-                                        "{}#Material{}",
-                                        ctx.gltf_path.path().display(),
-                                        idx
-                                    ),
-                                });
-                            } else {
-                                id.insert(MaterialPrefab::default());
-                            }
-                        } else {
-                            id.insert(MaterialPrefab::default());
-                        }
-                    }
+                            mesh: meshes.add(mesh),
+                            material: Some(material.clone()),
+                            extras: None,
+                            material_extras: None,
+                        }],
+                        extras: None,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let nodes = [
+                ("Root", None, Transform::IDENTITY),
+                (
+                    "Box",
+                    Some(gltf_mesh_handles[0].clone()),
+                    Transform::from_xyz(1.0, 0.0, 0.0),
+                ),
+                (
+                    "Box2",
+                    Some(gltf_mesh_handles[1].clone()),
+                    Transform::from_xyz(2.0, 0.0, 0.0),
+                ),
+                ("Camera", None, Transform::IDENTITY),
+            ];
+            let mut named_nodes = HashMap::new();
+            let mut node_handles = vec![];
+            for (idx, (name, mesh, transform)) in nodes.iter().enumerate() {
+                let handle = gltf_nodes.add(GltfNode {
+                    index: idx,
+                    name: name.to_string(),
+                    asset_label: GltfAssetLabel::Node(idx),
+                    children: vec![],
+                    mesh: mesh.clone(),
+                    transform: *transform,
+                    extras: None,
                 });
+                named_nodes.insert(Box::from(*name), handle.clone());
+                node_handles.push(handle);
+            }
+
+            let mut scene_world = World::new();
+            scene_world
+                .spawn(Transform::IDENTITY)
+                .with_children(|scene| {
+                    scene
+                        .spawn((Transform::IDENTITY, Name::new("Root")))
+                        .with_children(|root| {
+                            root.spawn((nodes[1].2, Name::new("Box")));
+                            root.spawn((nodes[2].2, Name::new("Box2")));
+                            root.spawn((
+                                Transform::IDENTITY,
+                                Name::new("Camera"),
+                                Projection::default(),
+                            ));
+                            root.spawn((
+                                Transform::from_xyz(0.0, 3.0, 0.0),
+                                Name::new("Lamp"),
+                                PointLight::default(),
+                            ));
+                        });
+                });
+
+            Self {
+                gltf: Gltf {
+                    scenes: vec![Handle::default(), Handle::default()],
+                    named_scenes: HashMap::new(),
+                    meshes: gltf_mesh_handles,
+                    named_meshes: HashMap::new(),
+                    materials: vec![material],
+                    named_materials: HashMap::new(),
+                    nodes: node_handles,
+                    named_nodes,
+                    default_scene: None,
+                    animations: vec![],
+                    named_animations: HashMap::new(),
+                    source: None,
+                },
+                gltf_path: AssetPath::from("models/test.gltf"),
+                gltf_nodes,
+                gltf_meshs,
+                meshes,
+                materials,
+                scene_world,
+            }
+        }
+
+        /// Unpack scene into new world
+        fn unpack(&self, options: GltfImportOptions) -> World {
+            let mesh_map = self
+                .gltf
+                .meshes
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(idx, handle)| (handle, idx))
+                .collect();
+            let material_map = self
+                .gltf
+                .materials
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(idx, handle)| (handle, idx))
+                .collect();
+            let ctx = UnpackContext {
+                material_map: &material_map,
+                mesh_map: &mesh_map,
+                gltf_meshs: &self.gltf_meshs,
+                gltf_nodes: &self.gltf_nodes,
+                meshes: Some(&self.meshes),
+                materials: Some(&self.materials),
+                gltf: &self.gltf,
+                gltf_path: &self.gltf_path,
+                scene_world: &self.scene_world,
+                options: &options,
+            };
+
+            let mut world = World::new();
+            let mut queue = CommandQueue::default();
+            spawn_scene(&mut Commands::new(&mut queue, &world), &ctx);
+            queue.apply(&mut world);
+            world
+        }
+    }
+
+    fn find(world: &mut World, name: &str) -> Option<Entity> {
+        world
+            .query::<(Entity, &Name)>()
+            .iter(world)
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+    }
+
+    #[test]
+    fn failed_gltf_loading_is_not_unpacked() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Gltf>()
+            .add_event::<space_shared::toast::ToastMessage>()
+            .add_plugins(BackgroundTaskStoragePlugin)
+            .add_event::<EditorUnpackGltf>()
+            .add_event::<GltfLoaded>()
+            .init_resource::<GltfSceneQueue>()
+            .add_systems(PreUpdate, (unpack_gltf_event, queue_push));
+
+        app.world_mut().send_event(EditorUnpackGltf {
+            path: "models/missing.gltf".to_string(),
+            options: GltfImportOptions::default(),
+        });
+        let mut reader = app
+            .world()
+            .resource::<Events<BackgroundTaskFinished>>()
+            .get_reader();
+        let mut finished = vec![];
+        for _ in 0..100 {
+            app.update();
+            let events = app.world().resource::<Events<BackgroundTaskFinished>>();
+            finished.extend(reader.read(events).cloned());
+            if !finished.is_empty() {
+                break;
             }
+            std::thread::sleep(std::time::Duration::from_millis(5));
         }
+
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].label, "Loading models/missing.gltf");
+        assert!(matches!(
+            finished[0].result,
+            BackgroundTaskResult::Failed(_)
+        ));
+        assert!(app
+            .world()
+            .resource::<GltfSceneQueue>()
+            .0
+            .lock()
+            .unwrap()
+            .is_empty());
     }
 
-    for child in &node.children {
-        let child_id = spawn_node(commands, child, _gltf, ctx);
-        commands.entity(id).add_child(child_id);
+    #[test]
+    fn selects_scene_by_index() {
+        let test = TestGltf::new();
+        let options = |scene| GltfImportOptions { scene, ..default() };
+        assert_eq!(scene_indices(&test.gltf, &options(None)), Ok(vec![0, 1]));
+        assert_eq!(scene_indices(&test.gltf, &options(Some(1))), Ok(vec![1]));
+        assert!(scene_indices(&test.gltf, &options(Some(2))).is_err());
     }
 
-    id
+    #[test]
+    fn imports_materials_as_references_or_prefabs() {
+        let test = TestGltf::new();
+
+        let mut world = test.unpack(GltfImportOptions::default());
+        let entity = find(&mut world, "Box").unwrap();
+        assert_eq!(
+            world.get::<AssetMesh>(entity).unwrap().path,
+            "models/test.gltf#Mesh0/Primitive0"
+        );
+        assert_eq!(
+            world.get::<AssetMaterial>(entity).unwrap().path,
+            "models/test.gltf#Material0"
+        );
+        assert!(world.get::<MaterialPrefab>(entity).is_none());
+
+        let mut world = test.unpack(GltfImportOptions {
+            materials: GltfMaterialImport::Prefab,
+            ..default()
+        });
+        let entity = find(&mut world, "Box").unwrap();
+        assert!(world.get::<AssetMaterial>(entity).is_none());
+        assert_eq!(
+            world.get::<MaterialPrefab>(entity).unwrap().base_color,
+            Color::srgb(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn imports_lights_and_cameras_when_enabled() {
+        let test = TestGltf::new();
+
+        let mut world = test.unpack(GltfImportOptions::default());
+        let root = find(&mut world, "Root").unwrap();
+        let lamp = find(&mut world, "Lamp").unwrap();
+        assert!(world.get::<PointLight>(lamp).is_some());
+        assert!(world.get::<PlaymodeLight>(lamp).is_some());
+        assert_eq!(world.get::<Parent>(lamp).map(Parent::get), Some(root));
+        let camera = find(&mut world, "Camera").unwrap();
+        assert!(world.get::<Camera3d>(camera).is_some());
+        assert!(world.get::<PlaymodeCamera>(camera).is_some());
+
+        let mut world = test.unpack(GltfImportOptions {
+            import_lights: false,
+            import_cameras: false,
+            ..default()
+        });
+        assert!(find(&mut world, "Lamp").is_none());
+        let camera = find(&mut world, "Camera").unwrap();
+        assert!(world.get::<Camera3d>(camera).is_none());
+        assert!(world.get::<PlaymodeCamera>(camera).is_none());
+    }
+
+    #[test]
+    fn merges_static_meshes_with_same_material() {
+        let test = TestGltf::new();
+
+        let mut world = test.unpack(GltfImportOptions::default());
+        assert!(find(&mut world, "Box2").is_some());
+
+        let mut world = test.unpack(GltfImportOptions {
+            merge_static_meshes: true,
+            ..default()
+        });
+        let root = find(&mut world, "Root").unwrap();
+        assert!(find(&mut world, "Box2").is_none());
+        let merged = find(&mut world, "Box").unwrap();
+        assert_eq!(world.get::<Parent>(merged).map(Parent::get), Some(root));
+        assert!(world.get::<AssetMesh>(merged).is_none());
+        assert_eq!(
+            world.get::<AssetMaterial>(merged).unwrap().path,
+            "models/test.gltf#Material0"
+        );
+        assert_eq!(
+            world.get::<MergedAssetMesh>(merged).unwrap().parts,
+            vec![
+                MergedMeshPart {
+                    path: "models/test.gltf#Mesh0/Primitive0".to_string(),
+                    transform: Transform::from_xyz(1.0, 0.0, 0.0),
+                },
+                MergedMeshPart {
+                    path: "models/test.gltf#Mesh1/Primitive0".to_string(),
+                    transform: Transform::from_xyz(2.0, 0.0, 0.0),
+                },
+            ]
+        );
+        // Camera is not static and keeps its node
+        assert!(find(&mut world, "Camera").is_some());
+    }
+
+    #[test]
+    fn root_transform_converts_z_up() {
+        let options = GltfImportOptions {
+            up_axis: GltfUpAxis::Z,
+            scale: 2.0,
+            ..default()
+        };
+        let up = options.root_transform().transform_point(Vec3::Z);
+        assert!(up.abs_diff_eq(Vec3::Y * 2.0, 1e-5));
+
+        let identity = GltfImportOptions::default().root_transform();
+        assert_eq!(identity, Transform::IDENTITY);
+    }
 }
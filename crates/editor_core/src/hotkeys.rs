@@ -1,14 +1,20 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 
+use std::fmt::Display;
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::input::{mouse::MouseWheel, InputSystem};
 use bevy::prelude::*;
 use bevy::reflect::GetTypeRegistration;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 
 #[cfg(feature = "persistence_editor")]
 use space_persistence::AppPersistenceExt;
 use space_persistence::PersistenceRegistry;
 
+use crate::keymap::Keymap;
+
 pub trait Hotkey:
     Send
     + Sync
@@ -25,10 +31,186 @@ pub trait Hotkey:
     fn name(&self) -> String;
 }
 
+/// Input device button which can be bound to hotkey
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum HotkeyInput {
+    Keyboard(KeyCode),
+    Mouse(MouseButton),
+    /// Mouse wheel is scrolled in direction in current frame
+    Wheel(WheelDirection),
+    /// Button of any connected gamepad
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl From<KeyCode> for HotkeyInput {
+    fn from(value: KeyCode) -> Self {
+        Self::Keyboard(value)
+    }
+}
+
+impl From<MouseButton> for HotkeyInput {
+    fn from(value: MouseButton) -> Self {
+        Self::Mouse(value)
+    }
+}
+
+impl From<WheelDirection> for HotkeyInput {
+    fn from(value: WheelDirection) -> Self {
+        Self::Wheel(value)
+    }
+}
+
+impl From<GamepadButtonType> for HotkeyInput {
+    fn from(value: GamepadButtonType) -> Self {
+        Self::Gamepad(value)
+    }
+}
+
+impl Display for HotkeyInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keyboard(key) => {
+                let name = format!("{:?}", key);
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                write!(f, "{}", name)
+            }
+            Self::Mouse(button) => write!(f, "Mouse {:?}", button),
+            Self::Wheel(direction) => write!(f, "Wheel {:?}", direction),
+            Self::Gamepad(button) => write!(f, "Gamepad {:?}", button),
+        }
+    }
+}
+
+/// Hotkey bindings saved before version 1 were lists of key codes
+const HOTKEY_SET_VERSION: u32 = 1;
+
+/// Wrap key codes of bindings saved before version 1 into [`HotkeyInput::Keyboard`]
+pub fn migrate_keyboard_bindings(version: u32, data: String) -> Result<String, String> {
+    if version >= HOTKEY_SET_VERSION {
+        return Ok(data);
+    }
+    let mut result = String::with_capacity(data.len());
+    let mut token = String::new();
+    let mut list_depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let flush = |result: &mut String, token: &mut String| {
+        if !token.is_empty() {
+            result.push_str(&format!("Keyboard({})", token));
+            token.clear();
+        }
+    };
+    for c in data.chars() {
+        if in_string {
+            result.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                result.push(c);
+            }
+            '[' => {
+                list_depth += 1;
+                result.push(c);
+            }
+            ',' | ']' if list_depth > 0 => {
+                flush(&mut result, &mut token);
+                if c == ']' {
+                    list_depth -= 1;
+                }
+                result.push(c);
+            }
+            c if list_depth > 0 && !c.is_whitespace() => token.push(c),
+            c => result.push(c),
+        }
+    }
+    if list_depth != 0 || in_string {
+        return Err("hotkey bindings are not closed".to_string());
+    }
+    Ok(result)
+}
+
+/// Part of editor which receives hotkey
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum HotkeyContext {
+    /// Hotkey works in every context except [`HotkeyContext::TextEditing`]
+    #[default]
+    Any,
+    /// Hotkey works in every context, also while text is typed
+    Always,
+    /// Game view tab is focused
+    Viewport,
+    /// Hierarchy tab is focused
+    Hierarchy,
+    /// Text field has keyboard focus
+    TextEditing,
+    /// Game is running
+    PlayMode,
+}
+
+impl HotkeyContext {
+    /// Hotkey with this context works while `active` context is active
+    pub fn is_active(self, active: Self) -> bool {
+        match self {
+            Self::Always => true,
+            // Typed text must not trigger hotkeys, for example undo in inspector text field
+            Self::Any => active != Self::TextEditing,
+            _ => self == active,
+        }
+    }
+
+    /// Hotkeys of both contexts can be triggered at the same time
+    pub fn overlaps(self, other: Self) -> bool {
+        self == other || self.is_active(other) || other.is_active(self)
+    }
+}
+
+/// Context which currently receives hotkeys. Updated by editor UI from egui focus and [`space_shared::EditorState`]
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveHotkeyContext(pub HotkeyContext);
+
 #[derive(Resource, Reflect)]
 pub struct HotkeySet<T: Hotkey> {
-    pub bindings: HashMap<T, Vec<KeyCode>>,
+    pub bindings: HashMap<T, Vec<HotkeyInput>>,
     pub name: String,
+    /// Context of hotkeys. Hotkeys without context work in [`HotkeyContext::Any`]
+    #[reflect(ignore)]
+    pub contexts: HashMap<T, HotkeyContext>,
+    /// Bindings given on hotkey registration
+    #[reflect(ignore)]
+    pub defaults: HashMap<T, Vec<HotkeyInput>>,
+}
+
+impl<T: Hotkey> HotkeySet<T> {
+    pub fn context(&self, key: &T) -> HotkeyContext {
+        self.contexts.get(key).copied().unwrap_or_default()
+    }
+
+    fn key_by_name(&self, hotkey_name: &str) -> Option<T> {
+        self.bindings
+            .keys()
+            .find(|key| key.name() == hotkey_name)
+            .copied()
+    }
 }
 
 impl<T> Default for HotkeySet<T>
@@ -39,6 +221,8 @@ where
         Self {
             bindings: HashMap::new(),
             name: T::short_type_path().to_string(),
+            contexts: HashMap::new(),
+            defaults: HashMap::new(),
         }
     }
 }
@@ -47,7 +231,9 @@ where
 pub struct AllHotkeys {
     pub mappers: Vec<
         Box<
-            dyn Fn(&mut World, &mut dyn FnMut(&mut World, String, &mut Vec<KeyCode>)) + Send + Sync,
+            dyn Fn(&mut World, &mut dyn FnMut(&mut World, String, &mut Vec<HotkeyInput>))
+                + Send
+                + Sync,
         >,
     >,
     pub global_mapper: Vec<
@@ -61,7 +247,7 @@ impl AllHotkeys {
     pub fn map(
         &self,
         world: &mut World,
-        map_fun: &mut dyn FnMut(&mut World, String, &mut Vec<KeyCode>),
+        map_fun: &mut dyn FnMut(&mut World, String, &mut Vec<HotkeyInput>),
     ) {
         for mapper in &self.mappers {
             mapper(world, map_fun);
@@ -77,15 +263,314 @@ impl AllHotkeys {
             mapper(world, map_fun);
         }
     }
+
+    /// Chords of all not empty bindings in all registered sets
+    pub fn chords(&self, world: &mut World) -> Vec<HotkeyChordBinding> {
+        let mut chords = vec![];
+        self.global_map(world, &mut |_, set| {
+            let set_name = set.get_name().to_string();
+            let contexts = set.get_flat_contexts();
+            for (name, binding) in set.get_flat_bindings() {
+                if !binding.is_empty() {
+                    chords.push(HotkeyChordBinding {
+                        set: set_name.clone(),
+                        context: contexts.get(&name).copied().unwrap_or_default(),
+                        name,
+                        chord: HotkeyChord::new(binding),
+                    });
+                }
+            }
+        });
+        chords
+    }
+
+    /// Chords which are bound to more than one hotkey with overlapping contexts in all registered sets
+    pub fn conflicts(&self, world: &mut World) -> Vec<HotkeyConflict> {
+        let chords = self.chords(world);
+        let mut conflicts: Vec<HotkeyConflict> = vec![];
+        for (index, binding) in chords.iter().enumerate() {
+            let conflicting = chords.iter().enumerate().any(|(other_index, other)| {
+                other_index != index
+                    && other.chord == binding.chord
+                    && other.context.overlaps(binding.context)
+            });
+            if !conflicting {
+                continue;
+            }
+            let hotkey = binding.hotkey();
+            if let Some(conflict) = conflicts.iter_mut().find(|c| c.chord == binding.chord) {
+                conflict.hotkeys.push(hotkey);
+            } else {
+                conflicts.push(HotkeyConflict {
+                    chord: binding.chord.clone(),
+                    hotkeys: vec![hotkey],
+                });
+            }
+        }
+        conflicts
+    }
+}
+
+/// Resolved binding of hotkey from some [`HotkeySet`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HotkeyChordBinding {
+    pub set: String,
+    pub name: String,
+    pub chord: HotkeyChord,
+    pub context: HotkeyContext,
+}
+
+impl HotkeyChordBinding {
+    /// Hotkey in `set: hotkey` format
+    pub fn hotkey(&self) -> String {
+        format!("{}: {}", self.set, self.name)
+    }
+}
+
+/// Several hotkeys bound to the same chord
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HotkeyConflict {
+    pub chord: HotkeyChord,
+    /// Hotkeys in `set: hotkey` format
+    pub hotkeys: Vec<String>,
+}
+
+/// Modifier keys of chord. Left and right keys are the same modifier
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HotkeyModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl HotkeyModifiers {
+    /// Add modifier of input. Returns false if input is not a modifier key
+    const fn insert(&mut self, input: HotkeyInput) -> bool {
+        let HotkeyInput::Keyboard(code) = input else {
+            return false;
+        };
+        match code {
+            KeyCode::ControlLeft | KeyCode::ControlRight => self.ctrl = true,
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => self.shift = true,
+            KeyCode::AltLeft | KeyCode::AltRight => self.alt = true,
+            KeyCode::SuperLeft | KeyCode::SuperRight => self.super_key = true,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Every modifier of `self` is in `other`
+    const fn is_subset(&self, other: &Self) -> bool {
+        (!self.ctrl || other.ctrl)
+            && (!self.shift || other.shift)
+            && (!self.alt || other.alt)
+            && (!self.super_key || other.super_key)
+    }
+}
+
+/// Binding split into modifiers and main keys. Chord with main keys is held only
+/// when exactly its modifiers are held, so `Ctrl + S` does not trigger `S`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HotkeyChord {
+    pub modifiers: HotkeyModifiers,
+    /// Sorted not modifier inputs
+    pub keys: Vec<HotkeyInput>,
+}
+
+impl HotkeyChord {
+    pub fn new<I: Into<HotkeyInput> + Copy>(binding: &[I]) -> Self {
+        let mut chord = Self::default();
+        for input in binding {
+            let input = (*input).into();
+            if !chord.modifiers.insert(input) {
+                chord.keys.push(input);
+            }
+        }
+        chord
+            .keys
+            .sort_by_cached_key(|input| format!("{:?}", input));
+        chord.keys.dedup();
+        chord
+    }
+
+    /// Chord like `Shift` which is held together with other hotkeys
+    pub const fn is_modifier_only(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check chord against set of pressed inputs
+    pub fn is_held_by(&self, pressed: &HashSet<HotkeyInput>) -> bool {
+        let mut modifiers = HotkeyModifiers::default();
+        for code in pressed {
+            modifiers.insert(*code);
+        }
+        if self.is_modifier_only() {
+            self.modifiers.is_subset(&modifiers)
+        } else {
+            self.modifiers == modifiers && self.keys.iter().all(|key| pressed.contains(key))
+        }
+    }
+
+    /// Both chords are held and `self` has all keys of `other` and more
+    fn is_more_specific(&self, other: &Self) -> bool {
+        self.modifiers == other.modifiers
+            && self.keys.len() > other.keys.len()
+            && other.keys.iter().all(|key| self.keys.contains(key))
+    }
+}
+
+impl Display for HotkeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        for (held, name) in [
+            (self.modifiers.ctrl, "Ctrl"),
+            (self.modifiers.shift, "Shift"),
+            (self.modifiers.alt, "Alt"),
+            (self.modifiers.super_key, "Super"),
+        ] {
+            if held {
+                parts.push(name.to_string());
+            }
+        }
+        for key in &self.keys {
+            parts.push(key.to_string());
+        }
+        write!(f, "{}", parts.join(" + "))
+    }
+}
+
+/// Chords resolved for current frame from bindings of all hotkey sets
+#[derive(Resource, Default)]
+pub struct HotkeyChordState {
+    /// Held chords which are not shadowed by more specific held chord
+    held: HashSet<HotkeyChord>,
+    /// Held chords which main key was pressed after modifiers in this frame
+    started: HashSet<HotkeyChord>,
+    /// Inputs pressed in previous frame
+    previous: HashSet<HotkeyInput>,
+}
+
+impl HotkeyChordState {
+    pub fn is_held(&self, chord: &HotkeyChord) -> bool {
+        self.held.contains(chord)
+    }
+
+    pub fn just_started(&self, chord: &HotkeyChord) -> bool {
+        self.started.contains(chord)
+    }
+}
+
+/// Inputs of all devices which are pressed in current frame
+fn pressed_inputs(
+    world: &World,
+    wheel_reader: &mut ManualEventReader<MouseWheel>,
+) -> Option<HashSet<HotkeyInput>> {
+    let mut pressed = world
+        .get_resource::<ButtonInput<KeyCode>>()?
+        .get_pressed()
+        .map(|key| HotkeyInput::Keyboard(*key))
+        .collect::<HashSet<_>>();
+    if let Some(mouse) = world.get_resource::<ButtonInput<MouseButton>>() {
+        pressed.extend(
+            mouse
+                .get_pressed()
+                .map(|button| HotkeyInput::Mouse(*button)),
+        );
+    }
+    if let Some(gamepads) = world.get_resource::<ButtonInput<GamepadButton>>() {
+        pressed.extend(
+            gamepads
+                .get_pressed()
+                .map(|button| HotkeyInput::Gamepad(button.button_type)),
+        );
+    }
+    if let Some(wheel) = world.get_resource::<Events<MouseWheel>>() {
+        for event in wheel_reader.read(wheel) {
+            if event.y > 0.0 {
+                pressed.insert(HotkeyInput::Wheel(WheelDirection::Up));
+            } else if event.y < 0.0 {
+                pressed.insert(HotkeyInput::Wheel(WheelDirection::Down));
+            }
+            if event.x > 0.0 {
+                pressed.insert(HotkeyInput::Wheel(WheelDirection::Right));
+            } else if event.x < 0.0 {
+                pressed.insert(HotkeyInput::Wheel(WheelDirection::Left));
+            }
+        }
+    }
+    Some(pressed)
+}
+
+/// Resolve held chords of all sets, so the most specific binding wins
+fn resolve_hotkey_chords(
+    world: &mut World,
+    mut wheel_reader: Local<ManualEventReader<MouseWheel>>,
+) {
+    let active = world
+        .get_resource::<ActiveHotkeyContext>()
+        .copied()
+        .unwrap_or_default();
+    // Bindings of inactive contexts must not shadow active ones
+    let chords = world.resource_scope::<AllHotkeys, _>(|world, all_hotkeys| {
+        all_hotkeys
+            .chords(world)
+            .into_iter()
+            .filter(|binding| binding.context.is_active(active.0))
+            .map(|binding| binding.chord)
+            .collect::<HashSet<_>>()
+    });
+    let Some(pressed) = pressed_inputs(world, &mut wheel_reader) else {
+        return;
+    };
+
+    let held = chords
+        .iter()
+        .filter(|chord| chord.is_held_by(&pressed))
+        .collect::<Vec<_>>();
+    let mut state = world.resource_mut::<HotkeyChordState>();
+    state.held = held
+        .iter()
+        .filter(|chord| {
+            chord.is_modifier_only() || !held.iter().any(|other| other.is_more_specific(chord))
+        })
+        .map(|chord| (*chord).clone())
+        .collect();
+    // Main key must be pressed last, modifier only chord must be pressed in this frame
+    let started = state
+        .held
+        .iter()
+        .filter(|chord| {
+            if chord.is_modifier_only() {
+                !chord.is_held_by(&state.previous)
+            } else {
+                chord.keys.iter().any(|key| !state.previous.contains(key))
+            }
+        })
+        .cloned()
+        .collect();
+    state.started = started;
+    // Every frame with wheel delta is a new wheel press, so fast scrolling fires once per frame
+    state.previous = pressed
+        .into_iter()
+        .filter(|input| !matches!(input, HotkeyInput::Wheel(_)))
+        .collect();
 }
 
 pub trait UntypedHotkeySet {
-    fn get_flat_bindings(&mut self) -> Vec<(String, &mut Vec<KeyCode>)>;
+    fn get_flat_bindings(&mut self) -> Vec<(String, &mut Vec<HotkeyInput>)>;
+    fn get_flat_contexts(&self) -> HashMap<String, HotkeyContext>;
     fn get_name(&self) -> &str;
+    /// Replace binding of hotkey. Returns false if there is no hotkey with this name
+    fn set_binding(&mut self, hotkey_name: &str, binding: Vec<HotkeyInput>) -> bool;
+    /// Binding is the same as on hotkey registration
+    fn is_default(&self, hotkey_name: &str) -> bool;
+    fn reset_binding(&mut self, hotkey_name: &str);
+    fn reset_all(&mut self);
 }
 
 impl<T: Hotkey> UntypedHotkeySet for HotkeySet<T> {
-    fn get_flat_bindings(&mut self) -> Vec<(String, &mut Vec<KeyCode>)> {
+    fn get_flat_bindings(&mut self) -> Vec<(String, &mut Vec<HotkeyInput>)> {
         let mut res = self
             .bindings
             .iter_mut()
@@ -96,19 +581,89 @@ impl<T: Hotkey> UntypedHotkeySet for HotkeySet<T> {
         res
     }
 
+    fn get_flat_contexts(&self) -> HashMap<String, HotkeyContext> {
+        self.bindings
+            .keys()
+            .map(|k| (k.name(), self.context(k)))
+            .collect()
+    }
+
     fn get_name(&self) -> &str {
         &self.name
     }
+
+    fn set_binding(&mut self, hotkey_name: &str, binding: Vec<HotkeyInput>) -> bool {
+        let Some(key) = self.key_by_name(hotkey_name) else {
+            return false;
+        };
+        self.bindings.insert(key, binding);
+        true
+    }
+
+    fn is_default(&self, hotkey_name: &str) -> bool {
+        let Some(key) = self.key_by_name(hotkey_name) else {
+            return true;
+        };
+        self.defaults.get(&key) == self.bindings.get(&key)
+    }
+
+    fn reset_binding(&mut self, hotkey_name: &str) {
+        if let Some(key) = self.key_by_name(hotkey_name) {
+            if let Some(binding) = self.defaults.get(&key) {
+                self.bindings.insert(key, binding.clone());
+            }
+        }
+    }
+
+    fn reset_all(&mut self) {
+        self.bindings.extend(self.defaults.clone());
+    }
 }
 
 pub trait HotkeyAppExt {
-    fn editor_hotkey<T: Hotkey>(&mut self, key: T, binding: Vec<KeyCode>) -> &mut Self;
+    /// Register hotkey which works in every context.
+    /// Binding can contain key codes, mouse buttons, wheel directions and gamepad buttons
+    fn editor_hotkey<T: Hotkey>(
+        &mut self,
+        key: T,
+        binding: Vec<impl Into<HotkeyInput>>,
+    ) -> &mut Self;
+
+    /// Register hotkey which works only while `context` is active
+    fn editor_hotkey_in_context<T: Hotkey>(
+        &mut self,
+        key: T,
+        binding: Vec<impl Into<HotkeyInput>>,
+        context: HotkeyContext,
+    ) -> &mut Self;
 }
 
 impl HotkeyAppExt for App {
-    fn editor_hotkey<T: Hotkey>(&mut self, key: T, binding: Vec<KeyCode>) -> &mut Self {
+    fn editor_hotkey<T: Hotkey>(
+        &mut self,
+        key: T,
+        binding: Vec<impl Into<HotkeyInput>>,
+    ) -> &mut Self {
+        self.editor_hotkey_in_context(key, binding, HotkeyContext::Any)
+    }
+
+    fn editor_hotkey_in_context<T: Hotkey>(
+        &mut self,
+        key: T,
+        binding: Vec<impl Into<HotkeyInput>>,
+        context: HotkeyContext,
+    ) -> &mut Self {
+        let binding = binding.into_iter().map(Into::into).collect::<Vec<_>>();
         if !self.world().contains_resource::<AllHotkeys>() {
             self.insert_resource(AllHotkeys::default());
+            self.init_resource::<HotkeyChordState>();
+            self.init_resource::<ActiveHotkeyContext>();
+            self.add_systems(PreUpdate, resolve_hotkey_chords.after(InputSystem));
+            self.register_type::<HotkeyInput>()
+                .register_type::<WheelDirection>()
+                .register_type::<Keymap>()
+                .register_type::<HashMap<String, Vec<HotkeyInput>>>()
+                .register_type::<HashMap<String, HashMap<String, Vec<HotkeyInput>>>>();
         }
 
         if !self.world().contains_resource::<HotkeySet<T>>() {
@@ -121,13 +676,17 @@ impl HotkeyAppExt for App {
                         |dst: &mut HotkeySet<T>, src: HotkeySet<T>| {
                             dst.bindings.extend(src.bindings);
                         },
-                    ));
+                    ))
+                    .persistence_version::<HotkeySet<T>>(
+                        HOTKEY_SET_VERSION,
+                        Box::new(migrate_keyboard_bindings),
+                    );
                 }
             }
-            self.add_systems(PreUpdate, hotkey_mapper::<T>);
-            self.register_type::<Vec<KeyCode>>();
+            self.add_systems(PreUpdate, hotkey_mapper::<T>.after(resolve_hotkey_chords));
+            self.register_type::<Vec<HotkeyInput>>();
             self.register_type::<HotkeySet<T>>();
-            self.register_type::<HashMap<T, Vec<KeyCode>>>();
+            self.register_type::<HashMap<T, Vec<HotkeyInput>>>();
             self.register_type::<T>();
             self.world_mut()
                 // Safe, was injected in this function
@@ -155,7 +714,9 @@ impl HotkeyAppExt for App {
         let Some(mut set) = self.world_mut().get_resource_mut::<HotkeySet<T>>() else {
             return self;
         };
+        set.defaults.insert(key, binding.clone());
         set.bindings.insert(key, binding);
+        set.contexts.insert(key, context);
         self
     }
 }
@@ -163,19 +724,26 @@ impl HotkeyAppExt for App {
 fn hotkey_mapper<T>(
     bindings: Res<HotkeySet<T>>,
     mut hotkeys: ResMut<ButtonInput<T>>,
-    input: Res<ButtonInput<KeyCode>>,
+    chords: Res<HotkeyChordState>,
+    active: Res<ActiveHotkeyContext>,
 ) where
     T: Hotkey,
 {
     hotkeys.clear();
     for (key, binding) in bindings.bindings.iter() {
-        let mut pressed = true;
-        for code in binding {
-            if !input.pressed(*code) {
-                pressed = false;
-            }
-        }
+        let chord = HotkeyChord::new(binding);
+        let pressed = if binding.is_empty() || !bindings.context(key).is_active(active.0) {
+            false
+        } else if hotkeys.pressed(*key) {
+            chords.is_held(&chord)
+        } else {
+            chords.just_started(&chord)
+        };
         if pressed {
+            // Held hotkey is pressed again, for example by next wheel scroll
+            if chords.just_started(&chord) {
+                hotkeys.reset(*key);
+            }
             hotkeys.press(*key);
         } else {
             hotkeys.release(*key);
@@ -186,6 +754,7 @@ fn hotkey_mapper<T>(
 #[cfg(test)]
 mod tests {
     use bevy::input::InputPlugin;
+    use serde::de::DeserializeSeed;
 
     use super::*;
 
@@ -201,6 +770,25 @@ mod tests {
         }
     }
 
+    #[derive(Reflect, Hash, PartialEq, Eq, Clone, Copy, Debug)]
+    enum OtherKey {
+        Save,
+        Scale,
+    }
+
+    impl Hotkey for OtherKey {
+        fn name(&self) -> String {
+            format!("{:?}", self)
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(code);
+        app.update();
+    }
+
     #[test]
     fn hotkey_tester() {
         let mut app = App::new();
@@ -224,4 +812,282 @@ mod tests {
             assert_eq!(input.pressed(TestKey::A), true);
         }
     }
+
+    #[test]
+    fn modifier_chord_does_not_trigger_plain_key() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::KeyS]);
+        app.editor_hotkey(OtherKey::Save, vec![KeyCode::KeyS, KeyCode::ControlLeft]);
+        app.update();
+
+        press(&mut app, KeyCode::ControlLeft);
+        press(&mut app, KeyCode::KeyS);
+        assert!(app
+            .world()
+            .resource::<ButtonInput<OtherKey>>()
+            .just_pressed(OtherKey::Save));
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::A));
+    }
+
+    #[test]
+    fn chord_main_key_must_be_pressed_last() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::KeyS]);
+        app.editor_hotkey(OtherKey::Save, vec![KeyCode::ControlLeft, KeyCode::KeyS]);
+        app.update();
+
+        press(&mut app, KeyCode::KeyS);
+        assert!(app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .just_pressed(TestKey::A));
+        press(&mut app, KeyCode::ControlLeft);
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::A));
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<OtherKey>>()
+            .pressed(OtherKey::Save));
+    }
+
+    #[test]
+    fn most_specific_chord_wins() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::KeyA]);
+        app.editor_hotkey(TestKey::B, vec![KeyCode::KeyA, KeyCode::KeyB]);
+        app.update();
+
+        {
+            let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            input.press(KeyCode::KeyA);
+            input.press(KeyCode::KeyB);
+        }
+        app.update();
+        let hotkeys = app.world().resource::<ButtonInput<TestKey>>();
+        assert!(hotkeys.just_pressed(TestKey::B));
+        assert!(!hotkeys.pressed(TestKey::A));
+    }
+
+    #[test]
+    fn conflicts_are_detected_across_sets() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::KeyS]);
+        app.editor_hotkey(TestKey::B, vec![KeyCode::ControlLeft, KeyCode::KeyS]);
+        app.editor_hotkey(OtherKey::Scale, vec![KeyCode::KeyS]);
+        app.editor_hotkey(OtherKey::Save, vec![KeyCode::ControlRight, KeyCode::KeyX]);
+
+        let conflicts = app
+            .world_mut()
+            .resource_scope::<AllHotkeys, _>(|world, all| all.conflicts(world));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].chord.to_string(), "S");
+        assert_eq!(conflicts[0].hotkeys.len(), 2);
+        assert_eq!(
+            HotkeyChord::new(&[KeyCode::KeyS, KeyCode::ShiftRight, KeyCode::ControlLeft])
+                .to_string(),
+            "Ctrl + Shift + S"
+        );
+    }
+
+    #[test]
+    fn hotkeys_work_only_in_their_context() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey_in_context(TestKey::A, vec![KeyCode::KeyS], HotkeyContext::Viewport);
+        app.editor_hotkey_in_context(
+            OtherKey::Scale,
+            vec![KeyCode::KeyS],
+            HotkeyContext::Hierarchy,
+        );
+        app.update();
+
+        app.insert_resource(ActiveHotkeyContext(HotkeyContext::TextEditing));
+        press(&mut app, KeyCode::KeyS);
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::A));
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(KeyCode::KeyS);
+        app.insert_resource(ActiveHotkeyContext(HotkeyContext::Viewport));
+        app.update();
+        press(&mut app, KeyCode::KeyS);
+        assert!(app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .just_pressed(TestKey::A));
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<OtherKey>>()
+            .pressed(OtherKey::Scale));
+
+        // Same key in different panels is not a conflict
+        let conflicts = app
+            .world_mut()
+            .resource_scope::<AllHotkeys, _>(|world, all| all.conflicts(world));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn typing_suppresses_hotkeys_without_text_context() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::ControlLeft, KeyCode::KeyZ]);
+        app.editor_hotkey_in_context(
+            OtherKey::Scale,
+            vec![KeyCode::ControlLeft, KeyCode::KeyZ],
+            HotkeyContext::Always,
+        );
+        app.insert_resource(ActiveHotkeyContext(HotkeyContext::TextEditing));
+        app.update();
+
+        press(&mut app, KeyCode::ControlLeft);
+        press(&mut app, KeyCode::KeyZ);
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::A));
+        assert!(app
+            .world()
+            .resource::<ButtonInput<OtherKey>>()
+            .just_pressed(OtherKey::Scale));
+
+        assert!(!HotkeyContext::Any.overlaps(HotkeyContext::TextEditing));
+        assert!(HotkeyContext::Always.overlaps(HotkeyContext::TextEditing));
+        assert!(HotkeyContext::Any.overlaps(HotkeyContext::Viewport));
+    }
+
+    #[test]
+    fn old_keyboard_bindings_are_migrated() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::A, vec![KeyCode::KeyA]);
+
+        let old = r#"{
+            "HotkeySet<[TestKey]>": (bindings: {A: [ControlLeft, KeyZ], B: []}),
+        }"#;
+        let data = migrate_keyboard_bindings(0, old.to_string()).unwrap();
+        assert!(data.contains("[Keyboard(ControlLeft), Keyboard(KeyZ)]"));
+        assert!(data.contains("B: []"));
+        assert!(data.contains("\"HotkeySet<[TestKey]>\""));
+        assert_eq!(migrate_keyboard_bindings(1, old.to_string()).unwrap(), old);
+        assert!(migrate_keyboard_bindings(0, "[KeyA".to_string()).is_err());
+
+        let set_data = "(bindings: {A: [Keyboard(ControlLeft), Mouse(Left)]}, name: \"TestKey\")";
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        let registration = registry
+            .get(std::any::TypeId::of::<HotkeySet<TestKey>>())
+            .unwrap();
+        let mut deserializer = ron::Deserializer::from_str(set_data).unwrap();
+        let value = bevy::reflect::serde::TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let set = HotkeySet::<TestKey>::from_reflect(value.as_ref()).unwrap();
+        assert_eq!(
+            set.bindings[&TestKey::A],
+            vec![
+                HotkeyInput::Keyboard(KeyCode::ControlLeft),
+                HotkeyInput::Mouse(MouseButton::Left)
+            ]
+        );
+    }
+
+    #[test]
+    fn mouse_and_wheel_bindings_trigger_hotkeys() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(
+            TestKey::A,
+            vec![
+                HotkeyInput::Keyboard(KeyCode::AltLeft),
+                HotkeyInput::Mouse(MouseButton::Middle),
+            ],
+        );
+        app.editor_hotkey(TestKey::B, vec![WheelDirection::Up]);
+        app.update();
+
+        press(&mut app, KeyCode::AltLeft);
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Middle);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .just_pressed(TestKey::A));
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(KeyCode::AltLeft);
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .release(MouseButton::Middle);
+        app.world_mut().send_event(MouseWheel {
+            unit: bevy::input::mouse::MouseScrollUnit::Line,
+            x: 0.0,
+            y: 1.0,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+        assert!(app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .just_pressed(TestKey::B));
+        app.update();
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::B));
+    }
+
+    #[test]
+    fn every_scrolled_frame_presses_wheel_hotkey() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(
+            TestKey::A,
+            vec![
+                HotkeyInput::Keyboard(KeyCode::ControlLeft),
+                HotkeyInput::Wheel(WheelDirection::Down),
+            ],
+        );
+        app.update();
+
+        press(&mut app, KeyCode::ControlLeft);
+        let mut presses = 0;
+        for _ in 0..3 {
+            app.world_mut().send_event(MouseWheel {
+                unit: bevy::input::mouse::MouseScrollUnit::Line,
+                x: 0.0,
+                y: -1.0,
+                window: Entity::PLACEHOLDER,
+            });
+            app.update();
+            if app
+                .world()
+                .resource::<ButtonInput<TestKey>>()
+                .just_pressed(TestKey::A)
+            {
+                presses += 1;
+            }
+        }
+        assert_eq!(presses, 3);
+
+        app.update();
+        assert!(!app
+            .world()
+            .resource::<ButtonInput<TestKey>>()
+            .pressed(TestKey::A));
+    }
 }
@@ -0,0 +1,454 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use bevy::{ecs::event::ManualEventReader, prelude::*, utils::HashMap};
+use bevy_egui::EguiContexts;
+use egui_dock::egui;
+use space_prefab::{
+    persistent_id::PersistentIdMap,
+    save::{SaveConfig, SaveOverride, SaveState},
+    scene_change::SceneChange,
+};
+use space_shared::{
+    toast::{ToastKind, ToastMessage},
+    *,
+};
+use space_undo::{ChangeCommitted, UndoSet};
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
+
+use crate::load::SceneLoaded;
+
+const JOURNAL_EXTENSION: &str = ".journal";
+
+/// Plugin which writes every committed change to journal file next to the scene,
+/// so unsaved work can be restored after crash
+pub struct ChangeJournalPlugin;
+
+impl Plugin for ChangeJournalPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChangeJournal>()
+            .init_resource::<ChangeJournalSettings>()
+            .register_type::<ChangeJournalSettings>()
+            .add_event::<JournalAction>();
+
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<ChangeJournalSettings>()
+            .persistence_layer::<ChangeJournalSettings>(PersistenceLayer::User);
+
+        app.add_systems(Startup, offer_found_journal);
+        app.add_systems(Update, (journal_actions, journal_scene_events).chain());
+        app.add_systems(
+            Update,
+            replay_journal
+                .after(crate::load::load_listener)
+                .in_set(crate::EditorLoadSet),
+        );
+        app.add_systems(OnEnter(SaveState::Save), clear_journal_on_save);
+        app.add_systems(
+            PostUpdate,
+            write_journal
+                .after(UndoSet::Global)
+                .in_set(EditorSet::Editor),
+        );
+    }
+}
+
+/// Plugin with dialog which offers to restore found journal
+pub struct ChangeJournalUiPlugin;
+
+impl Plugin for ChangeJournalUiPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, show_journal_offer.in_set(EditorSet::Editor));
+    }
+}
+
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource, Default)]
+pub struct ChangeJournalSettings {
+    pub enabled: bool,
+}
+
+impl Default for ChangeJournalSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Journal of unsaved changes of current scene
+#[derive(Resource, Default)]
+pub struct ChangeJournal {
+    path: Option<PathBuf>,
+    offer: Option<JournalOffer>,
+    pending_replay: Option<PendingReplay>,
+}
+
+impl ChangeJournal {
+    /// Journal found on disk, which can be restored or discarded with [`JournalAction`]
+    pub const fn offer(&self) -> Option<&JournalOffer> {
+        self.offer.as_ref()
+    }
+
+    /// Changes are not written until found journal is restored or discarded
+    fn writable_path(&self) -> Option<&Path> {
+        if self.offer.is_some() || self.pending_replay.is_some() {
+            None
+        } else {
+            self.path.as_deref()
+        }
+    }
+}
+
+/// Unsaved changes of scene found on disk
+#[derive(Clone, Debug)]
+pub struct JournalOffer {
+    /// Scene path relative to assets folder
+    pub scene: String,
+    pub journal: PathBuf,
+    pub changes: usize,
+}
+
+impl JournalOffer {
+    fn from_file(scene: String, journal: PathBuf) -> Option<Self> {
+        let changes = fs::read_to_string(&journal)
+            .ok()?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+        (changes > 0).then_some(Self {
+            scene,
+            journal,
+            changes,
+        })
+    }
+}
+
+struct PendingReplay {
+    scene: String,
+    journal: PathBuf,
+    records: Vec<SceneChange>,
+}
+
+/// User decision about found journal
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalAction {
+    /// Load scene and replay journal on top of it
+    Restore,
+    /// Delete found journal
+    Discard,
+}
+
+/// Journal file is stored next to scene file
+fn journal_path(scene_file: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", scene_file, JOURNAL_EXTENSION))
+}
+
+/// Scene path relative to assets folder for journal file
+fn journal_scene(journal: &Path) -> Option<String> {
+    let scene = journal.to_str()?.strip_suffix(JOURNAL_EXTENSION)?;
+    Some(scene_asset_path(scene))
+}
+
+fn remove_journal(path: &Path) {
+    if path.exists() {
+        if let Err(err) = fs::remove_file(path) {
+            error!("Failed to remove change journal {:?}: {}", path, err);
+        }
+    }
+}
+
+fn find_journals(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_journals(&path, found);
+        } else if path
+            .to_str()
+            .is_some_and(|path| path.ends_with(&format!(".scn.ron{}", JOURNAL_EXTENSION)))
+        {
+            found.push(path);
+        }
+    }
+}
+
+fn offer_found_journal(mut journal: ResMut<ChangeJournal>, settings: Res<ChangeJournalSettings>) {
+    if !settings.enabled {
+        return;
+    }
+    let mut found = vec![];
+    find_journals(Path::new(ASSETS_DIR), &mut found);
+    // Offer the most recent journal
+    found.sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+    journal.offer = found.into_iter().rev().find_map(|path| {
+        let scene = journal_scene(&path)?;
+        JournalOffer::from_file(scene, path)
+    });
+}
+
+fn journal_scene_events(mut events: EventReader<EditorEvent>, mut journal: ResMut<ChangeJournal>) {
+    for event in events.read() {
+        match event {
+            EditorEvent::Load(EditorPrefabPath::File(path)) => {
+                if journal
+                    .pending_replay
+                    .as_ref()
+                    .is_some_and(|replay| replay.scene == *path)
+                {
+                    continue;
+                }
+                let journal_file = journal_path(&asset_file_path(path));
+                journal.offer = JournalOffer::from_file(path.clone(), journal_file.clone());
+                journal.path = Some(journal_file);
+            }
+            EditorEvent::Save(EditorPrefabPath::File(path)) => {
+                // Changes of previous scene file are saved to the new one
+                let new_path = journal_path(path);
+                if let Some(old_path) = journal.path.replace(new_path.clone()) {
+                    if old_path != new_path && journal.writable_path().is_some() {
+                        remove_journal(&old_path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Saved scene already contains all journaled changes
+fn clear_journal_on_save(
+    journal: Res<ChangeJournal>,
+    save_config: Res<SaveConfig>,
+    save_override: Res<SaveOverride>,
+) {
+    // Scene is saved to memory on play mode start, and autosave does not save the scene file
+    if save_override.skip_config_path
+        || !matches!(save_config.path, Some(EditorPrefabPath::File(_)))
+    {
+        return;
+    }
+    if let Some(path) = journal.writable_path() {
+        remove_journal(path);
+    }
+}
+
+fn journal_actions(
+    mut actions: EventReader<JournalAction>,
+    mut journal: ResMut<ChangeJournal>,
+    mut editor_events: EventWriter<EditorEvent>,
+    mut toasts: EventWriter<ToastMessage>,
+) {
+    for action in actions.read() {
+        let Some(offer) = journal.offer.take() else {
+            continue;
+        };
+        match action {
+            JournalAction::Restore => {
+                let records = fs::read_to_string(&offer.journal)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| {
+                        text.lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .map(|line| {
+                                ron::from_str::<SceneChange>(line).map_err(|e| e.to_string())
+                            })
+                            .collect::<Result<Vec<_>, String>>()
+                    });
+                match records {
+                    Ok(records) => {
+                        editor_events.send(EditorEvent::Load(EditorPrefabPath::File(
+                            offer.scene.clone(),
+                        )));
+                        journal.pending_replay = Some(PendingReplay {
+                            scene: offer.scene,
+                            journal: offer.journal,
+                            records,
+                        });
+                    }
+                    Err(err) => {
+                        error!("Failed to read change journal {:?}: {}", offer.journal, err);
+                        toasts.send(ToastMessage::new(
+                            &format!("Failed to read change journal: {}", err),
+                            ToastKind::Error,
+                        ));
+                    }
+                }
+            }
+            JournalAction::Discard => {
+                remove_journal(&offer.journal);
+                journal.path = Some(offer.journal);
+            }
+        }
+    }
+}
+
+fn replay_journal(world: &mut World, mut reader: Local<ManualEventReader<SceneLoaded>>) {
+    let loaded = reader
+        .read(world.resource::<Events<SceneLoaded>>())
+        .next()
+        .is_some();
+    if !loaded {
+        return;
+    }
+    let Some(replay) = world.resource_mut::<ChangeJournal>().pending_replay.take() else {
+        return;
+    };
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut query = world.query::<(Entity, &PersistentId)>();
+    let mut entities = query
+        .iter(world)
+        .map(|(entity, id)| (id.0, entity))
+        .collect::<HashMap<_, _>>();
+
+    let mut failed = 0;
+    let mut replayed = 0;
+    let mut stopped_at = None;
+    for record in replay.records.iter() {
+        // Skipping the change would silently restore a different scene
+        if let Some(change) = record.unrecorded() {
+            warn!("Journal replay stopped at change `{}`", change);
+            stopped_at = Some(change.to_string());
+            break;
+        }
+        replayed += 1;
+        if let Err(err) = record.apply(world, &mut entities, &registry) {
+            warn!("Failed to replay journal record: {}", err);
+            failed += 1;
+        }
+    }
+
+    let (text, kind) = if let Some(change) = stopped_at {
+        (
+            format!(
+                "Restored {} of {} unsaved changes, change `{}` can't be restored",
+                replayed - failed,
+                replay.records.len(),
+                change
+            ),
+            ToastKind::Warning,
+        )
+    } else if failed == 0 {
+        (
+            format!("Restored {} unsaved changes", replay.records.len()),
+            ToastKind::Success,
+        )
+    } else {
+        (
+            format!(
+                "Restored {} unsaved changes, {} failed",
+                replay.records.len() - failed,
+                failed
+            ),
+            ToastKind::Warning,
+        )
+    };
+    world.send_event(ToastMessage::new(&text, kind));
+    world.resource_mut::<ChangeJournal>().path = Some(replay.journal);
+}
+
+fn write_journal(
+    mut events: EventReader<ChangeCommitted>,
+    journal: Res<ChangeJournal>,
+    settings: Res<ChangeJournalSettings>,
+    ids: Res<PersistentIdMap>,
+    registry: Res<AppTypeRegistry>,
+    mut loaded: EventReader<SceneLoaded>,
+) {
+    // Despawn of previous scene on load is not a user change
+    let scene_loaded = loaded.read().count() > 0;
+    let Some(path) = journal
+        .writable_path()
+        .filter(|_| settings.enabled && !scene_loaded)
+    else {
+        events.clear();
+        return;
+    };
+    let registry = registry.read();
+
+    let mut lines = String::new();
+    for event in events.read() {
+        let Some(record) = event.change.record() else {
+            continue;
+        };
+        let line = SceneChange::from_change(&record, &|entity| ids.id(entity), &registry)
+            .and_then(|record| ron::to_string(&record).map_err(|e| e.to_string()));
+        match line {
+            Ok(line) => {
+                lines.push_str(&line);
+                lines.push('\n');
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to write `{}` to change journal: {}",
+                    event.change.debug_text(),
+                    err
+                );
+            }
+        }
+    }
+    if lines.is_empty() {
+        return;
+    }
+
+    if let Err(err) = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+    {
+        error!("Failed to write change journal {:?}: {}", path, err);
+    }
+}
+
+fn show_journal_offer(
+    mut ctxs: EguiContexts,
+    journal: Res<ChangeJournal>,
+    mut actions: EventWriter<JournalAction>,
+) {
+    let Some(offer) = journal.offer() else {
+        return;
+    };
+    egui::Window::new("Restore unsaved changes")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(format!(
+                "Found {} unsaved changes of scene {}",
+                offer.changes, offer.scene
+            ));
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    actions.send(JournalAction::Restore);
+                }
+                if ui.button("Discard").clicked() {
+                    actions.send(JournalAction::Discard);
+                }
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_path_is_next_to_scene() {
+        let path = journal_path("assets/scenes/level.scn.ron");
+        assert_eq!(path, PathBuf::from("assets/scenes/level.scn.ron.journal"));
+        assert_eq!(
+            journal_scene(&path),
+            Some("scenes/level.scn.ron".to_string())
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::{any::TypeId, path::Path};
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        TypeRegistry,
+    },
+    utils::HashMap,
+};
+use serde::de::DeserializeSeed;
+
+use crate::hotkeys::{AllHotkeys, Hotkey, HotkeyInput};
+
+/// Keymap file used when no path is given
+pub const DEFAULT_KEYMAP_PATH: &str = "keymap.ron";
+
+/// Bindings of hotkeys by set and hotkey names. Can be shared as standalone RON file
+#[derive(Reflect, Default, Clone, Debug, PartialEq, Eq)]
+#[reflect(Default)]
+pub struct Keymap {
+    pub sets: HashMap<String, HashMap<String, Vec<HotkeyInput>>>,
+}
+
+impl Keymap {
+    /// Add binding of hotkey from set with default name
+    pub fn with<T: Hotkey>(mut self, key: T, binding: Vec<impl Into<HotkeyInput>>) -> Self {
+        self.sets
+            .entry(T::short_type_path().to_string())
+            .or_default()
+            .insert(key.name(), binding.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Bindings of `other` replace bindings of `self`
+    pub fn merge(&mut self, other: Self) {
+        for (set, bindings) in other.sets {
+            self.sets.entry(set).or_default().extend(bindings);
+        }
+    }
+
+    pub fn to_ron(&self, registry: &TypeRegistry) -> Result<String, String> {
+        let serializer = TypedReflectSerializer::new(self, registry);
+        ron::ser::to_string_pretty(&serializer, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn from_ron(data: &str, registry: &TypeRegistry) -> Result<Self, String> {
+        let registration = registry
+            .get(TypeId::of::<Self>())
+            .ok_or_else(|| "Keymap type is not registered".to_string())?;
+        let mut deserializer = ron::Deserializer::from_str(data).map_err(|e| e.to_string())?;
+        let value = TypedReflectDeserializer::new(registration, registry)
+            .deserialize(&mut deserializer)
+            .map_err(|e| e.to_string())?;
+        Self::from_reflect(value.as_ref()).ok_or_else(|| "Invalid keymap".to_string())
+    }
+}
+
+impl AllHotkeys {
+    /// Current bindings of all registered sets
+    pub fn export_keymap(&self, world: &mut World) -> Keymap {
+        let mut keymap = Keymap::default();
+        self.global_map(world, &mut |_, set| {
+            let bindings = set
+                .get_flat_bindings()
+                .into_iter()
+                .map(|(name, binding)| (name, binding.clone()))
+                .collect();
+            keymap.sets.insert(set.get_name().to_string(), bindings);
+        });
+        keymap
+    }
+
+    /// Apply bindings of keymap. Hotkeys missing in keymap keep their bindings
+    pub fn import_keymap(&self, world: &mut World, keymap: &Keymap) {
+        self.global_map(world, &mut |_, set| {
+            let Some(bindings) = keymap.sets.get(set.get_name()) else {
+                return;
+            };
+            for (name, binding) in bindings {
+                if !set.set_binding(name, binding.clone()) {
+                    warn!("Keymap has unknown hotkey {}: {}", set.get_name(), name);
+                }
+            }
+        });
+    }
+
+    /// Reset bindings of all sets to bindings given on registration
+    pub fn reset_all(&self, world: &mut World) {
+        self.global_map(world, &mut |_, set| set.reset_all());
+    }
+}
+
+/// Named keymaps which replace default bindings, like Blender or Unity style keymaps
+#[derive(Resource, Default)]
+pub struct KeymapPresets {
+    pub presets: Vec<(String, Keymap)>,
+}
+
+impl KeymapPresets {
+    pub fn get(&self, name: &str) -> Option<&Keymap> {
+        self.presets
+            .iter()
+            .find(|(preset, _)| preset == name)
+            .map(|(_, keymap)| keymap)
+    }
+}
+
+pub trait KeymapAppExt {
+    /// Add bindings to keymap preset. Presets with the same name are merged,
+    /// so every plugin can add its hotkeys to common presets
+    fn editor_keymap_preset(&mut self, name: &str, keymap: Keymap) -> &mut Self;
+}
+
+impl KeymapAppExt for App {
+    fn editor_keymap_preset(&mut self, name: &str, keymap: Keymap) -> &mut Self {
+        let mut presets = self
+            .world_mut()
+            .get_resource_or_insert_with(KeymapPresets::default);
+        if let Some((_, preset)) = presets
+            .presets
+            .iter_mut()
+            .find(|(preset, _)| preset == name)
+        {
+            preset.merge(keymap);
+        } else {
+            presets.presets.push((name.to_string(), keymap));
+        }
+        self
+    }
+}
+
+/// Reset all bindings and apply preset. Returns false if there is no preset with this name
+pub fn apply_keymap_preset(world: &mut World, name: &str) -> bool {
+    let Some(keymap) = world
+        .get_resource::<KeymapPresets>()
+        .and_then(|presets| presets.get(name))
+        .cloned()
+    else {
+        return false;
+    };
+    world.resource_scope::<AllHotkeys, _>(|world, all_hotkeys| {
+        all_hotkeys.reset_all(world);
+        all_hotkeys.import_keymap(world, &keymap);
+    });
+    true
+}
+
+/// Write bindings of all hotkey sets to keymap file
+pub fn save_keymap(world: &mut World, path: &Path) -> Result<(), String> {
+    let keymap = world
+        .resource_scope::<AllHotkeys, _>(|world, all_hotkeys| all_hotkeys.export_keymap(world));
+    let data = keymap.to_ron(&world.resource::<AppTypeRegistry>().read())?;
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Apply bindings from keymap file
+pub fn load_keymap(world: &mut World, path: &Path) -> Result<(), String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let keymap = Keymap::from_ron(&data, &world.resource::<AppTypeRegistry>().read())?;
+    world.resource_scope::<AllHotkeys, _>(|world, all_hotkeys| {
+        all_hotkeys.import_keymap(world, &keymap);
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use super::*;
+    use crate::hotkeys::{HotkeyAppExt, HotkeySet, UntypedHotkeySet};
+
+    #[derive(Reflect, Hash, PartialEq, Eq, Clone, Copy, Debug)]
+    enum TestKey {
+        Move,
+        Delete,
+    }
+
+    impl Hotkey for TestKey {
+        fn name(&self) -> String {
+            format!("{:?}", self)
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin));
+        app.editor_hotkey(TestKey::Move, vec![KeyCode::KeyG]);
+        app.editor_hotkey(TestKey::Delete, vec![KeyCode::KeyX]);
+        app
+    }
+
+    fn keys(codes: &[KeyCode]) -> Vec<HotkeyInput> {
+        codes.iter().copied().map(HotkeyInput::Keyboard).collect()
+    }
+
+    fn binding(app: &App, key: TestKey) -> Vec<HotkeyInput> {
+        app.world().resource::<HotkeySet<TestKey>>().bindings[&key].clone()
+    }
+
+    #[test]
+    fn keymap_ron_round_trip() {
+        let mut app = app();
+        app.world_mut()
+            .resource_mut::<HotkeySet<TestKey>>()
+            .set_binding("Move", keys(&[KeyCode::ControlLeft, KeyCode::KeyW]));
+
+        let path = std::env::temp_dir().join("space_editor_keymap_test.ron");
+        save_keymap(app.world_mut(), &path).unwrap();
+
+        let mut other = self::app();
+        load_keymap(other.world_mut(), &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            binding(&other, TestKey::Move),
+            keys(&[KeyCode::ControlLeft, KeyCode::KeyW])
+        );
+        assert_eq!(binding(&other, TestKey::Delete), keys(&[KeyCode::KeyX]));
+    }
+
+    #[test]
+    fn preset_replaces_bindings_and_reset_restores_defaults() {
+        let mut app = app();
+        app.editor_keymap_preset(
+            "Unity",
+            Keymap::default().with(TestKey::Move, vec![KeyCode::KeyW]),
+        );
+        app.editor_keymap_preset(
+            "Unity",
+            Keymap::default().with(TestKey::Delete, vec![KeyCode::Delete]),
+        );
+
+        assert!(apply_keymap_preset(app.world_mut(), "Unity"));
+        assert_eq!(binding(&app, TestKey::Move), keys(&[KeyCode::KeyW]));
+        assert_eq!(binding(&app, TestKey::Delete), keys(&[KeyCode::Delete]));
+        assert!(!apply_keymap_preset(app.world_mut(), "Unknown"));
+
+        let mut set = app.world_mut().resource_mut::<HotkeySet<TestKey>>();
+        assert!(!set.is_default("Move"));
+        set.reset_binding("Move");
+        assert!(set.is_default("Move"));
+        assert_eq!(set.bindings[&TestKey::Move], keys(&[KeyCode::KeyG]));
+        set.reset_all();
+        assert_eq!(set.bindings[&TestKey::Delete], keys(&[KeyCode::KeyX]));
+    }
+}
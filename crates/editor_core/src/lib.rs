@@ -1,14 +1,30 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 
+/// Periodic autosave of current scene
+pub mod autosave;
+/// Registry of editor commands for command palette and hotkeys
+pub mod commands;
 pub mod hotkeys;
+/// Crash recovery journal of unsaved changes
+pub mod journal;
+pub mod keymap;
+/// Sending of scene edits to separately launched game
+pub mod live_link;
 mod load;
 pub mod selected;
+/// Named selection sets and selection history
+pub mod selection_tools;
+/// Undo of editor settings resources
+pub mod settings_undo;
 pub mod task_storage;
 pub mod toast;
 
 pub mod prelude {
-    pub use super::{hotkeys::*, load::*, selected::*, task_storage::*};
+    pub use super::{
+        commands::*, hotkeys::*, keymap::*, load::*, selected::*, selection_tools::*,
+        settings_undo::*, task_storage::*,
+    };
     pub use crate::*;
     pub use space_undo;
 }
@@ -17,7 +33,7 @@ pub mod gltf_unpack;
 
 use bevy::prelude::*;
 
-use prelude::load_listener;
+use prelude::{cancel_scene_loading, load_listener, LoadReport, SceneLoaded};
 use space_prefab::save::{SaveConfig, SaveState};
 use space_shared::*;
 use space_undo::AppAutoUndo;
@@ -31,37 +47,68 @@ impl Plugin for EditorCore {
         app.add_plugins(gltf_unpack::UnpackGltfPlugin);
 
         #[cfg(feature = "persistence_editor")]
-        app.add_plugins(space_persistence::PersistencePlugin);
+        {
+            app.add_plugins(space_persistence::PersistencePlugin);
+            app.world_mut()
+                .resource_mut::<space_persistence::PersistenceRegistry>()
+                .set_source(
+                    space_persistence::PersistenceLayer::User,
+                    space_persistence::PersistenceDataSource::user_config(),
+                );
+            app.add_systems(Update, persistence_error_toasts);
+        }
 
         app.add_plugins(BackgroundTaskStoragePlugin);
 
         app.configure_sets(Update, EditorLoadSet.in_set(EditorSet::Editor));
 
         app.add_event::<EditorEvent>();
+        app.add_event::<SceneLoaded>();
+        app.init_resource::<LoadReport>();
 
         app.init_resource::<PrefabMemoryCache>();
         app.init_resource::<EditorLoader>();
 
         app.add_systems(
             Update,
-            (apply_deferred, load_listener)
+            (apply_deferred, cancel_scene_loading, load_listener)
                 .chain()
                 .in_set(EditorLoadSet),
         );
         app.add_systems(Update, editor_event_listener);
 
+        app.add_plugins(journal::ChangeJournalPlugin);
+        app.add_plugins(autosave::AutosavePlugin);
+        app.add_plugins(commands::EditorCommandsPlugin);
+        app.add_plugins(live_link::LiveLinkPlugin);
+
         app.auto_reflected_undo::<Parent>();
         app.auto_reflected_undo::<Children>();
-        app.auto_undo::<PrefabMarker>();
+        app.auto_reflected_undo::<PrefabMarker>();
     }
 }
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EditorLoadSet;
 
+#[cfg(feature = "persistence_editor")]
+fn persistence_error_toasts(
+    mut errors: EventReader<space_persistence::PersistenceError>,
+    mut toasts: EventWriter<space_shared::toast::ToastMessage>,
+) {
+    for error in errors.read() {
+        toasts.send(space_shared::toast::ToastMessage::new(
+            &error.to_string(),
+            space_shared::toast::ToastKind::Error,
+        ));
+    }
+}
+
 #[derive(Resource, Default, Clone)]
 pub struct EditorLoader {
     pub scene: Option<Handle<DynamicScene>>,
+    /// Asset path of loaded scene file, used to load scene partially if asset failed to load
+    pub path: Option<String>,
 }
 
 fn editor_event_listener(
@@ -73,6 +120,7 @@ fn editor_event_listener(
     mut start_game_state: ResMut<NextState<EditorState>>,
     cache: ResMut<PrefabMemoryCache>,
     mut gltf_events: EventWriter<gltf_unpack::EditorUnpackGltf>,
+    gltf_options: Res<gltf_unpack::GltfImportOptions>,
     mut background_tasks: ResMut<BackgroundTaskStorage>,
 ) {
     for event in events.read() {
@@ -85,10 +133,12 @@ fn editor_event_listener(
                         handle.clone().untyped(),
                     ));
                     load_server.scene = Some(handle);
+                    load_server.path = Some(path.to_string());
                     info!("Loading prefab by editor event from file {}", path);
                 }
                 EditorPrefabPath::MemoryCache => {
                     load_server.scene.clone_from(&cache.scene);
+                    load_server.path = None;
                     info!("Loading prefab by editor event from memory cache");
                 }
             },
@@ -101,7 +151,10 @@ fn editor_event_listener(
                 start_game_state.set(EditorState::GamePrepare);
             }
             EditorEvent::LoadGltfAsPrefab(path) => {
-                gltf_events.send(gltf_unpack::EditorUnpackGltf { path: path.clone() });
+                gltf_events.send(gltf_unpack::EditorUnpackGltf {
+                    path: path.clone(),
+                    options: gltf_options.clone(),
+                });
             }
         }
     }
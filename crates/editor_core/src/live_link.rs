@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use space_prefab::{persistent_id::PersistentIdMap, scene_change::SceneChange};
+use space_shared::{
+    local_socket::{random_token, tokens_match, LocalServer},
+    toast::{ToastKind, ToastMessage},
+    *,
+};
+use space_undo::{ChangeCommitted, UndoSet};
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
+
+pub use space_prefab::live_link::{DEFAULT_LIVE_LINK_PORT, LIVE_LINK_TOKEN_ENV};
+
+use crate::load::SceneLoaded;
+
+/// Plugin which sends committed scene changes to separately launched game
+///
+/// Game must add [`space_prefab::live_link::LiveLinkClientPlugin`] and send [`LiveLinkServer::token`]
+/// as first line of connection. Changes are sent only to games with valid token
+pub struct LiveLinkPlugin;
+
+impl Plugin for LiveLinkPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LiveLinkSettings>()
+            .register_type::<LiveLinkSettings>()
+            .init_resource::<LiveLinkServer>();
+
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<LiveLinkSettings>()
+            .persistence_layer::<LiveLinkSettings>(PersistenceLayer::User);
+
+        app.add_systems(
+            PostUpdate,
+            send_live_link
+                .after(UndoSet::Global)
+                .in_set(EditorSet::Editor),
+        );
+    }
+}
+
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+pub struct LiveLinkSettings {
+    pub enabled: bool,
+    /// Port on localhost
+    pub port: u16,
+}
+
+impl Default for LiveLinkSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_LIVE_LINK_PORT,
+        }
+    }
+}
+
+/// Listening socket and connected games
+#[derive(Resource)]
+pub struct LiveLinkServer {
+    pub server: LocalServer,
+    /// Token of this editor session, which games must send first
+    pub token: String,
+}
+
+impl Default for LiveLinkServer {
+    fn default() -> Self {
+        let token = std::env::var(LIVE_LINK_TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.is_empty())
+            .unwrap_or_else(random_token);
+        Self {
+            server: LocalServer::default(),
+            token,
+        }
+    }
+}
+
+fn send_live_link(
+    mut events: EventReader<ChangeCommitted>,
+    mut link: ResMut<LiveLinkServer>,
+    settings: Res<LiveLinkSettings>,
+    ids: Res<PersistentIdMap>,
+    registry: Res<AppTypeRegistry>,
+    mut loaded: EventReader<SceneLoaded>,
+    mut toasts: EventWriter<ToastMessage>,
+) {
+    let LiveLinkServer { server, token } = &mut *link;
+    server.sync(settings.enabled.then_some(settings.port));
+    server.accept();
+    // Games send only session token, later reading only detects closed connections
+    for (connection, line) in server.read_lines() {
+        if server.is_authenticated(connection) {
+            continue;
+        }
+        if tokens_match(&line, token) {
+            server.authenticate(connection);
+            toasts.send(ToastMessage::new(
+                "Game connected to live link",
+                ToastKind::Info,
+            ));
+        } else {
+            warn!("Game sent invalid live link token, closing connection");
+            server.disconnect(connection);
+        }
+    }
+
+    // Despawn of previous scene on load is not a user change
+    let scene_loaded = loaded.read().count() > 0;
+    if server.authenticated_connections() == 0 || scene_loaded {
+        events.clear();
+        server.flush();
+        return;
+    }
+    let registry = registry.read();
+
+    for event in events.read() {
+        let Some(record) = event.change.record() else {
+            continue;
+        };
+        let line = SceneChange::from_change(&record, &|entity| ids.id(entity), &registry)
+            .and_then(|change| ron::to_string(&change).map_err(|e| e.to_string()));
+        match line {
+            Ok(line) => server.broadcast_authenticated(&line),
+            Err(err) => {
+                warn!(
+                    "Failed to send `{}` to live link: {}",
+                    event.change.debug_text(),
+                    err
+                );
+            }
+        }
+    }
+    server.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use space_prefab::{editor_registry::EditorRegistryPlugin, persistent_id::PersistentIdPlugin};
+    use space_shared::local_socket::LocalClient;
+    use space_undo::AddedEntity;
+
+    use super::*;
+
+    #[test]
+    fn sends_committed_changes_to_game() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EditorRegistryPlugin, PersistentIdPlugin))
+            .add_event::<ChangeCommitted>()
+            .add_event::<SceneLoaded>()
+            .add_event::<ToastMessage>()
+            .insert_resource(LiveLinkSettings {
+                enabled: true,
+                port: 0,
+            })
+            .init_resource::<LiveLinkServer>()
+            .add_systems(PostUpdate, send_live_link);
+        let entity = app.world_mut().spawn(PrefabMarker).id();
+        app.update();
+        let id = app.world().get::<PersistentId>(entity).unwrap().0;
+        let port = app
+            .world()
+            .resource::<LiveLinkServer>()
+            .server
+            .local_addr
+            .unwrap()
+            .port();
+
+        let token = app.world().resource::<LiveLinkServer>().token.clone();
+        let mut client = LocalClient::with_handshake(token);
+        assert!(client.read_lines(port).is_some());
+        let mut stranger = LocalClient::with_handshake("wrong".to_string());
+        assert!(stranger.read_lines(port).is_some());
+        for _ in 0..100 {
+            app.update();
+            let server = &app.world().resource::<LiveLinkServer>().server;
+            // Connection with wrong token is closed
+            if server.authenticated_connections() > 0 && server.connections() == 1 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            app.world()
+                .resource::<LiveLinkServer>()
+                .server
+                .authenticated_connections(),
+            1
+        );
+
+        app.world_mut().send_event(ChangeCommitted {
+            change: Arc::new(AddedEntity { entity }),
+        });
+        app.update();
+        let mut lines = vec![];
+        for _ in 0..100 {
+            lines.extend(client.read_lines(port).unwrap());
+            if !lines.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(lines, vec![format!("AddedEntity({})", id)]);
+        assert!(stranger.read_lines(port).unwrap_or_default().is_empty());
+    }
+}
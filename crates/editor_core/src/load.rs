@@ -1,7 +1,65 @@
-use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use bevy::{asset::LoadState, ecs::entity::EntityHashMap, prelude::*};
+use space_prefab::partial_load::{
+    check_scene, parse_partial_scene, LoadIssue, OpaqueComponents, PartialScene,
+};
 use space_shared::{toast::ToastMessage, *};
 
-use crate::EditorLoader;
+use crate::{
+    task_storage::{BackgroundTaskFinished, BackgroundTaskResult},
+    EditorLoader,
+};
+
+/// Sent after editor scene was written to world
+#[derive(Event)]
+pub struct SceneLoaded;
+
+/// Components and resources which were not loaded with last scene
+#[derive(Resource, Default, Clone, Debug)]
+pub struct LoadReport {
+    /// Asset path of scene, `None` for memory cache
+    pub scene: Option<String>,
+    /// Issues with entities of world instead of scene entities
+    pub issues: Vec<LoadIssue>,
+    /// Report dialog is shown
+    pub open: bool,
+}
+
+impl LoadReport {
+    /// One line per issue
+    pub fn text(&self) -> String {
+        self.issues
+            .iter()
+            .map(|issue| match issue.entity {
+                Some(entity) => format!("{:?} {}: {}", entity, issue.type_path, issue.kind),
+                None => format!("Resource {}: {}", issue.type_path, issue.kind),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Scene asset fails to load on any unknown component, in this case scene file is loaded partially
+fn load_partial_file(path: &str, registry: &AppTypeRegistry) -> Result<PartialScene, String> {
+    let text = std::fs::read_to_string(asset_file_path(path)).map_err(|err| err.to_string())?;
+    parse_partial_scene(&text, &registry.read())
+}
+
+/// Scene which loading was cancelled in background tasks is not spawned
+pub fn cancel_scene_loading(
+    mut finished: EventReader<BackgroundTaskFinished>,
+    mut loader: ResMut<EditorLoader>,
+) {
+    for event in finished.read() {
+        let cancelled = event.result == BackgroundTaskResult::Cancelled
+            && event.asset.is_some()
+            && event.asset == loader.scene.as_ref().map(|scene| scene.id().untyped());
+        if cancelled {
+            info!("Scene loading is cancelled");
+            loader.scene = None;
+            loader.path = None;
+        }
+    }
+}
 
 pub fn load_listener(world: &mut World) {
     // AppTypeRegistry and are injected in Startup
@@ -10,23 +68,43 @@ pub fn load_listener(world: &mut World) {
         error!("Failed to get Editor Loader");
         return;
     };
-    let mut prefab;
+    let Some(handle) = load_server.scene.clone() else {
+        return;
+    };
+    let loaded = if let Some(scene) = world.resource::<Assets<DynamicScene>>().get(&handle) {
+        Ok(check_scene(scene, &app_registry.read()))
+    } else if let Some(LoadState::Failed(err)) = world
+        .get_resource::<AssetServer>()
+        .and_then(|assets| assets.get_load_state(&handle))
     {
-        let assets = world.resource::<Assets<DynamicScene>>();
-        if let Some(scene) = &load_server.scene {
-            if let Some(scene) = assets.get(scene) {
-                let Ok(mut scene) = Scene::from_dynamic_scene(scene, &app_registry) else {
-                    return;
-                };
-                scene.world.insert_resource(app_registry);
-                prefab = DynamicScene::from_scene(&scene); //kill me, is it clone() analog for DynamicScene
-            } else {
-                return;
+        match &load_server.path {
+            Some(path) => load_partial_file(path, &app_registry)
+                .map_err(|partial_err| format!("{}\n{}", err, partial_err)),
+            None => Err(err.to_string()),
+        }
+    } else {
+        return;
+    };
+    let PartialScene {
+        scene: mut prefab,
+        opaque,
+        mut issues,
+    } = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            if let Some(mut editor_loader) = world.get_resource_mut::<EditorLoader>() {
+                editor_loader.scene = None;
+                editor_loader.path = None;
             }
-        } else {
+            world.send_event(
+                ToastMessage::new("Failed to load scene", egui_toast::ToastKind::Error)
+                    .with_details(err.clone()),
+            );
+            error!("Failed to load scene: {}", err);
             return;
         }
-    }
+    };
+
     let Some(mut editor_loader) = world.get_resource_mut::<EditorLoader>() else {
         world.send_event(ToastMessage::new(
             "Failed to get prefab loader",
@@ -35,6 +113,7 @@ pub fn load_listener(world: &mut World) {
         return;
     };
     editor_loader.scene = None;
+    editor_loader.path = None;
 
     let mut query = world.query_filtered::<(Entity, Option<&Name>), With<PrefabMarker>>();
     let mark_to_delete: Vec<_> = query
@@ -72,16 +151,64 @@ pub fn load_listener(world: &mut World) {
     let res = prefab.write_to_world(world, &mut map);
     match res {
         Ok(_) => {
-            world.send_event(ToastMessage::new(
-                "Prefab loaded successfully",
-                egui_toast::ToastKind::Success,
-            ));
+            // Scenes saved before persistent ids existed get ids derived from scene entities,
+            // so the same scene always gets the same ids
+            for (scene_entity, entity) in map.iter() {
+                if let Some(mut entity) = world.get_entity_mut(*entity) {
+                    if !entity.contains::<PersistentId>() {
+                        entity.insert(PersistentId(scene_entity.to_bits()));
+                    }
+                }
+            }
+            for (scene_entity, components) in opaque {
+                let Some(mut entity) = map
+                    .get(&scene_entity)
+                    .and_then(|entity| world.get_entity_mut(*entity))
+                else {
+                    continue;
+                };
+                match entity.get_mut::<OpaqueComponents>() {
+                    Some(mut opaque) => opaque.0.extend(components),
+                    None => {
+                        entity.insert(OpaqueComponents(components));
+                    }
+                }
+            }
+            for issue in issues.iter_mut() {
+                issue.entity = issue.entity.and_then(|e| map.get(&e).copied());
+            }
+
+            world.send_event(SceneLoaded);
+            let report = LoadReport {
+                scene: load_server.path.clone(),
+                open: !issues.is_empty(),
+                issues,
+            };
+            if report.issues.is_empty() {
+                world.send_event(ToastMessage::new(
+                    "Prefab loaded successfully",
+                    egui_toast::ToastKind::Success,
+                ));
+            } else {
+                warn!("Scene loaded with {} issues", report.issues.len());
+                world.send_event(
+                    ToastMessage::new(
+                        &format!("Scene loaded with {} issues", report.issues.len()),
+                        egui_toast::ToastKind::Warning,
+                    )
+                    .with_details(report.text()),
+                );
+            }
+            world.insert_resource(report);
         }
         Err(err) => {
-            world.send_event(ToastMessage::new(
-                &format!("Failed to create scene:\n{err}"),
-                egui_toast::ToastKind::Error,
-            ));
+            world.send_event(
+                ToastMessage::new(
+                    &format!("Failed to create scene: {err}"),
+                    egui_toast::ToastKind::Error,
+                )
+                .with_details(err.to_string()),
+            );
             bevy::log::error!("{}", err)
         }
     }
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 
 #[cfg(not(feature = "bevy_mod_outline"))]
-use bevy::pbr::wireframe::{Wireframe, WireframePlugin};
+use bevy::pbr::wireframe::{Wireframe, WireframeColor, WireframePlugin};
 
 use space_shared::{EditorSet, EditorState};
 
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
+
 #[cfg(feature = "bevy_mod_outline")]
 use bevy_mod_outline::{OutlineBundle, OutlinePlugin, OutlineVolume};
 
@@ -12,6 +15,49 @@ use bevy_mod_outline::{OutlineBundle, OutlinePlugin, OutlineVolume};
 #[derive(Component, Default, Clone)]
 pub struct Selected;
 
+/// A marker for the most recently selected entity. Kept while entity stays selected
+#[derive(Component, Default, Clone)]
+pub struct PrimarySelected;
+
+/// A marker for entity under cursor in editor viewport
+#[derive(Component, Default, Clone)]
+pub struct Hovered;
+
+/// Colors and widths of selection and hover outlines
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource, Default)]
+pub struct SelectionHighlightSettings {
+    pub primary_color: Color,
+    pub secondary_color: Color,
+    pub hover_color: Color,
+    /// Outline width of selected entities
+    pub width: f32,
+    pub hover_width: f32,
+}
+
+impl Default for SelectionHighlightSettings {
+    fn default() -> Self {
+        Self {
+            primary_color: Color::srgb(1.0, 1.0, 0.0),
+            secondary_color: Color::srgb(1.0, 0.55, 0.0),
+            hover_color: Color::srgb(0.4, 0.8, 1.0),
+            width: 2.0,
+            hover_width: 1.0,
+        }
+    }
+}
+
+impl SelectionHighlightSettings {
+    /// Color and width of highlight. Selection highlight is shown over hover highlight
+    pub const fn highlight(&self, selected: bool, primary: bool) -> (Color, f32) {
+        match (selected, primary) {
+            (true, true) => (self.primary_color, self.width),
+            (true, false) => (self.secondary_color, self.width),
+            _ => (self.hover_color, self.hover_width),
+        }
+    }
+}
+
 /// Selection system plugins
 pub struct SelectedPlugin;
 
@@ -29,56 +75,132 @@ impl Plugin for SelectedPlugin {
                 app.add_plugins(OutlinePlugin);
             }
         }
+        app.init_resource::<SelectionHighlightSettings>()
+            .register_type::<SelectionHighlightSettings>();
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<SelectionHighlightSettings>()
+            .persistence_layer::<SelectionHighlightSettings>(PersistenceLayer::User);
+
         app.add_systems(
             Update,
-            selected_entity_wireframe_update.in_set(EditorSet::Editor),
+            (update_primary_selection, selected_entity_wireframe_update)
+                .chain()
+                .in_set(EditorSet::Editor),
+        );
+        app.add_systems(
+            OnEnter(EditorState::GamePrepare),
+            (clear_wireframes, clear_hovered),
         );
-        app.add_systems(OnEnter(EditorState::GamePrepare), clear_wireframes);
+    }
+}
+
+/// Keep [`PrimarySelected`] on the last selected entity
+fn update_primary_selection(
+    mut cmds: Commands,
+    added: Query<Entity, Added<Selected>>,
+    primary: Query<(Entity, Has<Selected>), With<PrimarySelected>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let new_primary = added.iter().max();
+    let mut has_primary = false;
+    for (entity, is_selected) in primary.iter() {
+        if is_selected && new_primary.is_none() {
+            has_primary = true;
+        } else {
+            cmds.entity(entity).remove::<PrimarySelected>();
+        }
+    }
+    if has_primary {
+        return;
+    }
+    if let Some(entity) = new_primary.or_else(|| selected.iter().next()) {
+        cmds.entity(entity).insert(PrimarySelected);
+    }
+}
+
+fn clear_hovered(mut cmds: Commands, hovered: Query<Entity, With<Hovered>>) {
+    for e in hovered.iter() {
+        cmds.entity(e).remove::<Hovered>();
     }
 }
 
 #[cfg(not(feature = "bevy_mod_outline"))]
 fn selected_entity_wireframe_update(
     mut cmds: Commands,
-    del_wireframe: Query<Entity, (With<Wireframe>, Without<Selected>)>,
-    need_wireframe: Query<Entity, (Without<Wireframe>, With<Selected>)>,
+    settings: Option<Res<SelectionHighlightSettings>>,
+    del_wireframe: Query<Entity, (With<Wireframe>, Without<Selected>, Without<Hovered>)>,
+    need_wireframe: Query<
+        (
+            Entity,
+            Has<Selected>,
+            Has<PrimarySelected>,
+            Option<&WireframeColor>,
+        ),
+        Or<(With<Selected>, With<Hovered>)>,
+    >,
 ) {
     for e in del_wireframe.iter() {
-        cmds.entity(e).remove::<Wireframe>();
+        cmds.entity(e).remove::<(Wireframe, WireframeColor)>();
     }
 
-    for e in need_wireframe.iter() {
-        cmds.entity(e).insert(Wireframe);
+    let settings = settings.as_deref().cloned().unwrap_or_default();
+    for (e, selected, primary, wireframe_color) in need_wireframe.iter() {
+        // Wireframe has no width
+        let (color, _) = settings.highlight(selected, primary);
+        if wireframe_color.map(|c| c.color) != Some(color) {
+            cmds.entity(e).insert((Wireframe, WireframeColor { color }));
+        }
     }
 }
 
 #[cfg(not(feature = "bevy_mod_outline"))]
 fn clear_wireframes(mut cmds: Commands, del_wireframe: Query<Entity, With<Wireframe>>) {
     for e in del_wireframe.iter() {
-        cmds.entity(e).remove::<Wireframe>();
+        cmds.entity(e).remove::<(Wireframe, WireframeColor)>();
     }
 }
 
 #[cfg(feature = "bevy_mod_outline")]
 fn selected_entity_wireframe_update(
     mut cmds: Commands,
-    del_wireframe: Query<Entity, (With<OutlineVolume>, Without<Selected>)>,
-    need_wireframe: Query<Entity, (Without<OutlineVolume>, With<Selected>)>,
+    settings: Option<Res<SelectionHighlightSettings>>,
+    del_wireframe: Query<Entity, (With<OutlineVolume>, Without<Selected>, Without<Hovered>)>,
+    mut need_wireframe: Query<
+        (
+            Entity,
+            Has<Selected>,
+            Has<PrimarySelected>,
+            Option<&mut OutlineVolume>,
+        ),
+        Or<(With<Selected>, With<Hovered>)>,
+    >,
 ) {
     for e in del_wireframe.iter() {
         cmds.entity(e).remove::<OutlineBundle>();
     }
 
-    for e in need_wireframe.iter() {
-        cmds.entity(e).insert(OutlineBundle {
-            outline: OutlineVolume {
-                visible: true,
-                colour: Color::srgb(1.0, 1.0, 0.0),
-                width: 2.0,
-            },
-            mode: bevy_mod_outline::OutlineMode::RealVertex,
-            ..Default::default()
-        });
+    let settings = settings.as_deref().cloned().unwrap_or_default();
+    for (e, selected, primary, outline) in need_wireframe.iter_mut() {
+        let (colour, width) = settings.highlight(selected, primary);
+        match outline {
+            Some(mut outline) => {
+                if outline.colour != colour || outline.width != width {
+                    outline.colour = colour;
+                    outline.width = width;
+                }
+            }
+            None => {
+                cmds.entity(e).insert(OutlineBundle {
+                    outline: OutlineVolume {
+                        visible: true,
+                        colour,
+                        width,
+                    },
+                    mode: bevy_mod_outline::OutlineMode::RealVertex,
+                    ..Default::default()
+                });
+            }
+        }
     }
 }
 
@@ -105,8 +227,8 @@ mod tests {
         });
         app.update();
 
-        let mut query = app.world.query_filtered::<Entity, With<Wireframe>>();
-        assert_eq!(0, query.iter(&app.world).count());
+        let mut query = app.world_mut().query_filtered::<Entity, With<Wireframe>>();
+        assert_eq!(0, query.iter(app.world()).count());
     }
 
     #[test]
@@ -120,8 +242,8 @@ mod tests {
         });
         app.update();
 
-        let mut query = app.world.query_filtered::<Entity, With<Wireframe>>();
-        assert_eq!(0, query.iter(&app.world).count());
+        let mut query = app.world_mut().query_filtered::<Entity, With<Wireframe>>();
+        assert_eq!(0, query.iter(app.world()).count());
     }
 
     #[test]
@@ -136,8 +258,59 @@ mod tests {
         app.update();
 
         let mut query = app
-            .world
+            .world_mut()
             .query_filtered::<Entity, (With<Wireframe>, With<Selected>)>();
-        assert_eq!(2, query.iter(&app.world).count());
+        assert_eq!(2, query.iter(app.world()).count());
+    }
+
+    #[test]
+    fn primary_selection_follows_last_selected() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_systems(Update, update_primary_selection);
+
+        let first = app.world_mut().spawn(Selected).id();
+        app.update();
+        assert!(app.world().get::<PrimarySelected>(first).is_some());
+
+        let second = app.world_mut().spawn(Selected).id();
+        app.update();
+        assert!(app.world().get::<PrimarySelected>(first).is_none());
+        assert!(app.world().get::<PrimarySelected>(second).is_some());
+
+        app.world_mut().entity_mut(second).remove::<Selected>();
+        app.update();
+        assert!(app.world().get::<PrimarySelected>(first).is_some());
+        assert!(app.world().get::<PrimarySelected>(second).is_none());
+    }
+
+    #[test]
+    fn highlight_color_depends_on_selection_and_hover() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<SelectionHighlightSettings>();
+        app.add_systems(
+            Update,
+            (update_primary_selection, selected_entity_wireframe_update).chain(),
+        );
+
+        let hovered = app.world_mut().spawn(Hovered).id();
+        let secondary = app.world_mut().spawn(Selected).id();
+        let primary = app.world_mut().spawn(Selected).id();
+        app.update();
+
+        let settings = SelectionHighlightSettings::default();
+        let color = |app: &App, entity| {
+            app.world()
+                .get::<WireframeColor>(entity)
+                .map(|wireframe| wireframe.color)
+        };
+        assert_eq!(color(&app, primary), Some(settings.primary_color));
+        assert_eq!(color(&app, secondary), Some(settings.secondary_color));
+        assert_eq!(color(&app, hovered), Some(settings.hover_color));
+
+        app.world_mut().entity_mut(hovered).remove::<Hovered>();
+        app.update();
+        assert!(app.world().get::<Wireframe>(hovered).is_none());
     }
 }
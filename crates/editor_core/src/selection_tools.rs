@@ -0,0 +1,571 @@
+use bevy::{
+    ecs::{component::ComponentId, event::ManualEventReader},
+    prelude::*,
+    utils::HashSet,
+};
+use space_prefab::{component::GltfPrefab, load::PrefabLoader};
+use space_shared::{EditorSet, PrefabMarker, SelectionSets};
+
+use crate::{
+    commands::EditorCommandAppExt,
+    hotkeys::{Hotkey, HotkeyAppExt, HotkeyContext},
+    selected::Selected,
+};
+
+/// Named selection sets, selection history and commands which change selection
+pub struct SelectionToolsPlugin;
+
+impl Plugin for SelectionToolsPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectionHistory>();
+        app.add_event::<SelectionAction>();
+
+        app.add_systems(
+            Update,
+            (send_selection_hotkey_actions, apply_selection_actions)
+                .chain()
+                .in_set(EditorSet::Editor),
+        );
+        // Selection is changed by picking and hierarchy up to PostUpdate
+        app.add_systems(Last, record_selection_history);
+
+        for (hotkey, binding) in [
+            (
+                SelectionHotkey::Back,
+                vec![KeyCode::AltLeft, KeyCode::ArrowLeft],
+            ),
+            (
+                SelectionHotkey::Forward,
+                vec![KeyCode::AltLeft, KeyCode::ArrowRight],
+            ),
+            (
+                SelectionHotkey::Invert,
+                vec![KeyCode::ControlLeft, KeyCode::KeyI],
+            ),
+            (SelectionHotkey::SelectChildren, vec![KeyCode::BracketRight]),
+            (SelectionHotkey::SelectParent, vec![KeyCode::BracketLeft]),
+        ] {
+            app.editor_hotkey_in_context(hotkey, binding, HotkeyContext::Viewport);
+        }
+
+        app.editor_command(
+            "selection.back",
+            "Previous selection",
+            "Selection",
+            |world| {
+                world.send_event(SelectionAction::Back);
+            },
+        )
+        .editor_command(
+            "selection.forward",
+            "Next selection",
+            "Selection",
+            |world| {
+                world.send_event(SelectionAction::Forward);
+            },
+        )
+        .editor_command(
+            "selection.invert",
+            "Invert selection",
+            "Selection",
+            |world| {
+                world.send_event(SelectionAction::Invert);
+            },
+        )
+        .editor_command(
+            "selection.children",
+            "Select children",
+            "Selection",
+            |world| {
+                world.send_event(SelectionAction::SelectChildren);
+            },
+        )
+        .editor_command("selection.parent", "Select parent", "Selection", |world| {
+            world.send_event(SelectionAction::SelectParent);
+        })
+        .editor_command(
+            "selection.same_prefab_source",
+            "Select same prefab source",
+            "Selection",
+            |world| {
+                world.send_event(SelectionAction::SamePrefabSource);
+            },
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum SelectionHotkey {
+    Back,
+    Forward,
+    Invert,
+    SelectChildren,
+    SelectParent,
+}
+
+impl Hotkey for SelectionHotkey {
+    fn name(&self) -> String {
+        match self {
+            Self::Back => "Previous selection".to_string(),
+            Self::Forward => "Next selection".to_string(),
+            Self::Invert => "Invert selection".to_string(),
+            Self::SelectChildren => "Select children".to_string(),
+            Self::SelectParent => "Select parent".to_string(),
+        }
+    }
+}
+
+/// Event to change selection of prefab entities
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub enum SelectionAction {
+    /// Replace selection with given entities
+    Select(Vec<Entity>),
+    /// Restore previous selection from [`SelectionHistory`]
+    Back,
+    /// Restore next selection from [`SelectionHistory`]
+    Forward,
+    /// Store current selection as named set. Set with the same name is replaced
+    SaveSet(String),
+    SelectSet(String),
+    RemoveSet(String),
+    /// Select all entities which have component
+    SameComponent(ComponentId),
+    /// Select all entities spawned from the same prefab or gltf file as selected entities
+    SamePrefabSource,
+    SelectChildren,
+    SelectParent,
+    Invert,
+}
+
+/// Previous selections which can be restored with [`SelectionAction::Back`] and [`SelectionAction::Forward`]
+#[derive(Resource)]
+pub struct SelectionHistory {
+    entries: Vec<Vec<Entity>>,
+    cursor: usize,
+    /// Max count of stored selections
+    pub limit: usize,
+}
+
+impl Default for SelectionHistory {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            cursor: 0,
+            limit: 100,
+        }
+    }
+}
+
+impl SelectionHistory {
+    pub const fn can_go_back(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub const fn can_go_forward(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+
+    /// Store selection as newest entry. Selections after current entry are dropped
+    pub fn record(&mut self, mut selection: Vec<Entity>) {
+        selection.sort();
+        if selection.is_empty() || self.entries.get(self.cursor) == Some(&selection) {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.cursor + 1);
+        }
+        self.entries.push(selection);
+        if self.entries.len() > self.limit {
+            self.entries.remove(0);
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+
+    /// Move to previous selection which still has existing entities
+    pub fn back(&mut self, exists: impl Fn(Entity) -> bool) -> Option<Vec<Entity>> {
+        while self.can_go_back() {
+            self.cursor -= 1;
+            if let Some(selection) = self.current_existing(&exists) {
+                return Some(selection);
+            }
+        }
+        None
+    }
+
+    /// Move to next selection which still has existing entities
+    pub fn forward(&mut self, exists: impl Fn(Entity) -> bool) -> Option<Vec<Entity>> {
+        while self.can_go_forward() {
+            self.cursor += 1;
+            if let Some(selection) = self.current_existing(&exists) {
+                return Some(selection);
+            }
+        }
+        None
+    }
+
+    fn current_existing(&mut self, exists: &impl Fn(Entity) -> bool) -> Option<Vec<Entity>> {
+        let entry = self.entries.get_mut(self.cursor)?;
+        // Despawned entities are dropped, so restored selection is equal to entry
+        entry.retain(|entity| exists(*entity));
+        (!entry.is_empty()).then(|| entry.clone())
+    }
+}
+
+/// Sorted names of all selection sets in scene
+pub fn selection_set_names<'a>(sets: impl Iterator<Item = &'a SelectionSets>) -> Vec<String> {
+    let mut names = sets
+        .flat_map(|sets| sets.0.iter().cloned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Path of prefab or gltf file which entity is spawned from
+pub fn prefab_source(entity: &EntityRef) -> Option<String> {
+    if let Some(loader) = entity.get::<PrefabLoader>() {
+        return Some(loader.path.clone());
+    }
+    entity
+        .get::<GltfPrefab>()
+        .map(|gltf| format!("{}#{}", gltf.path, gltf.scene))
+}
+
+fn send_selection_hotkey_actions(
+    hotkeys: Res<ButtonInput<SelectionHotkey>>,
+    mut actions: EventWriter<SelectionAction>,
+) {
+    for hotkey in hotkeys.get_just_pressed() {
+        actions.send(match hotkey {
+            SelectionHotkey::Back => SelectionAction::Back,
+            SelectionHotkey::Forward => SelectionAction::Forward,
+            SelectionHotkey::Invert => SelectionAction::Invert,
+            SelectionHotkey::SelectChildren => SelectionAction::SelectChildren,
+            SelectionHotkey::SelectParent => SelectionAction::SelectParent,
+        });
+    }
+}
+
+fn apply_selection_actions(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<SelectionAction>>,
+) {
+    let actions = reader
+        .read(world.resource::<Events<SelectionAction>>())
+        .cloned()
+        .collect::<Vec<_>>();
+    for action in actions {
+        apply_selection_action(world, &action);
+    }
+}
+
+/// Change selection of prefab entities
+pub fn apply_selection_action(world: &mut World, action: &SelectionAction) {
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<HashSet<_>>();
+    let mut prefabs = world.query_filtered::<EntityRef, With<PrefabMarker>>();
+
+    let target = match action {
+        SelectionAction::Select(entities) => {
+            let entities = entities
+                .iter()
+                .copied()
+                .filter(|entity| world.get_entity(*entity).is_some())
+                .collect::<Vec<_>>();
+            select_only(world, &selected, &entities);
+            None
+        }
+        SelectionAction::Back | SelectionAction::Forward => {
+            let exists = world
+                .query_filtered::<Entity, With<PrefabMarker>>()
+                .iter(world)
+                .collect::<HashSet<_>>();
+            let exists = |entity| exists.contains(&entity);
+            let mut history = world.resource_mut::<SelectionHistory>();
+            if *action == SelectionAction::Back {
+                history.back(exists)
+            } else {
+                history.forward(exists)
+            }
+        }
+        SelectionAction::SaveSet(name) => {
+            if selected.is_empty() {
+                warn!("Selection set {} is not saved: nothing is selected", name);
+            } else {
+                update_selection_set(world, name, |entity| selected.contains(&entity));
+            }
+            None
+        }
+        SelectionAction::SelectSet(name) => Some(
+            prefabs
+                .iter(world)
+                .filter(|entity| {
+                    entity
+                        .get::<SelectionSets>()
+                        .is_some_and(|sets| sets.0.contains(name))
+                })
+                .map(|entity| entity.id())
+                .collect(),
+        ),
+        SelectionAction::RemoveSet(name) => {
+            update_selection_set(world, name, |_| false);
+            None
+        }
+        SelectionAction::SameComponent(id) => Some(
+            prefabs
+                .iter(world)
+                .filter(|entity| entity.contains_id(*id))
+                .map(|entity| entity.id())
+                .collect(),
+        ),
+        SelectionAction::SamePrefabSource => {
+            let sources = prefabs
+                .iter(world)
+                .filter(|entity| selected.contains(&entity.id()))
+                .filter_map(|entity| prefab_source(&entity))
+                .collect::<HashSet<_>>();
+            Some(
+                prefabs
+                    .iter(world)
+                    .filter(|entity| {
+                        prefab_source(entity).is_some_and(|source| sources.contains(&source))
+                    })
+                    .map(|entity| entity.id())
+                    .collect(),
+            )
+        }
+        SelectionAction::SelectChildren => Some(
+            prefabs
+                .iter(world)
+                .filter(|entity| {
+                    entity
+                        .get::<Parent>()
+                        .is_some_and(|parent| selected.contains(&parent.get()))
+                })
+                .map(|entity| entity.id())
+                .collect(),
+        ),
+        SelectionAction::SelectParent => {
+            let parents = prefabs
+                .iter(world)
+                .filter(|entity| selected.contains(&entity.id()))
+                .filter_map(|entity| entity.get::<Parent>().map(Parent::get))
+                .collect::<HashSet<_>>();
+            Some(
+                prefabs
+                    .iter(world)
+                    .map(|entity| entity.id())
+                    .filter(|entity| parents.contains(entity))
+                    .collect(),
+            )
+        }
+        SelectionAction::Invert => {
+            let inverted = prefabs
+                .iter(world)
+                .map(|entity| entity.id())
+                .filter(|entity| !selected.contains(entity))
+                .collect::<Vec<_>>();
+            select_only(world, &selected, &inverted);
+            None
+        }
+    };
+
+    // Selection is kept if there is nothing to select
+    if let Some(target) = target.filter(|target| !target.is_empty()) {
+        select_only(world, &selected, &target);
+    }
+}
+
+fn select_only(world: &mut World, selected: &HashSet<Entity>, target: &[Entity]) {
+    for entity in selected.iter().filter(|entity| !target.contains(entity)) {
+        world.entity_mut(*entity).remove::<Selected>();
+    }
+    for entity in target.iter().filter(|entity| !selected.contains(*entity)) {
+        world.entity_mut(*entity).insert(Selected);
+    }
+}
+
+/// Make entities for which `is_member` is true the only members of set
+fn update_selection_set(world: &mut World, name: &str, is_member: impl Fn(Entity) -> bool) {
+    let mut query = world.query_filtered::<(Entity, Option<&SelectionSets>), With<PrefabMarker>>();
+    let changed = query
+        .iter(world)
+        .filter_map(|(entity, sets)| {
+            let mut sets = sets.cloned().unwrap_or_default();
+            let was_member = sets.0.iter().any(|set| set == name);
+            match (was_member, is_member(entity)) {
+                (false, true) => sets.0.push(name.to_string()),
+                (true, false) => sets.0.retain(|set| set != name),
+                _ => return None,
+            }
+            Some((entity, sets))
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, sets) in changed {
+        if sets.0.is_empty() {
+            world.entity_mut(entity).remove::<SelectionSets>();
+        } else {
+            world.entity_mut(entity).insert(sets);
+        }
+    }
+}
+
+fn record_selection_history(
+    mut history: ResMut<SelectionHistory>,
+    added: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if added.is_empty() && !removed_any {
+        return;
+    }
+    history.record(selected.iter().collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use super::*;
+
+    fn configure_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, SelectionToolsPlugin));
+        app
+    }
+
+    fn select(app: &mut App, entities: &[Entity]) {
+        send(app, SelectionAction::Select(entities.to_vec()));
+    }
+
+    fn selection(app: &mut App) -> Vec<Entity> {
+        let mut selection = app
+            .world_mut()
+            .query_filtered::<Entity, With<Selected>>()
+            .iter(app.world())
+            .collect::<Vec<_>>();
+        selection.sort();
+        selection
+    }
+
+    fn send(app: &mut App, action: SelectionAction) {
+        app.world_mut().send_event(action);
+        app.update();
+    }
+
+    #[test]
+    fn history_goes_back_and_forward() {
+        let mut app = configure_app();
+        let a = app.world_mut().spawn(PrefabMarker).id();
+        let b = app.world_mut().spawn(PrefabMarker).id();
+        let c = app.world_mut().spawn(PrefabMarker).id();
+
+        select(&mut app, &[a]);
+        select(&mut app, &[b]);
+        select(&mut app, &[c]);
+
+        send(&mut app, SelectionAction::Back);
+        assert_eq!(selection(&mut app), vec![b]);
+        send(&mut app, SelectionAction::Back);
+        assert_eq!(selection(&mut app), vec![a]);
+        send(&mut app, SelectionAction::Forward);
+        assert_eq!(selection(&mut app), vec![b]);
+
+        // New selection drops forward history
+        select(&mut app, &[a, c]);
+        assert!(!app.world().resource::<SelectionHistory>().can_go_forward());
+
+        // Despawned entities are skipped
+        app.world_mut().despawn(b);
+        send(&mut app, SelectionAction::Back);
+        assert_eq!(selection(&mut app), vec![a]);
+    }
+
+    #[test]
+    fn selection_sets_are_saved_selected_and_removed() {
+        let mut app = configure_app();
+        let a = app.world_mut().spawn(PrefabMarker).id();
+        let b = app.world_mut().spawn(PrefabMarker).id();
+        let c = app
+            .world_mut()
+            .spawn((PrefabMarker, SelectionSets(vec!["Lights".to_string()])))
+            .id();
+
+        select(&mut app, &[a, b]);
+        send(&mut app, SelectionAction::SaveSet("Lights".to_string()));
+        assert!(app.world().get::<SelectionSets>(c).is_none());
+        assert_eq!(
+            app.world().get::<SelectionSets>(a),
+            Some(&SelectionSets(vec!["Lights".to_string()]))
+        );
+
+        select(&mut app, &[c]);
+        send(&mut app, SelectionAction::SelectSet("Lights".to_string()));
+        assert_eq!(selection(&mut app), vec![a, b]);
+
+        let mut sets = app.world_mut().query::<&SelectionSets>();
+        assert_eq!(
+            selection_set_names(sets.iter(app.world())),
+            vec!["Lights".to_string()]
+        );
+
+        send(&mut app, SelectionAction::RemoveSet("Lights".to_string()));
+        assert_eq!(sets.iter(app.world()).count(), 0);
+    }
+
+    #[test]
+    fn select_relatives_invert_and_same_source() {
+        let mut app = configure_app();
+        let parent = app.world_mut().spawn(PrefabMarker).id();
+        let child = app
+            .world_mut()
+            .spawn((
+                PrefabMarker,
+                PrefabLoader {
+                    path: "tree.scn.ron".to_string(),
+                },
+            ))
+            .set_parent(parent)
+            .id();
+        let other = app
+            .world_mut()
+            .spawn((
+                PrefabMarker,
+                PrefabLoader {
+                    path: "tree.scn.ron".to_string(),
+                },
+            ))
+            .id();
+
+        select(&mut app, &[parent]);
+        send(&mut app, SelectionAction::SelectChildren);
+        assert_eq!(selection(&mut app), vec![child]);
+
+        send(&mut app, SelectionAction::SamePrefabSource);
+        assert_eq!(selection(&mut app), vec![child, other]);
+
+        send(&mut app, SelectionAction::Invert);
+        assert_eq!(selection(&mut app), vec![parent]);
+
+        // Root entity has no parent, so selection is kept
+        send(&mut app, SelectionAction::SelectParent);
+        assert_eq!(selection(&mut app), vec![parent]);
+
+        select(&mut app, &[child]);
+        send(&mut app, SelectionAction::SelectParent);
+        assert_eq!(selection(&mut app), vec![parent]);
+
+        let loader = app.world().component_id::<PrefabLoader>().unwrap();
+        send(&mut app, SelectionAction::SameComponent(loader));
+        assert_eq!(selection(&mut app), vec![child, other]);
+
+        // Empty selection clears it
+        select(&mut app, &[]);
+        assert!(selection(&mut app).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use space_undo::AppAutoUndo;
+
+#[cfg(feature = "persistence_editor")]
+use bevy::ecs::event::ManualEventReader;
+#[cfg(feature = "persistence_editor")]
+use space_persistence::PersistenceLoaded;
+#[cfg(feature = "persistence_editor")]
+use space_undo::{AutoUndoResourceStorage, UndoSet};
+
+pub trait AppSettingsUndo {
+    /// Track changes of settings resource like [`AppAutoUndo::auto_resource_undo`].
+    /// Values loaded from disk by persistence are not undoable changes
+    fn settings_undo<R: Resource + Reflect + FromReflect>(&mut self) -> &mut Self;
+}
+
+impl AppSettingsUndo for App {
+    fn settings_undo<R: Resource + Reflect + FromReflect>(&mut self) -> &mut Self {
+        self.auto_resource_undo::<R>();
+
+        #[cfg(feature = "persistence_editor")]
+        if self
+            .world()
+            .contains_resource::<AutoUndoResourceStorage<R>>()
+        {
+            self.add_systems(
+                PostUpdate,
+                reset_resource_undo_on_persistence_load::<R>.before(UndoSet::PerType),
+            );
+        }
+
+        self
+    }
+}
+
+#[cfg(feature = "persistence_editor")]
+fn reset_resource_undo_on_persistence_load<R: Resource>(
+    mut reader: Local<ManualEventReader<PersistenceLoaded<R>>>,
+    events: Option<Res<Events<PersistenceLoaded<R>>>>,
+    mut storage: ResMut<AutoUndoResourceStorage<R>>,
+) {
+    let Some(events) = events else {
+        return;
+    };
+    if reader.read(&events).next().is_some() {
+        storage.reset();
+    }
+}
+
+#[cfg(all(test, feature = "persistence_editor"))]
+mod tests {
+    use space_undo::{ChangeChain, UndoPlugin, UndoRedo};
+
+    use super::*;
+
+    #[derive(Resource, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Resource, Default)]
+    struct RecentFiles {
+        files: Vec<String>,
+    }
+
+    fn repeat_update(app: &mut App, times: usize) {
+        for _ in 0..times {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn resource_loaded_by_persistence_is_not_undoable() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UndoPlugin))
+            .init_resource::<RecentFiles>()
+            .add_event::<PersistenceLoaded<RecentFiles>>()
+            .settings_undo::<RecentFiles>();
+        repeat_update(&mut app, 10);
+
+        app.world_mut().resource_mut::<RecentFiles>().files = vec!["loaded".to_string()];
+        app.world_mut()
+            .send_event(PersistenceLoaded::<RecentFiles>::default());
+        repeat_update(&mut app, 10);
+        assert!(app.world().resource::<ChangeChain>().changes.is_empty());
+
+        // Changes after load are still recorded against loaded value
+        app.world_mut()
+            .resource_mut::<RecentFiles>()
+            .files
+            .push("new".to_string());
+        repeat_update(&mut app, 10);
+        app.world_mut().send_event(UndoRedo::Undo);
+        repeat_update(&mut app, 10);
+        assert_eq!(app.world().resource::<RecentFiles>().files, vec!["loaded"]);
+    }
+}
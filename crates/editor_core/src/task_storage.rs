@@ -1,4 +1,17 @@
-use bevy::{asset::LoadState, prelude::*};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use bevy::{
+    asset::{LoadState, UntypedAssetId},
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use space_shared::toast::{ToastKind, ToastMessage};
 
 pub struct BackgroundTaskStoragePlugin;
 
@@ -6,53 +19,229 @@ impl Plugin for BackgroundTaskStoragePlugin {
     #[cfg(not(tarpaulin_include))]
     fn build(&self, app: &mut App) {
         app.init_resource::<BackgroundTaskStorage>();
+        app.add_event::<BackgroundTaskFinished>();
 
-        app.add_systems(PostUpdate, update_storage);
+        app.add_systems(PostUpdate, (update_storage, send_finished_events).chain());
     }
 }
 
+/// Tasks which run in background. All tasks are running concurrently
 #[derive(Resource, Default)]
 pub struct BackgroundTaskStorage {
     pub tasks: Vec<BackgroundTask>,
+    next_id: u64,
+    finished: Vec<BackgroundTaskFinished>,
+}
+
+impl BackgroundTaskStorage {
+    /// Spawn async work on [`AsyncComputeTaskPool`]. Work can report progress and check cancellation
+    /// with given [`BackgroundTaskContext`]. [`BackgroundTaskFinished`] is sent when work is done
+    pub fn spawn<Fut>(
+        &mut self,
+        label: &str,
+        work: impl FnOnce(BackgroundTaskContext) -> Fut,
+    ) -> BackgroundTaskId
+    where
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let id = BackgroundTaskId(self.next_id);
+        self.next_id += 1;
+        let context = BackgroundTaskContext::default();
+        let task = AsyncComputeTaskPool::get().spawn(work(context.clone()));
+        self.tasks.push(BackgroundTask::Async(AsyncTask {
+            id,
+            label: label.to_string(),
+            context,
+            task,
+        }));
+        id
+    }
+
+    /// Stop task with given index. Async work is dropped at the next await point.
+    /// Scene which loading is cancelled is not spawned by editor loader
+    pub fn cancel(&mut self, index: usize) {
+        if index >= self.tasks.len() {
+            return;
+        }
+        let task = self.tasks.remove(index);
+        if let BackgroundTask::Async(task) = &task {
+            task.context.cancelled.store(true, Ordering::Relaxed);
+        }
+        self.finished.push(BackgroundTaskFinished {
+            id: task.id(),
+            label: task.label(),
+            asset: task.asset(),
+            result: BackgroundTaskResult::Cancelled,
+        });
+    }
+
+    pub fn cancel_id(&mut self, id: BackgroundTaskId) {
+        if let Some(index) = self.tasks.iter().position(|task| task.id() == Some(id)) {
+            self.cancel(index);
+        }
+    }
 }
 
 pub enum BackgroundTask {
     AssetLoading(String, UntypedHandle),
+    /// Work spawned by [`BackgroundTaskStorage::spawn`]
+    Async(AsyncTask),
     None,
 }
 
+impl BackgroundTask {
+    pub const fn id(&self) -> Option<BackgroundTaskId> {
+        match self {
+            Self::Async(task) => Some(task.id),
+            _ => None,
+        }
+    }
+
+    /// Asset loaded by [`BackgroundTask::AssetLoading`]
+    pub fn asset(&self) -> Option<UntypedAssetId> {
+        match self {
+            Self::AssetLoading(_, handle) => Some(handle.id()),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::AssetLoading(path, _) => format!("Loading {}", path),
+            Self::Async(task) => task.label.clone(),
+            Self::None => String::new(),
+        }
+    }
+
+    pub fn progress(&self) -> BackgroundTaskProgress {
+        match self {
+            Self::Async(task) => task.context.progress(),
+            _ => BackgroundTaskProgress::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BackgroundTaskId(pub u64);
+
+pub struct AsyncTask {
+    pub id: BackgroundTaskId,
+    pub label: String,
+    context: BackgroundTaskContext,
+    task: Task<Result<(), String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BackgroundTaskProgress {
+    /// Done part of work from 0 to 1. Unknown if `None`
+    pub fraction: Option<f32>,
+    /// Current step of work
+    pub label: String,
+}
+
+/// Handle given to background work to report progress and check cancellation
+#[derive(Clone, Default)]
+pub struct BackgroundTaskContext {
+    progress: Arc<Mutex<BackgroundTaskProgress>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl BackgroundTaskContext {
+    pub fn set_progress(&self, fraction: f32, label: &str) {
+        if let Ok(mut progress) = self.progress.lock() {
+            progress.fraction = Some(fraction.clamp(0.0, 1.0));
+            progress.label = label.to_string();
+        }
+    }
+
+    pub fn progress(&self) -> BackgroundTaskProgress {
+        self.progress
+            .lock()
+            .map(|progress| progress.clone())
+            .unwrap_or_default()
+    }
+
+    /// Long blocking work should check it and stop early
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackgroundTaskResult {
+    Completed,
+    Failed(String),
+    Cancelled,
+}
+
+/// Sent when background task is completed, failed or cancelled
+#[derive(Event, Clone, Debug)]
+pub struct BackgroundTaskFinished {
+    /// Id of task spawned with [`BackgroundTaskStorage::spawn`]
+    pub id: Option<BackgroundTaskId>,
+    pub label: String,
+    /// Asset of [`BackgroundTask::AssetLoading`]
+    pub asset: Option<UntypedAssetId>,
+    pub result: BackgroundTaskResult,
+}
+
 fn update_storage(mut storage: ResMut<BackgroundTaskStorage>, assets: Res<AssetServer>) {
-    if !storage.tasks.is_empty() {
-        let mut need_remove_task = false;
-        match &storage.tasks[0] {
+    let mut finished = vec![];
+    storage.tasks.retain_mut(|task| {
+        let result = match task {
             BackgroundTask::AssetLoading(_path, handle) => {
-                let load_state = assets.get_load_state(handle.id());
-                if load_state == Some(LoadState::Loaded)
-                    || load_state.is_none()
-                    || matches!(load_state, Some(LoadState::Failed(_)))
-                {
-                    need_remove_task = true;
+                match assets.get_load_state(handle.id()) {
+                    Some(LoadState::Loaded) | None => Some(Ok(())),
+                    Some(LoadState::Failed(err)) => Some(Err(err.to_string())),
+                    _ => None,
                 }
             }
-            BackgroundTask::None => {
-                need_remove_task = true;
-            }
-        }
+            BackgroundTask::Async(task) => block_on(future::poll_once(&mut task.task)),
+            BackgroundTask::None => return false,
+        };
+        let Some(result) = result else {
+            return true;
+        };
+        finished.push(BackgroundTaskFinished {
+            id: task.id(),
+            label: task.label(),
+            asset: task.asset(),
+            result: result.map_or_else(BackgroundTaskResult::Failed, |_| {
+                BackgroundTaskResult::Completed
+            }),
+        });
+        false
+    });
+    storage.finished.extend(finished);
+}
 
-        if need_remove_task {
-            storage.tasks.remove(0);
+fn send_finished_events(
+    mut storage: ResMut<BackgroundTaskStorage>,
+    mut events: EventWriter<BackgroundTaskFinished>,
+    mut toasts: EventWriter<ToastMessage>,
+) {
+    for event in storage.finished.drain(..) {
+        if let BackgroundTaskResult::Failed(error) = &event.result {
+            error!("{} failed: {}", event.label, error);
+            toasts.send(ToastMessage::new(
+                &format!("{} failed: {}", event.label, error),
+                ToastKind::Error,
+            ));
         }
+        events.send(event);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{load::cancel_scene_loading, EditorLoader};
 
     #[test]
     fn single_none_bg_task() {
         let storage = BackgroundTaskStorage {
             tasks: vec![BackgroundTask::None],
+            ..Default::default()
         };
 
         let mut app = App::new();
@@ -75,4 +264,102 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn async_tasks_run_concurrently_and_can_be_cancelled() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+        ))
+        .add_event::<ToastMessage>()
+        .add_plugins(BackgroundTaskStoragePlugin);
+
+        let mut storage = app.world_mut().resource_mut::<BackgroundTaskStorage>();
+        let done = storage.spawn("Export", |context| async move {
+            context.set_progress(1.0, "Done");
+            Ok(())
+        });
+        let failed = storage.spawn("Bake", |_| async { Err("no lights".to_string()) });
+        let cancelled = storage.spawn("Unpack", |_| future::pending());
+        storage.cancel_id(cancelled);
+
+        let mut reader = app
+            .world()
+            .resource::<Events<BackgroundTaskFinished>>()
+            .get_reader();
+        let mut results = vec![];
+        for _ in 0..100 {
+            app.update();
+            let events = app.world().resource::<Events<BackgroundTaskFinished>>();
+            results.extend(
+                reader
+                    .read(events)
+                    .map(|event| (event.id, event.result.clone())),
+            );
+            if results.len() >= 3 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert!(results.contains(&(Some(cancelled), BackgroundTaskResult::Cancelled)));
+        assert!(results.contains(&(Some(done), BackgroundTaskResult::Completed)));
+        assert!(results.contains(&(
+            Some(failed),
+            BackgroundTaskResult::Failed("no lights".to_string())
+        )));
+        assert!(app
+            .world()
+            .resource::<BackgroundTaskStorage>()
+            .tasks
+            .is_empty());
+    }
+
+    #[test]
+    fn cancelled_scene_loading_is_not_spawned() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+        ))
+        .init_asset::<DynamicScene>()
+        .add_event::<ToastMessage>()
+        .init_resource::<EditorLoader>()
+        .add_plugins(BackgroundTaskStoragePlugin)
+        .add_systems(Update, cancel_scene_loading);
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<DynamicScene>>()
+            .reserve_handle();
+        let other = app
+            .world_mut()
+            .resource_mut::<Assets<DynamicScene>>()
+            .reserve_handle();
+        *app.world_mut().resource_mut::<EditorLoader>() = EditorLoader {
+            scene: Some(handle.clone()),
+            path: Some("level.scn.ron".to_string()),
+        };
+        let cancel_loading = |app: &mut App, path: &str, handle: UntypedHandle| {
+            let mut storage = app.world_mut().resource_mut::<BackgroundTaskStorage>();
+            storage
+                .tasks
+                .push(BackgroundTask::AssetLoading(path.to_string(), handle));
+            storage.cancel(0);
+            app.update();
+            app.update();
+        };
+
+        // Loading of other asset does not stop scene loading
+        cancel_loading(&mut app, "other.scn.ron", other.untyped());
+        assert!(app.world().resource::<EditorLoader>().scene.is_some());
+
+        cancel_loading(&mut app, "level.scn.ron", handle.untyped());
+        let loader = app.world().resource::<EditorLoader>();
+        assert!(loader.scene.is_none());
+        assert!(loader.path.is_none());
+    }
 }
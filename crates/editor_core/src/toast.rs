@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 use egui_dock::egui::{self, Align2};
@@ -31,10 +33,56 @@ pub struct ToastsPerKind {
     pub error: Vec<String>,
 }
 
+/// Max count of messages kept in notification log
+pub const TOAST_LOG_LIMIT: usize = 1000;
+
+/// Message kept in notification log after toast disappears
+#[derive(Debug, Clone)]
+pub struct ToastLogEntry {
+    pub text: String,
+    pub kind: ToastKind,
+    pub entity: Option<Entity>,
+    pub details: Option<String>,
+    pub timestamp: SystemTime,
+}
+
+impl ToastLogEntry {
+    /// Time of message as `HH:MM:SS UTC`. Time zone is shown, because local time is not known
+    pub fn time_label(&self) -> String {
+        let secs = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default()
+            % 86400;
+        format!(
+            "{:02}:{:02}:{:02} UTC",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+
+    /// Message with details for clipboard
+    pub fn clipboard_text(&self) -> String {
+        let mut text = format!("[{}] {:?}: {}", self.time_label(), self.kind, self.text);
+        if let Some(entity) = self.entity {
+            text.push_str(&format!(" ({:?})", entity));
+        }
+        if let Some(details) = &self.details {
+            text.push('\n');
+            text.push_str(details);
+        }
+        text
+    }
+}
+
 #[derive(Resource)]
 pub struct ToastStorage {
     toasts: Toasts,
     pub toasts_per_kind: ToastsPerKind,
+    /// All received messages, oldest first
+    pub log: Vec<ToastLogEntry>,
 }
 
 impl ToastStorage {
@@ -44,6 +92,18 @@ impl ToastStorage {
             ToastKind::Error => self.toasts_per_kind.error.push(message.text.clone()),
             _ => (),
         }
+
+        self.log.push(ToastLogEntry {
+            text: message.text.clone(),
+            kind: message.kind,
+            entity: message.entity,
+            details: message.details.clone(),
+            timestamp: SystemTime::now(),
+        });
+        if self.log.len() > TOAST_LOG_LIMIT {
+            let overflow = self.log.len() - TOAST_LOG_LIMIT;
+            self.log.drain(..overflow);
+        }
     }
 
     pub fn has_toasts(&self) -> bool {
@@ -58,6 +118,7 @@ impl Default for ToastStorage {
                 .anchor(Align2::RIGHT_TOP, (-10.0, 10.0))
                 .direction(egui::Direction::TopDown),
             toasts_per_kind: ToastsPerKind::default(),
+            log: vec![],
         }
     }
 }
@@ -253,4 +314,53 @@ mod tests {
         let storage: &ToastStorage = app.world().get_resource::<ToastStorage>().unwrap();
         assert!(!storage.has_toasts());
     }
+
+    #[test]
+    fn toasts_are_kept_in_log() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ToastBasePlugin));
+
+        let entity = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .send_event(ToastMessage::new("Info message", ToastKind::Info));
+        app.world_mut().send_event(
+            ToastMessage::new("Failed to load", ToastKind::Error)
+                .with_entity(entity)
+                .with_details("Missing component"),
+        );
+        app.update();
+        app.world_mut().send_event(ClearToastMessage::all());
+        app.update();
+
+        let log = &app.world().resource::<ToastStorage>().log;
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].kind, ToastKind::Info);
+        assert_eq!(log[1].entity, Some(entity));
+        let text = log[1].clipboard_text();
+        assert!(text.contains("Failed to load"));
+        assert!(text.ends_with("\nMissing component"));
+    }
+
+    #[test]
+    fn time_label_is_utc() {
+        let entry = ToastLogEntry {
+            text: String::new(),
+            kind: ToastKind::Info,
+            entity: None,
+            details: None,
+            timestamp: UNIX_EPOCH + std::time::Duration::from_secs(86400 + 3661),
+        };
+        assert_eq!(entry.time_label(), "01:01:01 UTC");
+        assert!(entry.clipboard_text().starts_with("[01:01:01 UTC] Info"));
+    }
+
+    #[test]
+    fn log_is_limited() {
+        let mut storage = ToastStorage::default();
+        for idx in 0..TOAST_LOG_LIMIT + 5 {
+            storage.add(&ToastMessage::new(&idx.to_string(), ToastKind::Info));
+        }
+        assert_eq!(storage.log.len(), TOAST_LOG_LIMIT);
+        assert_eq!(storage.log[0].text, "5");
+    }
 }
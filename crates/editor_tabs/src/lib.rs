@@ -1,5 +1,6 @@
 /// This library contains dock-tab implementation for Space Editor
 pub mod editor_tab;
+pub mod saved_layout;
 pub mod schedule_editor_tab;
 pub mod start_layout;
 pub mod tab_name;
@@ -22,6 +23,7 @@ use tab_viewer::*;
 
 pub mod prelude {
     pub use super::editor_tab::*;
+    pub use super::saved_layout::*;
     pub use super::schedule_editor_tab::*;
     pub use super::start_layout::*;
     pub use super::tab_name::*;
@@ -66,6 +68,8 @@ pub struct EditorUi {
     pub registry: HashMap<TabNameHolder, EditorUiReg>,
     pub tree: egui_dock::DockState<TabNameHolder>,
     pub style_getter: fn(&World) -> &dyn TabStyle,
+    /// Tree built from start layout
+    default_tree: egui_dock::DockState<TabNameHolder>,
 }
 
 impl Default for EditorUi {
@@ -74,6 +78,7 @@ impl Default for EditorUi {
             registry: HashMap::default(),
             tree: egui_dock::DockState::new(vec![]),
             style_getter: |_| &DEFAULT_STYLE,
+            default_tree: egui_dock::DockState::new(vec![]),
         }
     }
 }
@@ -85,6 +90,7 @@ impl EditorUi {
 
     pub fn set_layout<T: StartLayout>(&mut self, layout: &T) {
         self.tree = layout.build();
+        self.default_tree = self.tree.clone();
     }
 
     pub fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
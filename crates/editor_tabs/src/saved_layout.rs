@@ -0,0 +1,140 @@
+/// This mod contains serialization of dock layout
+/// Tabs are stored by their stable names, so layout can be restored in next session
+use bevy::prelude::*;
+
+use crate::{tab_name::TabNameHolder, EditorUi};
+
+/// Serialized dock layouts of editor
+#[derive(Resource, Reflect, Default, Clone)]
+#[reflect(Resource, Default)]
+pub struct EditorLayouts {
+    /// Layout of last session (empty if layout was never saved)
+    pub current: String,
+    /// Named layouts, which can be switched from menu
+    pub presets: Vec<LayoutPreset>,
+}
+
+#[derive(Reflect, Default, Clone)]
+#[reflect(Default)]
+pub struct LayoutPreset {
+    pub name: String,
+    pub layout: String,
+}
+
+impl EditorLayouts {
+    pub fn preset(&self, name: &str) -> Option<&LayoutPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Add preset or replace preset with the same name
+    pub fn set_preset(&mut self, name: &str, layout: String) {
+        if let Some(preset) = self.presets.iter_mut().find(|preset| preset.name == name) {
+            preset.layout = layout;
+        } else {
+            self.presets.push(LayoutPreset {
+                name: name.to_string(),
+                layout,
+            });
+        }
+    }
+
+    pub fn remove_preset(&mut self, name: &str) {
+        self.presets.retain(|preset| preset.name != name);
+    }
+}
+
+impl EditorUi {
+    /// Serialize current dock tree with tabs replaced by their names
+    pub fn save_layout(&self) -> Result<String, String> {
+        let tree = self.tree.map_tabs(|tab| tab.value.clone());
+        ron::to_string(&tree).map_err(|e| e.to_string())
+    }
+
+    /// Restore dock tree saved by [`EditorUi::save_layout`].
+    /// Tabs which are not registered anymore are dropped
+    pub fn load_layout(&mut self, layout: &str) -> Result<(), String> {
+        let tree: egui_dock::DockState<String> =
+            ron::from_str(layout).map_err(|e| e.to_string())?;
+        let tree = tree.filter_map_tabs(|name| self.registered_tab(name));
+        if tree.iter_all_tabs().next().is_none() {
+            return Err("Layout has no registered tabs".to_string());
+        }
+        self.tree = tree;
+        Ok(())
+    }
+
+    /// Return to layout which was set by start layout
+    pub fn reset_layout(&mut self) {
+        self.tree = self.default_tree.clone();
+    }
+
+    fn registered_tab(&self, name: &str) -> Option<TabNameHolder> {
+        self.registry
+            .keys()
+            .find(|holder| holder.value == name)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tab_name::TabName, EditorUiReg};
+
+    #[derive(Debug)]
+    enum TestTab {
+        Left,
+        Right,
+    }
+
+    impl TabName for TestTab {
+        fn clear_background(&self) -> bool {
+            true
+        }
+
+        fn title(&self) -> String {
+            format!("{:?}", self)
+        }
+    }
+
+    #[test]
+    fn layout_roundtrip_drops_unknown_tabs() {
+        let mut editor = EditorUi::default();
+        editor
+            .registry
+            .insert(TestTab::Left.into(), EditorUiReg::Schedule);
+        editor
+            .registry
+            .insert(TestTab::Right.into(), EditorUiReg::Schedule);
+        editor.tree = egui_dock::DockState::new(vec![TestTab::Left.into()]);
+        editor.tree.main_surface_mut().split_right(
+            egui_dock::NodeIndex::root(),
+            0.5,
+            vec![TestTab::Right.into()],
+        );
+        let layout = editor.save_layout().unwrap();
+
+        editor.registry.remove(&TestTab::Right.into());
+        editor.tree = egui_dock::DockState::new(vec![]);
+        editor.load_layout(&layout).unwrap();
+
+        let tabs = editor
+            .tree
+            .iter_all_tabs()
+            .map(|(_, tab)| tab.value.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(tabs, vec!["Left".to_string()]);
+    }
+
+    #[test]
+    fn presets_are_replaced_by_name() {
+        let mut layouts = EditorLayouts::default();
+        layouts.set_preset("Animation", "a".to_string());
+        layouts.set_preset("Animation", "b".to_string());
+        assert_eq!(layouts.presets.len(), 1);
+        assert_eq!(layouts.preset("Animation").unwrap().layout, "b");
+
+        layouts.remove_preset("Animation");
+        assert!(layouts.presets.is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+use bevy::{
+    animation::{AnimationClip, AnimationTarget},
+    prelude::*,
+};
+use bevy_egui::egui;
+use space_editor_core::prelude::Selected;
+use space_prefab::component::{GltfAnimationGraph, GltfAnimationPrefab};
+use space_shared::{EditorSet, EditorState};
+use space_undo::OneFrameUndoIgnore;
+
+/// Plugin to preview gltf animations in editor mode
+pub struct AnimationPreviewPlugin;
+
+impl Plugin for AnimationPreviewPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationPreview>();
+        app.add_systems(Update, update_animation_preview.in_set(EditorSet::Editor));
+        app.add_systems(
+            OnEnter(EditorState::GamePrepare),
+            (stop_animation_preview, update_animation_preview).chain(),
+        );
+    }
+}
+
+/// Clip of [`GltfAnimationPrefab`] previewed in editor mode.
+/// Pose of animated entities is restored when preview stops
+#[derive(Resource, Default)]
+pub struct AnimationPreview {
+    /// Entity with [`GltfAnimationPrefab`]
+    pub entity: Option<Entity>,
+    pub clip: String,
+    /// Time of clip in seconds
+    pub time: f32,
+    pub playing: bool,
+    /// Duration of clip, zero while clip is loading
+    pub duration: f32,
+    player: Option<Entity>,
+    rest_pose: Vec<(Entity, Transform)>,
+}
+
+impl AnimationPreview {
+    pub fn start(&mut self, entity: Entity, clip: &str) {
+        self.entity = Some(entity);
+        self.clip = clip.to_string();
+        self.time = 0.0;
+        self.playing = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.entity = None;
+        self.playing = false;
+    }
+}
+
+fn stop_animation_preview(mut preview: ResMut<AnimationPreview>) {
+    preview.stop();
+}
+
+fn update_animation_preview(
+    mut commands: Commands,
+    mut preview: ResMut<AnimationPreview>,
+    graphs: Query<&GltfAnimationGraph>,
+    selected: Query<(), With<Selected>>,
+    mut players: Query<&mut AnimationPlayer>,
+    mut transforms: Query<&mut Transform>,
+    targets: Query<(Entity, &AnimationTarget)>,
+    clips: Res<Assets<AnimationClip>>,
+) {
+    if preview.entity.is_some_and(|e| !selected.contains(e)) {
+        preview.stop();
+    }
+
+    let active = preview
+        .entity
+        .and_then(|e| graphs.get(e).ok())
+        .and_then(|graph| {
+            let (node, clip) = graph.clips.get(&preview.clip)?;
+            Some((graph.player?, *node, clip.clone()))
+        });
+
+    // Restore pose when preview is stopped or switched to other player
+    if preview.player.is_some() && preview.player != active.as_ref().map(|(player, ..)| *player) {
+        if let Some(Ok(mut player)) = preview.player.map(|player| players.get_mut(player)) {
+            player.stop_all();
+        }
+        for (e, transform) in std::mem::take(&mut preview.rest_pose) {
+            if let Ok(mut current) = transforms.get_mut(e) {
+                *current = transform;
+                commands.entity(e).insert(OneFrameUndoIgnore::default());
+            }
+        }
+        preview.player = None;
+    }
+
+    let Some((player_entity, node, clip)) = active else {
+        return;
+    };
+    let Ok(mut player) = players.get_mut(player_entity) else {
+        return;
+    };
+
+    if preview.player.is_none() {
+        preview.rest_pose = targets
+            .iter()
+            .filter(|(_, target)| target.player == player_entity)
+            .filter_map(|(e, _)| Some((e, *transforms.get(e).ok()?)))
+            .collect();
+        preview.player = Some(player_entity);
+    }
+
+    if !player.is_playing_animation(node) {
+        player.stop_all();
+        player.play(node).repeat();
+    }
+
+    preview.duration = clips.get(&clip).map_or(0.0, AnimationClip::duration);
+    if let Some(animation) = player.animation_mut(node) {
+        if preview.playing {
+            animation.resume();
+            if preview.duration > 0.0 {
+                preview.time = animation.seek_time().rem_euclid(preview.duration);
+            }
+        } else {
+            animation.pause();
+            animation.seek_to(preview.time);
+        }
+    }
+
+    // Animated pose must not be recorded as user change
+    for (e, _) in preview.rest_pose.iter() {
+        commands.entity(*e).insert(OneFrameUndoIgnore::default());
+    }
+}
+
+/// Clip selection and scrubber of [`GltfAnimationPrefab`] for inspector
+pub fn animation_preview_ui(
+    ui: &mut egui::Ui,
+    entity: Entity,
+    prefab: &GltfAnimationPrefab,
+    preview: &mut AnimationPreview,
+) {
+    if prefab.clips.is_empty() {
+        return;
+    }
+    let previewed = preview.entity == Some(entity);
+
+    ui.label("Animation preview:");
+    ui.horizontal(|ui| {
+        let selected_text = if previewed {
+            preview.clip.as_str()
+        } else {
+            "None"
+        };
+        let mut start = None;
+        egui::ComboBox::from_id_source(format!("animation_preview_{:?}", entity))
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for clip in prefab.clips.iter() {
+                    let is_selected = previewed && preview.clip == clip.name;
+                    if ui.selectable_label(is_selected, &clip.name).clicked() {
+                        start = Some(clip.name.clone());
+                    }
+                }
+            });
+        if let Some(clip) = start {
+            preview.start(entity, &clip);
+        }
+
+        if previewed {
+            let play_label = if preview.playing { "⏸" } else { "▶" };
+            if ui.button(play_label).clicked() {
+                preview.playing = !preview.playing;
+            }
+            if ui
+                .button("⏹")
+                .on_hover_text("Stop preview and restore pose")
+                .clicked()
+            {
+                preview.stop();
+            }
+        }
+    });
+
+    if previewed {
+        let duration = preview.duration;
+        if ui
+            .add(egui::Slider::new(&mut preview.time, 0.0..=duration).suffix(" s"))
+            .changed()
+        {
+            preview.playing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{animation::AnimationTargetId, utils::HashMap};
+
+    use super::*;
+
+    #[test]
+    fn stopped_preview_restores_pose() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<AnimationClip>()
+            .init_resource::<AnimationPreview>()
+            .add_systems(Update, update_animation_preview);
+
+        let mut graph = AnimationGraph::new();
+        let node = graph.add_clip(Handle::default(), 1.0, graph.root);
+        let root = app.world_mut().spawn(AnimationPlayer::default()).id();
+        app.world_mut().entity_mut(root).insert((
+            Selected,
+            GltfAnimationGraph {
+                graph: Handle::default(),
+                clips: HashMap::from([("Walk".to_string(), (node, Handle::default()))]),
+                player: Some(root),
+            },
+        ));
+        let rest = Transform::from_xyz(1.0, 2.0, 3.0);
+        let bone = app
+            .world_mut()
+            .spawn((
+                rest,
+                AnimationTarget {
+                    id: AnimationTargetId::from_name(&Name::new("Bone")),
+                    player: root,
+                },
+            ))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<AnimationPreview>()
+            .start(root, "Walk");
+        app.update();
+        assert!(app
+            .world()
+            .get::<AnimationPlayer>(root)
+            .unwrap()
+            .is_playing_animation(node));
+
+        // pose changed by animation
+        app.world_mut()
+            .get_mut::<Transform>(bone)
+            .unwrap()
+            .translation = Vec3::ZERO;
+        app.world_mut().entity_mut(root).remove::<Selected>();
+        app.update();
+
+        assert_eq!(app.world().resource::<AnimationPreview>().entity, None);
+        assert_eq!(*app.world().get::<Transform>(bone).unwrap(), rest);
+        assert!(app.world().get::<OneFrameUndoIgnore>(bone).is_some());
+        assert!(!app
+            .world()
+            .get::<AnimationPlayer>(root)
+            .unwrap()
+            .is_playing_animation(node));
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Align, Align2, Key, Layout, RichText},
+    EguiContexts,
+};
+use space_editor_core::prelude::*;
+use space_shared::EditorSet;
+
+use crate::ShowEditorUi;
+
+/// Plugin with fuzzy search palette of registered [`EditorCommand`]s
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandPalette>();
+        app.add_event::<RunEditorCommand>();
+        app.editor_hotkey(
+            CommandPaletteHotkey::Open,
+            vec![KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::KeyP],
+        );
+        app.add_systems(
+            Update,
+            command_palette_ui
+                .in_set(EditorSet::Editor)
+                .run_if(in_state(ShowEditorUi::Show)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum CommandPaletteHotkey {
+    Open,
+}
+
+impl Hotkey for CommandPaletteHotkey {
+    fn name(&self) -> String {
+        match self {
+            Self::Open => "Open command palette".to_string(),
+        }
+    }
+}
+
+/// State of command palette window
+#[derive(Resource, Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    pub query: String,
+    /// Index of highlighted command in search results
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Move highlighted command, wrapping around `len` results
+    pub fn move_selection(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.selected = 0;
+            return;
+        }
+        let len = len as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+}
+
+fn command_palette_ui(
+    mut ctxs: EguiContexts,
+    mut palette: ResMut<CommandPalette>,
+    hotkeys: Res<ButtonInput<CommandPaletteHotkey>>,
+    commands: Option<Res<EditorCommands>>,
+    bindings: Option<Res<HotkeySet<CommandHotkey>>>,
+    mut run_events: EventWriter<RunEditorCommand>,
+) {
+    if hotkeys.just_pressed(CommandPaletteHotkey::Open) {
+        palette.toggle();
+    }
+    if !palette.open {
+        return;
+    }
+    let Some(commands) = commands else {
+        return;
+    };
+    let ctx = ctxs.ctx_mut();
+
+    // Palette is shown in editor, so play mode commands can't run from it
+    let found = commands
+        .search(&palette.query)
+        .into_iter()
+        .filter(|command| !command.playmode)
+        .collect::<Vec<_>>();
+    let (up, down, enter, escape) = ctx.input(|input| {
+        (
+            input.key_pressed(Key::ArrowUp),
+            input.key_pressed(Key::ArrowDown),
+            input.key_pressed(Key::Enter),
+            input.key_pressed(Key::Escape),
+        )
+    });
+    if up {
+        palette.move_selection(-1, found.len());
+    }
+    if down {
+        palette.move_selection(1, found.len());
+    }
+    palette.selected = palette.selected.min(found.len().saturating_sub(1));
+
+    let mut run = found.get(palette.selected).copied().filter(|_| enter);
+    egui::Window::new("Command Palette")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(450.)
+        .anchor(Align2::CENTER_TOP, [0., 60.])
+        .show(ctx, |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut palette.query)
+                    .hint_text("Type command name")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                palette.selected = 0;
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .max_height(300.)
+                .show(ui, |ui| {
+                    if found.is_empty() {
+                        ui.label(RichText::new("No matching commands").weak());
+                    }
+                    for (idx, command) in found.iter().enumerate() {
+                        let is_selected = idx == palette.selected;
+                        ui.horizontal(|ui| {
+                            let row = ui.selectable_label(
+                                is_selected,
+                                format!("{}: {}", command.category, command.label),
+                            );
+                            if is_selected && (up || down) {
+                                row.scroll_to_me(None);
+                            }
+                            if row.clicked() {
+                                run = Some(*command);
+                            }
+                            let chord = bindings
+                                .as_ref()
+                                .and_then(|set| set.bindings.get(&CommandHotkey(command.id)))
+                                .filter(|binding| !binding.is_empty())
+                                .map(|binding| HotkeyChord::new(binding).to_string());
+                            if let Some(chord) = chord {
+                                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                                    ui.label(RichText::new(chord).weak());
+                                });
+                            }
+                        });
+                    }
+                });
+        });
+
+    if let Some(command) = run {
+        run_events.send(RunEditorCommand(command.id.to_string()));
+        palette.toggle();
+    } else if escape {
+        palette.toggle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_wraps_around_results() {
+        let mut palette = CommandPalette::default();
+        palette.toggle();
+        assert!(palette.open);
+
+        palette.move_selection(-1, 3);
+        assert_eq!(palette.selected, 2);
+        palette.move_selection(1, 3);
+        assert_eq!(palette.selected, 0);
+        palette.move_selection(1, 0);
+        assert_eq!(palette.selected, 0);
+
+        palette.toggle();
+        assert!(!palette.open);
+    }
+}
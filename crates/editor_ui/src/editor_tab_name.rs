@@ -13,6 +13,7 @@ pub enum EditorTabName {
     ToolBox,
     ChangeChain,
     DebugWorldInspector,
+    Notifications,
 }
 
 impl TabName for EditorTabName {
@@ -33,6 +34,7 @@ impl TabName for EditorTabName {
             Self::ToolBox => "Tool Box".to_string(),
             Self::ChangeChain => "Change Chain".to_string(),
             Self::DebugWorldInspector => "Debug World Inspector".to_string(),
+            Self::Notifications => "Notifications".to_string(),
         }
     }
 }
@@ -0,0 +1,374 @@
+use bevy::{
+    animation::AnimationClip,
+    gltf::{Gltf, GltfMesh, GltfNode},
+    input::InputPlugin,
+    prelude::*,
+    scene::ScenePlugin,
+};
+use space_editor_core::{
+    autosave::AutosaveSettings, journal::ChangeJournalSettings, prelude::*, EditorCore,
+};
+use space_prefab::{
+    prelude::*,
+    save::{SaveState, SceneWriteTasks},
+};
+use space_shared::{EditorEvent, EditorPrefabPath, EditorState, PrefabMarker, PrefabMemoryCache};
+use space_undo::{SyncUndoMarkersPlugin, UndoPlugin, UndoRedo};
+
+use crate::{ui_registration::spawn_editor_bundle, EditorDefaultBundlesPlugin, EditorSetsPlugin};
+
+/// Frames to wait for auto undo to detect change
+const CHANGE_FRAMES: usize = 10;
+/// Frames to wait for save or load to finish
+const MAX_WAIT_FRAMES: usize = 100;
+
+/// Editor logic (core, prefabs, undo, selection and hierarchy) without window, rendering and egui.
+/// Must be used with [`MinimalPlugins`]. See [`HeadlessEditor`] for test API
+pub struct HeadlessEditorPlugin;
+
+impl Plugin for HeadlessEditorPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AssetPlugin>() {
+            app.add_plugins(AssetPlugin::default());
+        }
+        app.add_plugins((
+            TransformPlugin,
+            HierarchyPlugin,
+            InputPlugin,
+            ImagePlugin::default(),
+            ScenePlugin,
+        ));
+        // Assets used by prefab systems, which are usually registered by render plugins
+        app.init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<ColorMaterial>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_asset::<Gltf>()
+            .init_asset::<GltfMesh>()
+            .init_asset::<GltfNode>()
+            .init_asset::<AnimationClip>()
+            .init_asset::<AnimationGraph>();
+
+        // Tests must not depend on files of user and project
+        #[cfg(feature = "persistence_editor")]
+        app.insert_resource(space_persistence::PersistenceSettings {
+            load_on_startup: false,
+            save_on_close: false,
+        });
+        app.insert_resource(ChangeJournalSettings { enabled: false })
+            .insert_resource(AutosaveSettings {
+                enabled: false,
+                ..default()
+            });
+
+        app.add_plugins((
+            UndoPlugin,
+            SyncUndoMarkersPlugin::<PrefabMarker>::default(),
+            PrefabPlugin,
+            EditorCore,
+            SelectionToolsPlugin,
+            EditorSetsPlugin,
+            EditorDefaultBundlesPlugin,
+        ));
+
+        #[cfg(feature = "persistence_editor")]
+        {
+            use space_persistence::{PersistenceDataSource, PersistenceLayer, PersistenceRegistry};
+            let mut registry = app.world_mut().resource_mut::<PersistenceRegistry>();
+            for layer in PersistenceLayer::LOAD_ORDER {
+                registry.set_source(layer, PersistenceDataSource::Memory);
+            }
+        }
+
+        app.add_systems(Startup, start_headless_editor);
+    }
+}
+
+fn start_headless_editor(mut state: ResMut<NextState<EditorState>>) {
+    state.set(EditorState::Editor);
+}
+
+/// Headless editor app with editor actions for tests.
+/// Actions go through the same events and commands as editor ui.
+/// Every action updates app until its result is visible in world
+pub struct HeadlessEditor {
+    pub app: App,
+}
+
+impl Default for HeadlessEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessEditor {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        Self::with_app(app)
+    }
+
+    /// Headless editor which loads scenes from given assets folder
+    pub fn with_assets_folder(path: &str) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: path.to_string(),
+                ..default()
+            },
+        ));
+        Self::with_app(app)
+    }
+
+    fn with_app(mut app: App) -> Self {
+        app.add_plugins(HeadlessEditorPlugin);
+        app.update();
+        app.update();
+        Self { app }
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn update(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Update until `condition` is true. Returns false if it was not reached
+    pub fn update_until(&mut self, condition: impl Fn(&mut World) -> bool) -> bool {
+        for _ in 0..MAX_WAIT_FRAMES {
+            if condition(self.app.world_mut()) {
+                return true;
+            }
+            self.app.update();
+        }
+        condition(self.app.world_mut())
+    }
+
+    /// Spawn registered bundle as it is done from spawn menu
+    pub fn spawn_bundle(&mut self, category: &str, name: &str) -> Option<Entity> {
+        let entity = spawn_editor_bundle(self.app.world_mut(), category, name)?;
+        self.update(CHANGE_FRAMES);
+        Some(entity)
+    }
+
+    /// Replace selection with given entities
+    pub fn select(&mut self, entities: &[Entity]) {
+        self.app
+            .world_mut()
+            .send_event(SelectionAction::Select(entities.to_vec()));
+        self.app.update();
+    }
+
+    /// Run registered editor command, for example `edit.delete_selected`
+    pub fn run_command(&mut self, id: &str) {
+        self.app
+            .world_mut()
+            .send_event(RunEditorCommand(id.to_string()));
+        self.update(CHANGE_FRAMES);
+    }
+
+    pub fn selected(&mut self) -> Vec<Entity> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<Entity, With<Selected>>()
+            .iter(world)
+            .collect()
+    }
+
+    pub fn find_by_name(&mut self, name: &str) -> Option<Entity> {
+        let world = self.app.world_mut();
+        world
+            .query::<(Entity, &Name)>()
+            .iter(world)
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Move entity as gizmo does. Change is recorded by auto undo
+    pub fn move_to(&mut self, entity: Entity, translation: Vec3) {
+        if let Some(mut transform) = self.app.world_mut().get_mut::<Transform>(entity) {
+            transform.translation = translation;
+        }
+        self.update(CHANGE_FRAMES);
+    }
+
+    /// Delete entity with children by selecting it and running delete command
+    pub fn delete(&mut self, entity: Entity) {
+        self.select(&[entity]);
+        self.delete_selected();
+    }
+
+    pub fn delete_selected(&mut self) {
+        self.run_command("edit.delete_selected");
+    }
+
+    pub fn undo(&mut self) {
+        self.app.world_mut().send_event(UndoRedo::Undo);
+        self.update(CHANGE_FRAMES);
+    }
+
+    pub fn redo(&mut self) {
+        self.app.world_mut().send_event(UndoRedo::Redo);
+        self.update(CHANGE_FRAMES);
+    }
+
+    /// Save scene to [`PrefabMemoryCache`]. Returns false if save was not finished
+    pub fn save_to_memory(&mut self) -> bool {
+        self.app
+            .world_mut()
+            .send_event(EditorEvent::Save(EditorPrefabPath::MemoryCache));
+        self.app.update();
+        self.update_until(|world| {
+            *world.resource::<State<SaveState>>().get() == SaveState::Idle
+                && world.resource::<PrefabMemoryCache>().scene.is_some()
+        })
+    }
+
+    /// Save scene to file. Returns false if file was not written
+    pub fn save_to_file(&mut self, path: &str) -> bool {
+        self.app
+            .world_mut()
+            .send_event(EditorEvent::Save(EditorPrefabPath::File(path.to_string())));
+        self.app.update();
+        let path = path.to_string();
+        self.update_until(move |world| {
+            *world.resource::<State<SaveState>>().get() == SaveState::Idle
+                && world.resource::<SceneWriteTasks>().is_empty()
+                && std::path::Path::new(&path).exists()
+        })
+    }
+
+    /// Load scene from file (relative to assets folder) or memory cache.
+    /// Returns false if scene was not loaded
+    pub fn load(&mut self, path: EditorPrefabPath) -> bool {
+        self.app.world_mut().send_event(EditorEvent::Load(path));
+        self.app.update();
+        let loaded = self.update_until(|world| world.resource::<EditorLoader>().scene.is_none());
+        self.update(CHANGE_FRAMES);
+        loaded
+    }
+
+    /// Number of entities which are part of edited scene
+    pub fn prefab_entities(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<(), With<PrefabMarker>>()
+            .iter(world)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_registration::EditorUiExt;
+
+    #[test]
+    fn spawn_move_undo_save_and_load() {
+        let mut editor = HeadlessEditor::new();
+        editor.app.editor_bundle(
+            "Test",
+            "Marker",
+            (Name::new("Marker"), Transform::default()),
+        );
+
+        let entity = editor.spawn_bundle("Test", "Marker").unwrap();
+        editor.select(&[entity]);
+        assert_eq!(editor.selected(), vec![entity]);
+
+        editor.move_to(entity, Vec3::X);
+        editor.undo();
+        assert_eq!(
+            editor.world().get::<Transform>(entity).unwrap().translation,
+            Vec3::ZERO
+        );
+        editor.redo();
+        assert_eq!(
+            editor.world().get::<Transform>(entity).unwrap().translation,
+            Vec3::X
+        );
+
+        assert!(editor.save_to_memory());
+        editor.delete_selected();
+        assert_eq!(editor.prefab_entities(), 0);
+
+        assert!(editor.load(EditorPrefabPath::MemoryCache));
+        let loaded = editor.find_by_name("Marker").unwrap();
+        assert_eq!(
+            editor.world().get::<Transform>(loaded).unwrap().translation,
+            Vec3::X
+        );
+    }
+
+    #[test]
+    fn delete_parent_with_children_and_undo() {
+        let mut editor = HeadlessEditor::new();
+        editor.app.editor_bundle(
+            "Test",
+            "Parent",
+            (Name::new("Parent"), Transform::default()),
+        );
+        editor
+            .app
+            .editor_bundle("Test", "Child", (Name::new("Child"), Transform::default()));
+        let parent = editor.spawn_bundle("Test", "Parent").unwrap();
+        let child = editor.spawn_bundle("Test", "Child").unwrap();
+        editor.world_mut().entity_mut(parent).add_child(child);
+        editor.update(CHANGE_FRAMES);
+
+        editor.delete(parent);
+        assert!(editor.world().get_entity(parent).is_none());
+        assert!(editor.world().get_entity(child).is_none());
+        assert_eq!(editor.prefab_entities(), 0);
+
+        editor.undo();
+        assert_eq!(editor.prefab_entities(), 2);
+        let parent = editor.find_by_name("Parent").unwrap();
+        let child = editor.find_by_name("Child").unwrap();
+        assert_eq!(
+            editor.world().get::<Parent>(child).map(Parent::get),
+            Some(parent)
+        );
+
+        editor.redo();
+        assert_eq!(editor.prefab_entities(), 0);
+    }
+
+    #[test]
+    fn save_and_load_file() {
+        let dir = std::env::temp_dir().join("space_editor_headless_file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut editor = HeadlessEditor::with_assets_folder(dir.to_str().unwrap());
+        editor.app.editor_bundle(
+            "Test",
+            "Marker",
+            (Name::new("Marker"), Transform::default()),
+        );
+        let entity = editor.spawn_bundle("Test", "Marker").unwrap();
+        editor.move_to(entity, Vec3::Y);
+
+        let file = dir.join("level.scn.ron");
+        assert!(editor.save_to_file(file.to_str().unwrap()));
+        editor.delete(entity);
+        assert_eq!(editor.prefab_entities(), 0);
+
+        assert!(editor.load(EditorPrefabPath::File("level.scn.ron".to_string())));
+        let loaded = editor.find_by_name("Marker").unwrap();
+        assert_eq!(
+            editor.world().get::<Transform>(loaded).unwrap().translation,
+            Vec3::Y
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
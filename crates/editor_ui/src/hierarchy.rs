@@ -1,14 +1,18 @@
 #![allow(clippy::too_many_arguments)]
 use std::sync::Arc;
 
-use bevy::{ecs::query::QueryFilter, prelude::*, utils::HashMap};
+use bevy::{
+    ecs::query::QueryFilter,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_egui::{
     egui::{collapsing_header::CollapsingState, TextEdit},
     *,
 };
 use space_editor_core::prelude::*;
 use space_prefab::{component::SceneAutoChild, editor_registry::EditorRegistry};
-use space_undo::{AddedEntity, NewChange, RemovedEntity, UndoSet};
+use space_undo::{AddedEntity, LabeledChange, NewChange, RemovedEntity, UndoSet};
 
 use space_shared::*;
 
@@ -41,6 +45,22 @@ impl Plugin for SpaceHierarchyPlugin {
                 .before(UndoSet::PerType),
         );
         app.add_event::<CloneEvent>();
+        app.editor_command(
+            "edit.clone_selected",
+            "Clone selected",
+            "Edit",
+            clone_selected,
+        );
+    }
+}
+
+fn clone_selected(world: &mut World) {
+    let selected = world
+        .query_filtered::<Entity, With<Selected>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for id in selected {
+        world.send_event(CloneEvent { id });
     }
 }
 
@@ -49,6 +69,8 @@ pub struct HierarchyTabState {
     pub show_editor_entities: bool,
     pub show_spawnable_bundles: bool,
     pub entity_filter: String,
+    /// Entities which children are hidden in hierarchy
+    pub collapsed: HashSet<Entity>,
 }
 
 pub type HierarchyQueryIter<'a> = (
@@ -112,6 +134,7 @@ pub fn show_hierarchy(
                         &mut clone_events,
                         &mut changes,
                         &auto_children,
+                        &mut state.collapsed,
                     );
                 } else {
                     draw_entity::<With<PrefabMarker>>(
@@ -123,6 +146,7 @@ pub fn show_hierarchy(
                         &mut clone_events,
                         &mut changes,
                         &auto_children,
+                        &mut state.collapsed,
                     );
                 }
             }
@@ -146,6 +170,7 @@ fn draw_entity<F: QueryFilter>(
     clone_events: &mut EventWriter<CloneEvent>,
     changes: &mut EventWriter<NewChange>,
     auto_children: &Query<(), With<SceneAutoChild>>,
+    collapsed: &mut HashSet<Entity>,
 ) {
     let Ok((_, name, children, parent)) = query.get(entity) else {
         return;
@@ -159,12 +184,13 @@ fn draw_entity<F: QueryFilter>(
     let is_selected = selected.contains(entity);
 
     if children.is_some_and(|children| children.iter().any(|child| query.get(*child).is_ok())) {
-        CollapsingState::load_with_default_open(
+        let mut collapsing_state = CollapsingState::load_with_default_open(
             ui.ctx(),
             ui.make_persistent_id(entity_name.clone()),
             true,
-        )
-        .show_header(ui, |ui| {
+        );
+        collapsing_state.set_open(!collapsed.contains(&entity));
+        let (toggle, _, _) = collapsing_state.show_header(ui, |ui| {
             let mut entity_name = egui::RichText::new(entity_name.clone());
             let is_auto_child = auto_children.get(entity).is_ok();
             if is_auto_child {
@@ -186,6 +212,7 @@ fn draw_entity<F: QueryFilter>(
                         ui,
                         commands,
                         entity,
+                        name,
                         changes,
                         clone_events,
                         selected,
@@ -223,9 +250,13 @@ fn draw_entity<F: QueryFilter>(
                     clone_events,
                     changes,
                     auto_children,
+                    collapsed,
                 );
             }
         });
+        if toggle.clicked() && !collapsed.remove(&entity) {
+            collapsed.insert(entity);
+        }
     } else {
         let mut entity_name = egui::RichText::new(format!("      {}", entity_name));
         let is_auto_child = auto_children.get(entity).is_ok();
@@ -249,6 +280,7 @@ fn draw_entity<F: QueryFilter>(
                     ui,
                     commands,
                     entity,
+                    name,
                     changes,
                     clone_events,
                     selected,
@@ -280,6 +312,7 @@ fn hierarchy_entity_context(
     ui: &mut egui::Ui,
     commands: &mut Commands<'_, '_>,
     entity: Entity,
+    name: Option<&Name>,
     changes: &mut EventWriter<'_, NewChange>,
     clone_events: &mut EventWriter<'_, CloneEvent>,
     selected: &mut Query<'_, '_, Entity, With<Selected>>,
@@ -295,8 +328,13 @@ fn hierarchy_entity_context(
     }
     if ui.button("Delete").clicked() {
         commands.entity(entity).despawn_recursive();
+        // Name is not available after despawn, so label is stored with change
+        let label = name.map_or_else(
+            || format!("Delete Entity {}", entity.index()),
+            |name| format!("Delete {}", name),
+        );
         changes.send(NewChange {
-            change: Arc::new(RemovedEntity { entity }),
+            change: Arc::new(LabeledChange::new(label, RemovedEntity { entity })),
         });
         ui.close_menu();
     }
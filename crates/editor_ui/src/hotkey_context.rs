@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use space_editor_core::prelude::*;
+use space_editor_tabs::prelude::*;
+use space_shared::EditorState;
+
+use crate::editor_tab_name::EditorTabName;
+
+/// Plugin which derives [`ActiveHotkeyContext`] from egui focus and editor state
+pub struct HotkeyContextPlugin;
+
+impl Plugin for HotkeyContextPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveHotkeyContext>();
+        app.add_systems(PostUpdate, update_hotkey_context);
+    }
+}
+
+fn update_hotkey_context(
+    mut ctxs: EguiContexts,
+    state: Res<State<EditorState>>,
+    mut editor_ui: ResMut<EditorUi>,
+    mut active: ResMut<ActiveHotkeyContext>,
+) {
+    let Some(ctx) = ctxs.try_ctx_mut() else {
+        return;
+    };
+    let context = if ctx.wants_keyboard_input() {
+        HotkeyContext::TextEditing
+    } else if *state.get() != EditorState::Editor {
+        HotkeyContext::PlayMode
+    } else {
+        focused_tab_context(&mut editor_ui)
+    };
+    active.set_if_neq(ActiveHotkeyContext(context));
+}
+
+/// Context of focused dock tab. Tabs without own hotkeys have only [`HotkeyContext::Any`] hotkeys
+fn focused_tab_context(editor_ui: &mut EditorUi) -> HotkeyContext {
+    let Some((_, tab)) = editor_ui.tree.find_active_focused() else {
+        return HotkeyContext::Any;
+    };
+    if *tab == EditorTabName::GameView.into() {
+        HotkeyContext::Viewport
+    } else if *tab == EditorTabName::Hierarchy.into() {
+        HotkeyContext::Hierarchy
+    } else {
+        HotkeyContext::Any
+    }
+}
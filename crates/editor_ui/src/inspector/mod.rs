@@ -17,7 +17,10 @@ use bevy::{
 use bevy_egui::{egui::TextEdit, *};
 
 use space_editor_core::prelude::*;
-use space_prefab::{component::EntityLink, editor_registry::EditorRegistry};
+use space_prefab::{
+    component::{EntityLink, GltfAnimationPrefab},
+    editor_registry::EditorRegistry,
+};
 use space_shared::{
     ext::bevy_inspector_egui::{
         inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
@@ -25,7 +28,11 @@ use space_shared::{
     toast::{ToastKind, ToastMessage},
 };
 
-use crate::{editor_tab_name::EditorTabName, icons::add_component_icon};
+use crate::{
+    animation_preview::{animation_preview_ui, AnimationPreview},
+    editor_tab_name::EditorTabName,
+    icons::add_component_icon,
+};
 use space_editor_tabs::prelude::*;
 
 use self::{
@@ -267,6 +274,15 @@ impl EditorTab for InspectorTab {
                     }
                 });
 
+                if let (Some(prefab), Some(mut preview)) =
+                    (unsafe { e.get::<GltfAnimationPrefab>() }, unsafe {
+                        cell.get_resource_mut::<AnimationPreview>()
+                    })
+                {
+                    ui.separator();
+                    animation_preview_ui(ui, e.id(), prefab, &mut preview);
+                }
+
                 ui.separator();
             }
         });
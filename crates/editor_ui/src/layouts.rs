@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use space_editor_core::prelude::EditorCommandAppExt;
+use space_editor_tabs::prelude::*;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+/// Plugin which stores dock layout between sessions and manages layout presets
+pub struct EditorLayoutsPlugin;
+
+impl Plugin for EditorLayoutsPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorLayouts>()
+            .register_type::<LayoutPreset>()
+            .register_type::<Vec<LayoutPreset>>();
+
+        app.editor_command(
+            "layout.reset",
+            "Reset layout to default",
+            "Layout",
+            |world| {
+                if let Some(mut editor_ui) = world.get_resource_mut::<EditorUi>() {
+                    editor_ui.reset_layout();
+                }
+            },
+        );
+        for (id, label, run) in LAYOUT_PRESET_COMMANDS {
+            app.editor_command(id, label, "Layout", run);
+        }
+
+        #[cfg(feature = "persistence_editor")]
+        {
+            // Window arrangement is personal, so it is kept out of project file
+            app.persistence_resource::<EditorLayouts>()
+                .persistence_layer::<EditorLayouts>(PersistenceLayer::User);
+            app.add_systems(
+                Update,
+                (
+                    store_layout_before_save.in_set(PersistenceSet::EventReader),
+                    restore_layout_on_load.after(PersistenceSet::Collect),
+                ),
+            );
+        }
+    }
+}
+
+/// Commands which load layout presets by their order in layout menu
+const LAYOUT_PRESET_COMMANDS: [(&str, &str, fn(&mut World)); 4] = [
+    ("layout.preset_1", "Load layout preset 1", |world| {
+        load_layout_preset(world, 0)
+    }),
+    ("layout.preset_2", "Load layout preset 2", |world| {
+        load_layout_preset(world, 1)
+    }),
+    ("layout.preset_3", "Load layout preset 3", |world| {
+        load_layout_preset(world, 2)
+    }),
+    ("layout.preset_4", "Load layout preset 4", |world| {
+        load_layout_preset(world, 3)
+    }),
+];
+
+fn load_layout_preset(world: &mut World, idx: usize) {
+    let Some(preset) = world
+        .get_resource::<EditorLayouts>()
+        .and_then(|layouts| layouts.presets.get(idx).cloned())
+    else {
+        warn!("Layout preset {} is not saved", idx + 1);
+        return;
+    };
+    let Some(mut editor_ui) = world.get_resource_mut::<EditorUi>() else {
+        return;
+    };
+    if let Err(err) = editor_ui.load_layout(&preset.layout) {
+        error!("Failed to load layout preset {}: {}", preset.name, err);
+    }
+}
+
+#[cfg(feature = "persistence_editor")]
+fn store_layout_before_save(
+    mut events: EventReader<PersistenceEvent>,
+    editor_ui: Res<EditorUi>,
+    mut layouts: ResMut<EditorLayouts>,
+) {
+    if !events
+        .read()
+        .any(|event| matches!(event, PersistenceEvent::Save))
+    {
+        return;
+    }
+    match editor_ui.save_layout() {
+        Ok(layout) => layouts.current = layout,
+        Err(err) => error!("Failed to save editor layout: {}", err),
+    }
+}
+
+#[cfg(feature = "persistence_editor")]
+fn restore_layout_on_load(
+    mut events: EventReader<PersistenceLoaded<EditorLayouts>>,
+    mut editor_ui: ResMut<EditorUi>,
+    layouts: Res<EditorLayouts>,
+) {
+    if events.read().next().is_none() || layouts.current.is_empty() {
+        return;
+    }
+    if let Err(err) = editor_ui.load_layout(&layouts.current) {
+        warn!("Failed to restore editor layout: {}", err);
+    }
+}
+
+/// Menu with layout presets
+pub fn layout_menu(
+    ui: &mut egui::Ui,
+    editor_ui: &mut EditorUi,
+    layouts: &mut EditorLayouts,
+    new_preset_name: &mut String,
+) {
+    let mut removed = None;
+    for preset in layouts.presets.iter() {
+        ui.horizontal(|ui| {
+            if ui.button(&preset.name).clicked() {
+                if let Err(err) = editor_ui.load_layout(&preset.layout) {
+                    error!("Failed to load layout preset {}: {}", preset.name, err);
+                }
+                ui.close_menu();
+            }
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Delete preset")
+                .clicked()
+            {
+                removed = Some(preset.name.clone());
+            }
+        });
+    }
+    if let Some(name) = removed {
+        layouts.remove_preset(&name);
+    }
+    if !layouts.presets.is_empty() {
+        ui.separator();
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(new_preset_name).hint_text("Preset name"));
+        let can_save = !new_preset_name.trim().is_empty();
+        if ui
+            .add_enabled(can_save, egui::Button::new("Save"))
+            .on_hover_text("Save current layout as preset")
+            .clicked()
+        {
+            match editor_ui.save_layout() {
+                Ok(layout) => {
+                    layouts.set_preset(new_preset_name.trim(), layout);
+                    new_preset_name.clear();
+                }
+                Err(err) => error!("Failed to save layout preset: {}", err),
+            }
+        }
+    });
+
+    if ui.button("Reset to default").clicked() {
+        editor_ui.reset_layout();
+        ui.close_menu();
+    }
+}
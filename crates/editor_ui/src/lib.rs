@@ -1,5 +1,8 @@
 #![allow(clippy::type_complexity)]
 
+/// This module contains gltf animation preview for inspector
+pub mod animation_preview;
+
 /// This module contains ui logics, which will be work through events with editor core module and prefab module
 mod mouse_check;
 
@@ -12,21 +15,42 @@ pub mod menu_toolbars;
 /// This module contains UI logic for undo/redo functionality
 pub mod change_chain;
 
+/// This module contains command palette with fuzzy search of editor commands
+pub mod command_palette;
+
 /// This module contains UI logic for debug panels (like WorldInspector)
 pub mod debug_panels;
 
 /// This module contains Game view tab logic
 pub mod game_view;
 
+/// This module contains headless editor plugin and test API for editor logic
+pub mod headless;
+
 /// This module contains Hierarchy tab logic
 pub mod hierarchy;
 
+/// This module contains hotkey context detection from egui focus
+pub mod hotkey_context;
+
 /// This module contains Inspector tab logic
 pub mod inspector;
 
+/// This module contains dock layout persistence and layout presets
+pub mod layouts;
+
 /// This module contains methods to visualize entities without a mesh attached
 pub mod meshless_visualizer;
 
+/// This module contains Notifications tab with log of toast messages
+pub mod notifications;
+
+/// This module contains JSON-RPC server to control editor from external tools
+pub mod remote_control;
+
+/// This module contains per-scene view state (camera, selection, hierarchy) persistence
+pub mod scene_view_state;
+
 /// This module contains Settings tab logic
 pub mod settings;
 
@@ -120,14 +144,18 @@ pub const LAST_RENDER_LAYER: u8 = MAX_RENDER_LAYERS - 1;
 
 pub mod prelude {
     pub use super::{
+        animation_preview::*,
         asset_inspector::*,
         change_chain::*,
+        command_palette::*,
         debug_panels::*,
         game_view::*,
         hierarchy::*,
         inspector::*,
         menu_toolbars::*,
         meshless_visualizer::*,
+        notifications::*,
+        remote_control::*,
         settings::*,
         tool::*, //tools::*,
         ui_registration::*,
@@ -176,6 +204,8 @@ impl PluginGroup for EditorPluginGroup {
         let mut res = PluginGroupBuilder::start::<Self>()
             .add(EditorGizmoPlugin)
             .add(ToastUiPlugin)
+            .add(space_editor_core::journal::ChangeJournalUiPlugin)
+            .add(space_editor_core::autosave::AutosaveUiPlugin)
             .add(UndoPlugin)
             .add(SyncUndoMarkersPlugin::<PrefabMarker>::default())
             .add(PrefabPlugin)
@@ -321,6 +351,7 @@ fn clear_and_load_on_start(
         Some(space_shared::EditorPrefabPath::File(path)) => {
             info!("Loading prefab from file {}", path);
             load_server.scene = Some(assets.load(format!("{}.scn.ron", path)));
+            load_server.path = Some(format!("{}.scn.ron", path));
         }
         Some(space_shared::EditorPrefabPath::MemoryCache) => {
             info!("Loading prefab from cache");
@@ -1,6 +1,15 @@
 use std::sync::Arc;
 
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        archetype::Archetypes,
+        component::{ComponentId, Components},
+        entity::Entities,
+        system::SystemParam,
+    },
+    prelude::*,
+    utils::get_short_name,
+};
 use bevy_egui::{
     egui::{Align, Align2, Margin, Pos2, Stroke, Widget},
     *,
@@ -8,19 +17,24 @@ use bevy_egui::{
 use bevy_panorbit_camera::PanOrbitCamera;
 use egui_dock::egui::RichText;
 use space_editor_core::{
+    gltf_unpack::{EditorUnpackGltf, GltfImportOptions, GltfMaterialImport, GltfUpAxis},
     prelude::*,
     toast::{ClearToastMessage, ToastStorage},
 };
 use space_editor_tabs::prelude::*;
-use space_prefab::{component::GltfPrefab, load::PrefabBundle, plugins::PrefabPlugin};
+use space_prefab::{
+    component::GltfPrefab, editor_registry::EditorRegistry, load::PrefabBundle,
+    plugins::PrefabPlugin,
+};
 use space_shared::{ext::egui_file, *};
-use space_undo::{AddedEntity, NewChange, RemovedEntity};
+use space_undo::{AddedEntity, ChangeChain, LabeledChange, NewChange, RemovedEntity, UndoRedo};
 
 use crate::{
     hierarchy::{HierarchyQueryIter, HierarchyTabState},
     icons::{add_bundle_icon, add_entity_icon, delete_entity_icon, prefab_icon},
+    layouts::layout_menu,
     sizing::{to_colored_richtext, to_richtext},
-    ui_registration::{BundleReg, EditorBundleUntyped},
+    ui_registration::{spawn_editor_bundle, BundleReg, EditorBundleUntyped},
     ShowEditorUi,
 };
 
@@ -53,11 +67,90 @@ impl Plugin for BottomMenuPlugin {
                 .in_set(EditorSet::Editor)
                 .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
         );
+        app.add_systems(
+            Update,
+            gltf_import_dialog
+                .after(top_menu)
+                .in_set(EditorSet::Editor)
+                .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
+        );
+        app.add_systems(
+            Update,
+            load_report_dialog
+                .after(EditorLoadSet)
+                .in_set(EditorSet::Editor)
+                .run_if(in_state(EditorState::Editor).and_then(in_state(ShowEditorUi::Show))),
+        );
         app.add_systems(Update, in_game_menu.in_set(EditorSet::Game));
+        app.editor_hotkey_in_context(
+            PlaymodeHotkey::Pause,
+            vec![GamepadButtonType::Start],
+            HotkeyContext::PlayMode,
+        );
+        app.editor_hotkey_in_context(
+            PlaymodeHotkey::Step,
+            vec![GamepadButtonType::Select],
+            HotkeyContext::PlayMode,
+        );
         app.add_event::<MenuLoadEvent>();
+
+        app.playmode_command("game.pause", "Pause or resume game", "Game", toggle_pause)
+            .playmode_command("game.step", "Step by delta time", "Game", step_game)
+            .editor_command("scene.open", "Open scene", "Scene", |world| {
+                world.resource_mut::<MenuToolbarState>().load_dialog = Some(load_scene_dialog());
+            })
+            .editor_command("scene.save_as", "Save scene as", "Scene", |world| {
+                world.resource_mut::<MenuToolbarState>().save_dialog = Some(save_scene_dialog());
+            })
+            .editor_command("scene.import_gltf", "Import GLTF", "Scene", |world| {
+                world.resource_mut::<MenuToolbarState>().gltf_dialog = Some(open_gltf_dialog());
+            });
     }
 }
 
+fn toggle_pause(world: &mut World) {
+    let mut time = world.resource_mut::<Time<Virtual>>();
+    if time.is_paused() {
+        time.unpause();
+    } else {
+        time.pause();
+    }
+}
+
+fn step_game(world: &mut World) {
+    let mut time = world.resource_mut::<Time<Virtual>>();
+    let frame_duration = time.delta();
+    time.advance_by(frame_duration);
+}
+
+fn load_scene_dialog() -> egui_file::FileDialog {
+    let mut dialog = egui_file::FileDialog::open_file(Some("assets/scenes".into()))
+        .show_files_filter(Box::new(|path| {
+            path.to_str().unwrap().ends_with(".scn.ron")
+        }))
+        .title("Load Scene (*.scn.ron)");
+    dialog.open();
+    dialog
+}
+
+fn save_scene_dialog() -> egui_file::FileDialog {
+    let mut dialog = egui_file::FileDialog::save_file(Some("./assets/scenes".into()))
+        .default_filename("Scene0.scn.ron")
+        .title("Save Scene");
+    dialog.open();
+    dialog
+}
+
+fn open_gltf_dialog() -> egui_file::FileDialog {
+    let mut dialog = egui_file::FileDialog::open_file(Some("assets/models".into()))
+        .show_files_filter(Box::new(|path| {
+            path.to_str().unwrap().ends_with(".gltf") || path.to_str().unwrap().ends_with(".glb")
+        }))
+        .title("Opens GLTF as Prefab");
+    dialog.open();
+    dialog
+}
+
 #[derive(Event)]
 pub struct MenuLoadEvent {
     pub path: String,
@@ -73,6 +166,21 @@ impl Default for FrameSpeedMultiplier {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum PlaymodeHotkey {
+    Pause,
+    Step,
+}
+
+impl Hotkey for PlaymodeHotkey {
+    fn name(&self) -> String {
+        match self {
+            Self::Pause => "Pause or resume game".to_string(),
+            Self::Step => "Step by delta time".to_string(),
+        }
+    }
+}
+
 fn in_game_menu(
     mut smoothed_dt: Local<f32>,
     mut frame_speed_mult: Local<FrameSpeedMultiplier>,
@@ -80,11 +188,12 @@ fn in_game_menu(
     mut state: ResMut<NextState<EditorState>>,
     mut time: ResMut<Time<Virtual>>,
     sizing: Res<Sizing>,
+    hotkeys: Res<ButtonInput<PlaymodeHotkey>>,
+    mut run_command: EventWriter<RunEditorCommand>,
 ) {
     egui::TopBottomPanel::top("top_gameplay_panel")
         .min_height(&sizing.icon.to_size() + 8.)
         .show(ctxs.ctx_mut(), |ui| {
-            let frame_duration = time.delta();
             if !time.is_paused() {
                 *smoothed_dt = (*smoothed_dt).mul_add(0.98, time.delta_seconds() * 0.02);
             }
@@ -99,12 +208,8 @@ fn in_game_menu(
                 } else {
                     to_richtext("⏸", &sizing.icon)
                 };
-                if ui.button(button).clicked() {
-                    if time.is_paused() {
-                        time.unpause();
-                    } else {
-                        time.pause();
-                    }
+                if ui.button(button).clicked() || hotkeys.just_pressed(PlaymodeHotkey::Pause) {
+                    run_command.send(RunEditorCommand("game.pause".to_string()));
                 }
                 if ui.button(to_richtext("⏹", &sizing.icon)).clicked() {
                     state.set(EditorState::Editor);
@@ -113,8 +218,9 @@ fn in_game_menu(
                     .button(to_richtext("⏭", &sizing.icon))
                     .on_hover_text("Step by delta time")
                     .clicked()
+                    || hotkeys.just_pressed(PlaymodeHotkey::Step)
                 {
-                    time.advance_by(frame_duration);
+                    run_command.send(RunEditorCommand("game.step".to_string()));
                 }
 
                 ui.add_space(60.);
@@ -140,6 +246,120 @@ fn in_game_menu(
         });
 }
 
+/// Selection history, selection sets and selection commands for top menu
+#[derive(SystemParam)]
+pub struct SelectionMenu<'w, 's> {
+    history: Res<'w, SelectionHistory>,
+    actions: EventWriter<'w, SelectionAction>,
+    sets: Query<'w, 's, &'static SelectionSets>,
+    selected: Query<'w, 's, Entity, With<Selected>>,
+    entities: &'w Entities,
+    archetypes: &'w Archetypes,
+    components: &'w Components,
+    registry: Res<'w, EditorRegistry>,
+}
+
+impl SelectionMenu<'_, '_> {
+    /// Editor components of first selected entity
+    fn selected_components(&self) -> Vec<(String, ComponentId)> {
+        let Some(archetype) = self
+            .selected
+            .iter()
+            .next()
+            .and_then(|entity| self.entities.get(entity))
+            .and_then(|location| self.archetypes.get(location.archetype_id))
+        else {
+            return vec![];
+        };
+        let registry = self.registry.registry.read();
+        let mut components = archetype
+            .components()
+            .filter_map(|id| {
+                let info = self.components.get_info(id)?;
+                let type_id = info.type_id()?;
+                if registry.get(type_id).is_none() || self.registry.silent.contains(&type_id) {
+                    return None;
+                }
+                Some((get_short_name(info.name()), id))
+            })
+            .collect::<Vec<_>>();
+        components.sort();
+        components
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, set_name: &mut String) {
+        let mut action = None;
+        if ui
+            .add_enabled(self.history.can_go_back(), egui::Button::new("Back"))
+            .clicked()
+        {
+            action = Some(SelectionAction::Back);
+        }
+        if ui
+            .add_enabled(self.history.can_go_forward(), egui::Button::new("Forward"))
+            .clicked()
+        {
+            action = Some(SelectionAction::Forward);
+        }
+        ui.separator();
+
+        let has_selection = !self.selected.is_empty();
+        for (label, selection_action) in [
+            ("Children", SelectionAction::SelectChildren),
+            ("Parent", SelectionAction::SelectParent),
+            ("Same prefab source", SelectionAction::SamePrefabSource),
+            ("Invert", SelectionAction::Invert),
+        ] {
+            if ui
+                .add_enabled(has_selection, egui::Button::new(label))
+                .clicked()
+            {
+                action = Some(selection_action);
+            }
+        }
+        let components = self.selected_components();
+        ui.add_enabled_ui(!components.is_empty(), |ui| {
+            ui.menu_button("Same component", |ui| {
+                for (name, id) in components {
+                    if ui.button(name).clicked() {
+                        action = Some(SelectionAction::SameComponent(id));
+                    }
+                }
+            });
+        });
+        ui.separator();
+
+        ui.label("Selection sets");
+        for name in selection_set_names(self.sets.iter()) {
+            ui.horizontal(|ui| {
+                if ui.button(&name).clicked() {
+                    action = Some(SelectionAction::SelectSet(name.clone()));
+                }
+                if ui.small_button("🗑").on_hover_text("Remove set").clicked() {
+                    action = Some(SelectionAction::RemoveSet(name.clone()));
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(set_name).hint_text("Set name"));
+            if ui
+                .add_enabled(
+                    has_selection && !set_name.is_empty(),
+                    egui::Button::new("Save selection"),
+                )
+                .clicked()
+            {
+                action = Some(SelectionAction::SaveSet(std::mem::take(set_name)));
+            }
+        });
+
+        if let Some(action) = action {
+            self.actions.send(action);
+            ui.close_menu();
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct MenuToolbarState {
     pub file_dialog: Option<egui_file::FileDialog>,
@@ -149,6 +369,189 @@ pub struct MenuToolbarState {
     pub subscene_dialog: Option<egui_file::FileDialog>,
     show_toasts: bool,
     pub path: String,
+    /// Name of new layout preset
+    pub layout_preset_name: String,
+    /// Name of new selection set
+    pub selection_set_name: String,
+    /// Gltf file waiting for import options confirmation
+    pub gltf_import_path: Option<String>,
+}
+
+/// Window with import options of selected gltf file
+fn gltf_import_dialog(
+    mut ctxs: EguiContexts,
+    mut menu_state: ResMut<MenuToolbarState>,
+    mut options: ResMut<GltfImportOptions>,
+    mut unpack_events: EventWriter<EditorUnpackGltf>,
+) {
+    let Some(path) = menu_state.gltf_import_path.clone() else {
+        return;
+    };
+    let mut open = true;
+    let mut import = false;
+    egui::Window::new("GLTF Import Options")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0., 0.])
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(&path);
+            ui.separator();
+            egui::Grid::new("gltf_import_options")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Materials");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(
+                            &mut options.materials,
+                            GltfMaterialImport::AssetReference,
+                            "Asset references",
+                        );
+                        ui.radio_value(
+                            &mut options.materials,
+                            GltfMaterialImport::Prefab,
+                            "Editable prefabs",
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label("Scene");
+                    ui.horizontal(|ui| {
+                        let mut all_scenes = options.scene.is_none();
+                        if ui.checkbox(&mut all_scenes, "All").changed() {
+                            options.scene = if all_scenes { None } else { Some(0) };
+                        }
+                        if let Some(scene) = &mut options.scene {
+                            ui.add(egui::DragValue::new(scene).prefix("Index "));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Root scale");
+                    ui.add(
+                        egui::DragValue::new(&mut options.scale)
+                            .speed(0.01)
+                            .range(0.0001..=f32::MAX),
+                    );
+                    ui.end_row();
+
+                    ui.label("Up axis");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut options.up_axis, GltfUpAxis::Y, "Y");
+                        ui.radio_value(&mut options.up_axis, GltfUpAxis::Z, "Z");
+                    });
+                    ui.end_row();
+                });
+            ui.checkbox(&mut options.import_lights, "Import lights");
+            ui.checkbox(&mut options.import_cameras, "Import cameras");
+            ui.checkbox(&mut options.import_animations, "Import animations");
+            ui.checkbox(&mut options.merge_static_meshes, "Merge static meshes")
+                .on_hover_text(
+                    "Merge primitives of static mesh nodes, which share material, into one mesh under their nearest non static parent",
+                );
+            ui.separator();
+            ui.horizontal(|ui| {
+                import = ui.button("Import").clicked();
+                if ui.button("Reset").clicked() {
+                    *options = GltfImportOptions::default();
+                }
+            });
+        });
+
+    if import {
+        unpack_events.send(EditorUnpackGltf {
+            path,
+            options: options.clone(),
+        });
+    }
+    if import || !open {
+        menu_state.gltf_import_path = None;
+    }
+}
+
+/// Window with components which were not loaded with last scene
+fn load_report_dialog(
+    mut commands: Commands,
+    mut ctxs: EguiContexts,
+    mut report: ResMut<LoadReport>,
+    names: Query<Option<&Name>>,
+    selected: Query<Entity, With<Selected>>,
+) {
+    if !report.open {
+        return;
+    }
+    let mut open = true;
+    let mut select = None;
+    egui::Window::new("Load Report")
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(500.)
+        .anchor(Align2::CENTER_CENTER, [0., 0.])
+        .show(ctxs.ctx_mut(), |ui| {
+            ui.label(format!(
+                "{} loaded with {} issues",
+                report.scene.as_deref().unwrap_or("Scene"),
+                report.issues.len()
+            ));
+            ui.label("Components which were not loaded are kept and written back on save");
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(300.)
+                .show(ui, |ui| {
+                    egui::Grid::new("load_report_issues")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Entity");
+                            ui.strong("Type");
+                            ui.strong("Problem");
+                            ui.end_row();
+                            for issue in report.issues.iter() {
+                                match issue.entity {
+                                    Some(entity) => {
+                                        let label = match names.get(entity) {
+                                            Ok(Some(name)) => format!("{} ({:?})", name, entity),
+                                            Ok(None) => format!("{:?}", entity),
+                                            Err(_) => format!("{:?} (despawned)", entity),
+                                        };
+                                        if ui
+                                            .add_enabled(
+                                                names.contains(entity),
+                                                egui::Button::new(label).small(),
+                                            )
+                                            .on_hover_text("Select entity")
+                                            .clicked()
+                                        {
+                                            select = Some(entity);
+                                        }
+                                    }
+                                    None => {
+                                        ui.label("Resource");
+                                    }
+                                }
+                                ui.label(get_short_name(&issue.type_path))
+                                    .on_hover_text(&issue.type_path);
+                                ui.colored_label(WARN_COLOR, issue.kind.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+            ui.separator();
+            if ui.button("📋 Copy report").clicked() {
+                let text = report.text();
+                ui.output_mut(|output| output.copied_text = text);
+            }
+        });
+
+    if let Some(entity) = select {
+        for e in selected.iter() {
+            commands.entity(e).remove::<Selected>();
+        }
+        commands.entity(entity).insert(Selected);
+    }
+    if !open {
+        report.open = false;
+    }
 }
 
 pub fn bottom_menu(
@@ -184,7 +587,10 @@ pub fn bottom_menu(
                         commands.entity(entity).despawn_recursive();
 
                         changes.send(NewChange {
-                            change: Arc::new(RemovedEntity { entity }),
+                            change: Arc::new(LabeledChange::new(
+                                "Clear all entities",
+                                RemovedEntity { entity },
+                            )),
                         });
                     }
                 }
@@ -242,21 +648,28 @@ pub fn bottom_menu(
                                         )> = category_bundle.iter().collect();
                                         categories_vec.sort_by(|a, b| a.0.cmp(b.0));
 
-                                        for (name, dyn_bundle) in categories_vec {
+                                        for (name, _) in categories_vec {
                                             let button = egui::Button::new(name).ui(ui);
                                             if button.clicked() {
-                                                let entity = dyn_bundle.spawn(&mut commands);
-                                                if let Ok(pan_cam) = q_pan_cam.get_single() {
-                                                    commands.entity(entity).insert(
-                                                        SpatialBundle::from_transform(
-                                                            Transform::from_translation(
-                                                                pan_cam.focus,
+                                                let category = category_name.clone();
+                                                let name = name.clone();
+                                                let focus = q_pan_cam
+                                                    .get_single()
+                                                    .ok()
+                                                    .map(|pan_cam| pan_cam.focus);
+                                                commands.add(move |world: &mut World| {
+                                                    let Some(entity) = spawn_editor_bundle(
+                                                        world, &category, &name,
+                                                    ) else {
+                                                        return;
+                                                    };
+                                                    if let Some(focus) = focus {
+                                                        world.entity_mut(entity).insert(
+                                                            SpatialBundle::from_transform(
+                                                                Transform::from_translation(focus),
                                                             ),
-                                                        ),
-                                                    );
-                                                }
-                                                changes.send(NewChange {
-                                                    change: Arc::new(AddedEntity { entity }),
+                                                        );
+                                                    }
                                                 });
                                             }
                                         }
@@ -293,9 +706,15 @@ pub fn top_menu(
     mut menu_state: ResMut<MenuToolbarState>,
     mut editor_events: EventWriter<EditorEvent>,
     mut clear_toast: EventWriter<ClearToastMessage>,
-    background_tasks: Res<BackgroundTaskStorage>,
+    mut background_tasks: ResMut<BackgroundTaskStorage>,
     toasts: Res<ToastStorage>,
     sizing: Res<Sizing>,
+    change_chain: Res<ChangeChain>,
+    names: Query<&Name>,
+    mut undo_redo: EventWriter<UndoRedo>,
+    mut editor_ui: ResMut<EditorUi>,
+    mut layouts: ResMut<EditorLayouts>,
+    mut selection: SelectionMenu,
 ) {
     let ctx = ctxs.ctx_mut();
     egui::TopBottomPanel::top("top_menu_bar")
@@ -306,6 +725,51 @@ pub fn top_menu(
                 let stl = ui.style_mut();
                 stl.spacing.button_padding = egui::Vec2::new(8., 4.);
 
+                // Edit menu
+                ui.menu_button(RichText::new("Edit").size(sizing.text), |ui| {
+                    let name_of = |entity| names.get(entity).ok().map(|name| name.to_string());
+
+                    let undo_label = change_chain.undo_label(&name_of);
+                    let undo_button = egui::Button::new(
+                        undo_label
+                            .as_ref()
+                            .map_or_else(|| "Undo".to_string(), |label| format!("Undo: {}", label)),
+                    );
+                    if ui.add_enabled(undo_label.is_some(), undo_button).clicked() {
+                        undo_redo.send(UndoRedo::Undo);
+                        ui.close_menu();
+                    }
+
+                    let redo_label = change_chain.redo_label(&name_of);
+                    let redo_button = egui::Button::new(
+                        redo_label
+                            .as_ref()
+                            .map_or_else(|| "Redo".to_string(), |label| format!("Redo: {}", label)),
+                    );
+                    if ui.add_enabled(redo_label.is_some(), redo_button).clicked() {
+                        undo_redo.send(UndoRedo::Redo);
+                        ui.close_menu();
+                    }
+                });
+                // END Edit menu
+
+                // Select menu
+                ui.menu_button(RichText::new("Select").size(sizing.text), |ui| {
+                    selection.ui(ui, &mut menu_state.selection_set_name);
+                });
+                // END Select menu
+
+                // Layout menu
+                ui.menu_button(RichText::new("Layout").size(sizing.text), |ui| {
+                    layout_menu(
+                        ui,
+                        &mut editor_ui,
+                        &mut layouts,
+                        &mut menu_state.layout_preset_name,
+                    );
+                });
+                // END Layout menu
+
                 // Open Assets Folder
                 let open_button = egui::Button::new(to_richtext("📂", &sizing.icon))
                     .stroke(stroke_default_color());
@@ -358,12 +822,7 @@ pub fn top_menu(
                     .on_hover_text("Save current scene")
                     .clicked()
                 {
-                    let mut save_dialog =
-                        egui_file::FileDialog::save_file(Some("./assets/scenes".into()))
-                            .default_filename("Scene0.scn.ron")
-                            .title("Save Scene");
-                    save_dialog.open();
-                    menu_state.save_dialog = Some(save_dialog);
+                    menu_state.save_dialog = Some(save_scene_dialog());
                 }
 
                 if let Some(save_dialog) = &mut menu_state.save_dialog {
@@ -403,13 +862,7 @@ pub fn top_menu(
                     .on_hover_text("Load scene file")
                     .clicked()
                 {
-                    let mut dialog = egui_file::FileDialog::open_file(Some("assets/scenes".into()))
-                        .show_files_filter(Box::new(|path| {
-                            path.to_str().unwrap().ends_with(".scn.ron")
-                        }))
-                        .title("Load Scene (*.scn.ron)");
-                    dialog.open();
-                    menu_state.load_dialog = Some(dialog);
+                    menu_state.load_dialog = Some(load_scene_dialog());
                 }
 
                 if let Some(dialog) = &mut menu_state.load_dialog {
@@ -451,15 +904,7 @@ pub fn top_menu(
                     .on_hover_text("Open GLTF/GLB as prefab")
                     .clicked()
                 {
-                    let mut gltf_dialog =
-                        egui_file::FileDialog::open_file(Some("assets/models".into()))
-                            .show_files_filter(Box::new(|path| {
-                                path.to_str().unwrap().ends_with(".gltf")
-                                    || path.to_str().unwrap().ends_with(".glb")
-                            }))
-                            .title("Opens GLTF as Prefab");
-                    gltf_dialog.open();
-                    menu_state.gltf_dialog = Some(gltf_dialog);
+                    menu_state.gltf_dialog = Some(open_gltf_dialog());
                 }
 
                 if let Some(gltf_dialog) = &mut menu_state.gltf_dialog {
@@ -469,7 +914,7 @@ pub fn top_menu(
                             if path.starts_with("assets/") {
                                 path = path.replace("assets/", "");
 
-                                editor_events.send(EditorEvent::LoadGltfAsPrefab(path));
+                                menu_state.gltf_import_path = Some(path);
                             }
                         }
                     } else {
@@ -616,17 +1061,7 @@ pub fn top_menu(
                         menu_state.show_toasts = !menu_state.show_toasts;
                     }
 
-                    if !background_tasks.tasks.is_empty() {
-                        //Spinning circle
-                        ui.spinner();
-
-                        match &background_tasks.tasks[0] {
-                            BackgroundTask::AssetLoading(path, _) => {
-                                ui.label(format!("Loading {}", path));
-                            }
-                            BackgroundTask::None => {}
-                        }
-                    }
+                    background_tasks_status(ui, &mut background_tasks);
                 });
             });
         });
@@ -640,3 +1075,51 @@ pub fn top_menu(
     }
     events.clear();
 }
+
+/// Spinner with label of running background tasks. Menu lists progress of every task and allows to cancel it
+fn background_tasks_status(ui: &mut egui::Ui, storage: &mut BackgroundTaskStorage) {
+    let running = storage
+        .tasks
+        .iter()
+        .filter(|task| !matches!(task, BackgroundTask::None))
+        .collect::<Vec<_>>();
+    let Some(first) = running.first() else {
+        return;
+    };
+    let title = if running.len() == 1 {
+        first.label()
+    } else {
+        format!("{} (+{} tasks)", first.label(), running.len() - 1)
+    };
+
+    //Spinning circle
+    ui.spinner();
+    let mut cancel = None;
+    ui.menu_button(title, |ui| {
+        egui::Grid::new("background_tasks").show(ui, |ui| {
+            for (index, task) in storage.tasks.iter().enumerate() {
+                if matches!(task, BackgroundTask::None) {
+                    continue;
+                }
+                let progress = task.progress();
+                ui.label(task.label());
+                match progress.fraction {
+                    Some(fraction) => ui.add(
+                        egui::ProgressBar::new(fraction)
+                            .desired_width(120.)
+                            .show_percentage(),
+                    ),
+                    None => ui.spinner(),
+                };
+                ui.label(progress.label);
+                if ui.small_button("✖").on_hover_text("Cancel").clicked() {
+                    cancel = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    });
+    if let Some(index) = cancel {
+        storage.cancel(index);
+    }
+}
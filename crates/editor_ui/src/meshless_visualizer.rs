@@ -17,7 +17,7 @@ use space_prefab::editor_registry::EditorRegistryExt;
 use space_shared::*;
 
 use crate::{EditorGizmo, LAST_RENDER_LAYER};
-use space_editor_core::selected::Selected;
+use space_editor_core::selected::{Hovered, PrimarySelected, Selected, SelectionHighlightSettings};
 
 #[derive(Default)]
 pub struct MeshlessVisualizerPlugin;
@@ -39,7 +39,12 @@ impl Plugin for MeshlessVisualizerPlugin {
             .add_plugins(BillboardPlugin)
             .add_systems(
                 Update,
-                (visualize_meshless, visualize_custom_meshless).in_set(EditorSet::Editor),
+                (
+                    visualize_meshless,
+                    visualize_custom_meshless,
+                    highlight_meshless_icons,
+                )
+                    .in_set(EditorSet::Editor),
             )
             .editor_registry::<CustomMeshless>();
     }
@@ -370,6 +375,45 @@ pub fn visualize_custom_meshless(
     }
 }
 
+/// Radius of highlight circle around meshless icon
+const ICON_HIGHLIGHT_RADIUS: f32 = 1.2;
+
+/// Meshless entities have no mesh to outline, so selection and hover highlight
+/// is drawn around their icons
+pub fn highlight_meshless_icons(
+    mut gizmos: Gizmos<EditorGizmo>,
+    settings: Res<SelectionHighlightSettings>,
+    cameras: Query<&GlobalTransform, With<EditorCameraMarker>>,
+    billboards: Query<(&GlobalTransform, &Parent), With<BillboardTextureHandle>>,
+    objects: Query<(&GlobalTransform, &SelectParent, &Visibility), Without<BillboardTextureHandle>>,
+    highlighted: Query<(Has<Selected>, Has<PrimarySelected>), Or<(With<Selected>, With<Hovered>)>>,
+) {
+    let Some(camera) = cameras.iter().next() else {
+        return;
+    };
+    let icons = billboards
+        .iter()
+        .map(|(transform, parent)| (transform, parent.get()))
+        .chain(
+            objects
+                .iter()
+                // Hidden spheres only make billboards clickable
+                .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+                .map(|(transform, select, _)| (transform, select.parent)),
+        );
+    for (transform, entity) in icons {
+        let Ok((selected, primary)) = highlighted.get(entity) else {
+            continue;
+        };
+        let (color, _) = settings.highlight(selected, primary);
+        let position = transform.translation();
+        let Ok(normal) = Dir3::new(camera.translation() - position) else {
+            continue;
+        };
+        gizmos.circle(position, normal, ICON_HIGHLIGHT_RADIUS, color);
+    }
+}
+
 pub fn clean_meshless(
     mut commands: Commands,
     // this covers all entities that are the children of the lights and Cameras
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy_egui::egui::{self, RichText};
+use space_editor_core::{
+    prelude::Selected,
+    toast::{ToastKind, ToastLogEntry, ToastStorage},
+};
+use space_editor_tabs::prelude::*;
+
+use crate::{
+    colors::{ERROR_COLOR, PLAY_COLOR, WARN_COLOR},
+    editor_tab_name::EditorTabName,
+};
+
+pub struct NotificationsTabPlugin;
+
+impl Plugin for NotificationsTabPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.editor_tab_by_trait(NotificationsTab::default());
+    }
+}
+
+/// Tab with log of all toast messages
+#[derive(Resource)]
+pub struct NotificationsTab {
+    pub search: String,
+    pub show_info: bool,
+    pub show_success: bool,
+    pub show_warning: bool,
+    pub show_error: bool,
+}
+
+impl Default for NotificationsTab {
+    fn default() -> Self {
+        Self {
+            search: String::new(),
+            show_info: true,
+            show_success: true,
+            show_warning: true,
+            show_error: true,
+        }
+    }
+}
+
+impl NotificationsTab {
+    /// Entry passes severity filter and contains search text in message or details
+    pub fn is_visible(&self, entry: &ToastLogEntry) -> bool {
+        let kind_visible = match entry.kind {
+            ToastKind::Info => self.show_info,
+            ToastKind::Success => self.show_success,
+            ToastKind::Warning => self.show_warning,
+            ToastKind::Error => self.show_error,
+            ToastKind::Custom(_) => true,
+        };
+        let search = self.search.to_lowercase();
+        kind_visible
+            && (search.is_empty()
+                || entry.text.to_lowercase().contains(&search)
+                || entry
+                    .details
+                    .as_ref()
+                    .is_some_and(|details| details.to_lowercase().contains(&search)))
+    }
+}
+
+fn kind_label(kind: ToastKind) -> RichText {
+    match kind {
+        ToastKind::Warning => RichText::new("⚠").color(WARN_COLOR),
+        ToastKind::Error => RichText::new("❗").color(ERROR_COLOR),
+        ToastKind::Success => RichText::new("✔").color(PLAY_COLOR),
+        _ => RichText::new("ℹ"),
+    }
+}
+
+impl EditorTab for NotificationsTab {
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, world: &mut World) {
+        let mut clear = false;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_error, "Errors");
+            ui.checkbox(&mut self.show_warning, "Warnings");
+            ui.checkbox(&mut self.show_info, "Info");
+            ui.checkbox(&mut self.show_success, "Success");
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"));
+            clear = ui.button("Clear").clicked();
+        });
+        ui.separator();
+
+        let Some(storage) = world.get_resource::<ToastStorage>() else {
+            error!("Failed to get toast storage");
+            return;
+        };
+
+        let mut select = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for (idx, entry) in storage.log.iter().enumerate() {
+                    if !self.is_visible(entry) {
+                        continue;
+                    }
+                    ui.push_id(idx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(entry.time_label()).weak());
+                            ui.label(kind_label(entry.kind));
+                            ui.label(&entry.text);
+                            if ui
+                                .small_button("📋")
+                                .on_hover_text("Copy to clipboard")
+                                .clicked()
+                            {
+                                ui.output_mut(|output| {
+                                    output.copied_text = entry.clipboard_text();
+                                });
+                            }
+                            if let Some(entity) = entry.entity {
+                                let exists = world.get_entity(entity).is_some();
+                                if ui
+                                    .add_enabled(
+                                        exists,
+                                        egui::Button::new(format!("{:?}", entity)).small(),
+                                    )
+                                    .on_hover_text("Select entity")
+                                    .on_disabled_hover_text("Entity no longer exists")
+                                    .clicked()
+                                {
+                                    select = Some(entity);
+                                }
+                            }
+                        });
+                        if let Some(details) = &entry.details {
+                            egui::CollapsingHeader::new("Details")
+                                .id_source(idx)
+                                .show(ui, |ui| {
+                                    ui.label(details);
+                                });
+                        }
+                    });
+                }
+            });
+
+        if clear {
+            world.resource_mut::<ToastStorage>().log.clear();
+        }
+        if let Some(entity) = select {
+            let selected = world
+                .query_filtered::<Entity, With<Selected>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            for e in selected {
+                world.entity_mut(e).remove::<Selected>();
+            }
+            world.entity_mut(entity).insert(Selected);
+        }
+    }
+
+    fn tab_name(&self) -> space_editor_tabs::tab_name::TabNameHolder {
+        EditorTabName::Notifications.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    fn entry(text: &str, kind: ToastKind, details: Option<&str>) -> ToastLogEntry {
+        ToastLogEntry {
+            text: text.to_string(),
+            kind,
+            entity: None,
+            details: details.map(ToString::to_string),
+            timestamp: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn filter_by_kind_and_search() {
+        let mut tab = NotificationsTab::default();
+        let error = entry(
+            "Failed to create scene",
+            ToastKind::Error,
+            Some("Missing Foo"),
+        );
+        let info = entry("Prefab loaded", ToastKind::Info, None);
+        assert!(tab.is_visible(&error));
+        assert!(tab.is_visible(&info));
+
+        tab.show_info = false;
+        assert!(!tab.is_visible(&info));
+
+        tab.search = "missing foo".to_string();
+        assert!(tab.is_visible(&error));
+        tab.search = "Saved".to_string();
+        assert!(!tab.is_visible(&error));
+    }
+}
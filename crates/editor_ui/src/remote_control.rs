@@ -0,0 +1,733 @@
+use std::path::{Component, Path};
+
+use bevy::{
+    prelude::*,
+    reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer},
+};
+use serde::{de::DeserializeSeed, Deserialize};
+use serde_json::{json, Value};
+use space_editor_core::prelude::*;
+use space_prefab::{editor_registry::EditorRegistry, save::SaveConfig};
+use space_shared::{
+    local_socket::{random_token, tokens_match, LocalServer},
+    EditorEvent, EditorPrefabPath, EditorSet, PrefabMarker, ASSETS_DIR,
+};
+use space_undo::UndoRedo;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::{AppPersistenceExt, PersistenceLayer};
+
+use crate::ui_registration::{spawn_editor_bundle, BundleReg};
+
+pub const DEFAULT_REMOTE_CONTROL_PORT: u16 = 15710;
+/// Environment variable with session token. Random token is generated if it is not set
+pub const REMOTE_CONTROL_TOKEN_ENV: &str = "SPACE_EDITOR_REMOTE_TOKEN";
+
+/// Plugin to control editor from external tools with JSON-RPC 2.0 over local TCP socket
+///
+/// Each request and response is one line of JSON. Server is disabled by default
+/// and can be enabled in settings with [`RemoteControlSettings`].
+/// First request of connection must be `session.authenticate` with [`RemoteControlServer::token`]
+pub struct RemoteControlPlugin;
+
+impl Plugin for RemoteControlPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RemoteControlSettings>()
+            .register_type::<RemoteControlSettings>()
+            .init_resource::<RemoteControlServer>();
+
+        #[cfg(feature = "persistence_editor")]
+        app.persistence_resource::<RemoteControlSettings>()
+            .persistence_layer::<RemoteControlSettings>(PersistenceLayer::User);
+
+        app.add_systems(Update, update_remote_control.in_set(EditorSet::Editor));
+    }
+}
+
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+pub struct RemoteControlSettings {
+    pub enabled: bool,
+    /// Port on localhost. Server never listens on other interfaces
+    pub port: u16,
+}
+
+impl Default for RemoteControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_REMOTE_CONTROL_PORT,
+        }
+    }
+}
+
+/// Listening socket and connected clients of remote control
+#[derive(Resource)]
+pub struct RemoteControlServer {
+    pub server: LocalServer,
+    /// Token of this editor session, which clients must send in `session.authenticate`
+    pub token: String,
+}
+
+impl Default for RemoteControlServer {
+    fn default() -> Self {
+        let token = std::env::var(REMOTE_CONTROL_TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.is_empty())
+            .unwrap_or_else(random_token);
+        Self {
+            server: LocalServer::default(),
+            token,
+        }
+    }
+}
+
+fn update_remote_control(world: &mut World) {
+    let settings = world.resource::<RemoteControlSettings>().clone();
+    let requests = {
+        let server = &mut world.resource_mut::<RemoteControlServer>().server;
+        server.sync(settings.enabled.then_some(settings.port));
+        server.accept();
+        server.read_lines()
+    };
+
+    for (connection, line) in requests {
+        let remote = world.resource::<RemoteControlServer>();
+        let response = if remote.server.is_authenticated(connection) {
+            handle_remote_request(world, &line)
+        } else {
+            match authenticate_request(&line, &remote.token) {
+                Ok(response) => {
+                    let server = &mut world.resource_mut::<RemoteControlServer>().server;
+                    server.authenticate(connection);
+                    Some(response)
+                }
+                Err(response) => {
+                    let server = &mut world.resource_mut::<RemoteControlServer>().server;
+                    if let Some(response) = response {
+                        server.send(connection, &response);
+                    }
+                    server.disconnect(connection);
+                    None
+                }
+            }
+        };
+        if let Some(response) = response {
+            let server = &mut world.resource_mut::<RemoteControlServer>().server;
+            server.send(connection, &response);
+        }
+    }
+    world.resource_mut::<RemoteControlServer>().server.flush();
+}
+
+/// Check first line of connection. Returns response for authenticated connection,
+/// or error response for connection which must be closed. Lines which are not JSON-RPC
+/// requests, for example HTTP requests from browser, get no response
+fn authenticate_request(line: &str, token: &str) -> Result<String, Option<String>> {
+    #[derive(Deserialize)]
+    struct Params {
+        token: String,
+    }
+    let value = serde_json::from_str::<Value>(line).map_err(|_| None)?;
+    if value.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err(None);
+    }
+    let request = RemoteRequest::deserialize(&value).map_err(|_| None)?;
+    let id = request.id.unwrap_or_default();
+    if request.method != "session.authenticate" {
+        return Err(Some(error_response(
+            id,
+            RemoteError::new(
+                RemoteError::UNAUTHORIZED,
+                "First request must be session.authenticate",
+            ),
+        )));
+    }
+    match parse_params::<Params>(&request.params) {
+        Ok(params) if tokens_match(&params.token, token) => {
+            Ok(json!({"jsonrpc": "2.0", "id": id, "result": null}).to_string())
+        }
+        _ => Err(Some(error_response(
+            id,
+            RemoteError::new(RemoteError::UNAUTHORIZED, "Invalid session token"),
+        ))),
+    }
+}
+
+/// JSON-RPC error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RemoteError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// Request is valid, but editor can not execute it
+    pub const EDITOR_ERROR: i64 = -32000;
+    /// Connection was not authenticated with session token
+    pub const UNAUTHORIZED: i64 = -32001;
+
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_params(message: impl ToString) -> Self {
+        Self::new(Self::INVALID_PARAMS, message.to_string())
+    }
+
+    fn editor(message: impl Into<String>) -> Self {
+        Self::new(Self::EDITOR_ERROR, message)
+    }
+}
+
+#[derive(Deserialize)]
+struct RemoteRequest {
+    /// Request without id is notification and gets no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Handle one JSON-RPC request line. Returns response line, or `None` for notifications
+pub fn handle_remote_request(world: &mut World, line: &str) -> Option<String> {
+    let value = match serde_json::from_str::<Value>(line) {
+        Ok(value) => value,
+        Err(err) => {
+            return Some(error_response(
+                Value::Null,
+                RemoteError::new(RemoteError::PARSE_ERROR, err.to_string()),
+            ))
+        }
+    };
+    let request = match RemoteRequest::deserialize(&value) {
+        Ok(request) => request,
+        Err(err) => {
+            let id = value.get("id").cloned().unwrap_or_default();
+            return Some(error_response(
+                id,
+                RemoteError::new(RemoteError::INVALID_REQUEST, err.to_string()),
+            ));
+        }
+    };
+
+    let result = run_remote_method(world, &request.method, &request.params);
+    if let Err(err) = &result {
+        warn!(
+            "Remote control request {} failed: {}",
+            request.method, err.message
+        );
+    }
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}).to_string(),
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn error_response(id: Value, error: RemoteError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": error.code, "message": error.message},
+    })
+    .to_string()
+}
+
+fn run_remote_method(
+    world: &mut World,
+    method: &str,
+    params: &Value,
+) -> Result<Value, RemoteError> {
+    match method {
+        "entity.list" => Ok(list_entities(world)),
+        "component.get" => get_component(world, params),
+        "component.set" => set_component(world, params),
+        "bundle.list" => Ok(list_bundles(world)),
+        "bundle.spawn" => spawn_bundle(world, params),
+        "selection.get" => Ok(get_selection(world)),
+        "selection.set" => set_selection(world, params),
+        "scene.save" => save_scene(world, params),
+        "scene.load" => {
+            #[derive(Deserialize)]
+            struct Params {
+                /// Asset path of scene file, for example `scenes/level.scn.ron`
+                path: String,
+            }
+            let params = parse_params::<Params>(params)?;
+            relative_path(&params.path)?;
+            world.send_event(EditorEvent::Load(EditorPrefabPath::File(params.path)));
+            Ok(Value::Null)
+        }
+        "undo" => {
+            world.send_event(UndoRedo::Undo);
+            Ok(Value::Null)
+        }
+        "redo" => {
+            world.send_event(UndoRedo::Redo);
+            Ok(Value::Null)
+        }
+        "command.run" => {
+            #[derive(Deserialize)]
+            struct Params {
+                id: String,
+            }
+            let params = parse_params::<Params>(params)?;
+            if world
+                .get_resource::<EditorCommands>()
+                .and_then(|commands| commands.get(&params.id))
+                .is_none()
+            {
+                return Err(RemoteError::editor(format!(
+                    "Editor command {} is not registered",
+                    params.id
+                )));
+            }
+            world.send_event(RunEditorCommand(params.id));
+            Ok(Value::Null)
+        }
+        _ => Err(RemoteError::new(
+            RemoteError::METHOD_NOT_FOUND,
+            format!("Method {} not found", method),
+        )),
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: &Value) -> Result<T, RemoteError> {
+    T::deserialize(params).map_err(RemoteError::invalid_params)
+}
+
+/// Entity of edited scene by [`Entity::to_bits`]
+fn scene_entity(world: &World, bits: u64) -> Result<Entity, RemoteError> {
+    Entity::try_from_bits(bits)
+        .ok()
+        .filter(|entity| {
+            world
+                .get_entity(*entity)
+                .is_some_and(|entity| entity.contains::<PrefabMarker>())
+        })
+        .ok_or_else(|| RemoteError::editor(format!("Entity {} is not in edited scene", bits)))
+}
+
+fn list_entities(world: &mut World) -> Value {
+    let editor_registry = world.resource::<EditorRegistry>().registry.clone();
+    let editor_registry = editor_registry.read();
+    let mut query =
+        world.query_filtered::<(Entity, Option<&Name>, Option<&Parent>), With<PrefabMarker>>();
+    let entities = query
+        .iter(world)
+        .map(|(entity, name, parent)| {
+            let components = world
+                .entity(entity)
+                .archetype()
+                .components()
+                .filter_map(|id| world.components().get_info(id)?.type_id())
+                .filter_map(|type_id| editor_registry.get(type_id))
+                .map(|registration| registration.type_info().type_path())
+                .collect::<Vec<_>>();
+            json!({
+                "entity": entity.to_bits(),
+                "name": name.map(Name::as_str),
+                "parent": parent.map(|parent| parent.get().to_bits()),
+                "components": components,
+            })
+        })
+        .collect();
+    Value::Array(entities)
+}
+
+#[derive(Deserialize)]
+struct ComponentParams {
+    entity: u64,
+    /// Full type path, for example `bevy_transform::components::transform::Transform`
+    type_path: String,
+    #[serde(default)]
+    value: Option<Value>,
+}
+
+/// Reflected component registered in editor. Changes of such components are recorded by auto undo
+fn editor_component(
+    world: &World,
+    type_path: &str,
+) -> Result<(std::any::TypeId, ReflectComponent), RemoteError> {
+    let editor_registry = world.resource::<EditorRegistry>().registry.read();
+    let registration = editor_registry
+        .get_with_type_path(type_path)
+        .ok_or_else(|| RemoteError::editor(format!("{} is not registered in editor", type_path)))?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| RemoteError::editor(format!("{} is not a component", type_path)))?;
+    Ok((registration.type_id(), reflect_component.clone()))
+}
+
+fn get_component(world: &mut World, params: &Value) -> Result<Value, RemoteError> {
+    let params = parse_params::<ComponentParams>(params)?;
+    let entity = scene_entity(world, params.entity)?;
+    let (_, reflect_component) = editor_component(world, &params.type_path)?;
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let component = reflect_component
+        .reflect(world.entity(entity))
+        .ok_or_else(|| RemoteError::editor(format!("Entity has no {}", params.type_path)))?;
+    serde_json::to_value(TypedReflectSerializer::new(component, &type_registry))
+        .map_err(|err| RemoteError::editor(err.to_string()))
+}
+
+/// Insert or change component. Change is recorded by auto undo of editor registry
+fn set_component(world: &mut World, params: &Value) -> Result<Value, RemoteError> {
+    let params = parse_params::<ComponentParams>(params)?;
+    let entity = scene_entity(world, params.entity)?;
+    let (type_id, reflect_component) = editor_component(world, &params.type_path)?;
+    let value = params
+        .value
+        .ok_or_else(|| RemoteError::invalid_params("missing field `value`"))?;
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let registration = type_registry
+        .get(type_id)
+        .ok_or_else(|| RemoteError::editor(format!("{} is not registered", params.type_path)))?;
+
+    let component = TypedReflectDeserializer::new(registration, &type_registry)
+        .deserialize(value)
+        .map_err(RemoteError::invalid_params)?;
+    reflect_component.apply_or_insert(
+        &mut world.entity_mut(entity),
+        component.as_ref(),
+        &type_registry,
+    );
+    Ok(Value::Null)
+}
+
+fn list_bundles(world: &World) -> Value {
+    let Some(bundles) = world.get_resource::<BundleReg>() else {
+        return Value::Array(vec![]);
+    };
+    bundles
+        .bundles
+        .iter()
+        .flat_map(|(category, bundles)| {
+            bundles
+                .keys()
+                .map(move |name| json!({"category": category, "name": name}))
+        })
+        .collect()
+}
+
+/// Spawn bundle from [`BundleReg`] as it is done from spawn menu
+fn spawn_bundle(world: &mut World, params: &Value) -> Result<Value, RemoteError> {
+    #[derive(Deserialize)]
+    struct Params {
+        category: String,
+        name: String,
+    }
+    let params = parse_params::<Params>(params)?;
+    let entity = spawn_editor_bundle(world, &params.category, &params.name).ok_or_else(|| {
+        RemoteError::editor(format!(
+            "Bundle {}/{} is not registered",
+            params.category, params.name
+        ))
+    })?;
+    Ok(json!({"entity": entity.to_bits()}))
+}
+
+fn get_selection(world: &mut World) -> Value {
+    let mut query = world.query_filtered::<Entity, With<Selected>>();
+    query
+        .iter(world)
+        .map(|entity| Value::from(entity.to_bits()))
+        .collect()
+}
+
+fn set_selection(world: &mut World, params: &Value) -> Result<Value, RemoteError> {
+    #[derive(Deserialize)]
+    struct Params {
+        entities: Vec<u64>,
+    }
+    let params = parse_params::<Params>(params)?;
+    let entities = params
+        .entities
+        .into_iter()
+        .map(|bits| scene_entity(world, bits))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    apply_selection_action(world, &SelectionAction::Select(entities));
+    Ok(Value::Null)
+}
+
+fn save_scene(world: &mut World, params: &Value) -> Result<Value, RemoteError> {
+    #[derive(Deserialize)]
+    struct Params {
+        /// File path, for example `assets/scenes/level.scn.ron`. Current scene file is used if not set
+        #[serde(default)]
+        path: Option<String>,
+    }
+    let params = parse_params::<Params>(params)?;
+    let path = match params.path {
+        Some(path) => {
+            let mut components = relative_path(&path)?
+                .components()
+                .filter(|component| *component != Component::CurDir);
+            if components.next() != Some(Component::Normal(ASSETS_DIR.as_ref()))
+                || components.next().is_none()
+            {
+                return Err(RemoteError::invalid_params(format!(
+                    "Path {} is not in {} folder",
+                    path, ASSETS_DIR
+                )));
+            }
+            EditorPrefabPath::File(path)
+        }
+        None => match world.resource::<SaveConfig>().path.clone() {
+            Some(path @ EditorPrefabPath::File(_)) => path,
+            _ => return Err(RemoteError::editor("Scene has no file, set path")),
+        },
+    };
+    world.send_event(EditorEvent::Save(path));
+    Ok(Value::Null)
+}
+
+/// Path without `..`, root or prefix, so it stays inside working directory
+fn relative_path(path: &str) -> Result<&Path, RemoteError> {
+    let relative = Path::new(path);
+    if relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(relative)
+    } else {
+        Err(RemoteError::invalid_params(format!(
+            "Path {} must be relative and must not contain ..",
+            path
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpStream,
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{headless::HeadlessEditor, ui_registration::EditorUiExt};
+
+    fn request(editor: &mut HeadlessEditor, method: &str, params: Value) -> Value {
+        let line = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response = handle_remote_request(editor.world_mut(), &line.to_string()).unwrap();
+        editor.update(10);
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn spawn_set_component_and_undo() {
+        let mut editor = HeadlessEditor::new();
+        editor.app.editor_bundle(
+            "Test",
+            "Marker",
+            (Name::new("Marker"), Transform::default()),
+        );
+
+        let response = request(
+            &mut editor,
+            "bundle.spawn",
+            json!({"category": "Test", "name": "Marker"}),
+        );
+        let bits = response["result"]["entity"].as_u64().unwrap();
+        let entity = Entity::from_bits(bits);
+
+        let entities = request(&mut editor, "entity.list", Value::Null);
+        assert_eq!(entities["result"][0]["name"], "Marker");
+
+        let type_path = Transform::type_path();
+        let mut transform = request(
+            &mut editor,
+            "component.get",
+            json!({"entity": bits, "type_path": type_path}),
+        )["result"]
+            .clone();
+        transform["translation"] = json!([1.0, 2.0, 3.0]);
+        let response = request(
+            &mut editor,
+            "component.set",
+            json!({"entity": bits, "type_path": type_path, "value": transform}),
+        );
+        assert_eq!(response["result"], Value::Null);
+        assert_eq!(
+            editor.world().get::<Transform>(entity).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+
+        request(&mut editor, "undo", Value::Null);
+        assert_eq!(
+            editor.world().get::<Transform>(entity).unwrap().translation,
+            Vec3::ZERO
+        );
+
+        let response = request(&mut editor, "selection.set", json!({"entities": [bits]}));
+        assert_eq!(response["result"], Value::Null);
+        assert_eq!(editor.selected(), vec![entity]);
+    }
+
+    #[test]
+    fn errors_and_notifications() {
+        let mut editor = HeadlessEditor::new();
+        let response = request(&mut editor, "unknown", Value::Null);
+        assert_eq!(response["error"]["code"], RemoteError::METHOD_NOT_FOUND);
+
+        let response = request(
+            &mut editor,
+            "component.get",
+            json!({"entity": 12345, "type_path": "Missing"}),
+        );
+        assert_eq!(response["error"]["code"], RemoteError::EDITOR_ERROR);
+
+        let response = handle_remote_request(editor.world_mut(), "{not json").unwrap();
+        assert!(response.contains(&RemoteError::PARSE_ERROR.to_string()));
+
+        let notification = json!({"jsonrpc": "2.0", "method": "redo"}).to_string();
+        assert_eq!(
+            handle_remote_request(editor.world_mut(), &notification),
+            None
+        );
+    }
+
+    fn connect(editor: &mut HeadlessEditor) -> BufReader<TcpStream> {
+        editor.app.add_plugins(RemoteControlPlugin);
+        editor.world_mut().insert_resource(RemoteControlSettings {
+            enabled: true,
+            port: 0,
+        });
+        editor.update(1);
+        let addr = editor
+            .world()
+            .resource::<RemoteControlServer>()
+            .server
+            .local_addr
+            .unwrap();
+
+        let stream = TcpStream::connect(addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+        BufReader::new(stream)
+    }
+
+    fn send(reader: &mut BufReader<TcpStream>, line: &str) {
+        reader.get_mut().write_all(line.as_bytes()).unwrap();
+        reader.get_mut().write_all(b"\n").unwrap();
+    }
+
+    /// Next response line, `None` if editor closed connection
+    fn receive(editor: &mut HeadlessEditor, reader: &mut BufReader<TcpStream>) -> Option<Value> {
+        let mut line = String::new();
+        for _ in 0..100 {
+            editor.update(1);
+            match reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) => return Some(serde_json::from_str(&line).unwrap()),
+                Err(err) if err.kind() == std::io::ErrorKind::ConnectionReset => return None,
+                Err(_) => {}
+            }
+        }
+        panic!("No response from editor");
+    }
+
+    fn authenticate(token: &str) -> String {
+        json!({"jsonrpc": "2.0", "id": 1, "method": "session.authenticate", "params": {"token": token}})
+            .to_string()
+    }
+
+    #[test]
+    fn requests_over_socket() {
+        let mut editor = HeadlessEditor::new();
+        let mut reader = connect(&mut editor);
+        let token = editor
+            .world()
+            .resource::<RemoteControlServer>()
+            .token
+            .clone();
+
+        send(&mut reader, &authenticate(&token));
+        send(
+            &mut reader,
+            r#"{"jsonrpc": "2.0", "id": 7, "method": "selection.get"}"#,
+        );
+        let response = receive(&mut editor, &mut reader).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], Value::Null);
+        let response = receive(&mut editor, &mut reader).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"], json!([]));
+    }
+
+    #[test]
+    fn unauthenticated_connections_are_closed() {
+        let mut editor = HeadlessEditor::new();
+        let mut reader = connect(&mut editor);
+        send(&mut reader, "GET / HTTP/1.1");
+        assert_eq!(receive(&mut editor, &mut reader), None);
+
+        let addr = reader.get_ref().peer_addr().unwrap();
+        for first_line in [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "selection.get"}"#.to_string(),
+            authenticate("wrong"),
+        ] {
+            let stream = TcpStream::connect(addr).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_millis(10)))
+                .unwrap();
+            let mut reader = BufReader::new(stream);
+            send(&mut reader, &first_line);
+            let response = receive(&mut editor, &mut reader).unwrap();
+            assert_eq!(response["error"]["code"], RemoteError::UNAUTHORIZED);
+            assert_eq!(receive(&mut editor, &mut reader), None);
+        }
+        assert_eq!(
+            editor
+                .world()
+                .resource::<RemoteControlServer>()
+                .server
+                .connections(),
+            0
+        );
+    }
+
+    #[test]
+    fn scene_paths_stay_in_assets_folder() {
+        let mut editor = HeadlessEditor::new();
+        for path in [
+            "../scenes/level.scn.ron",
+            "assets/../../level.scn.ron",
+            "/tmp/level.scn.ron",
+            "scenes/level.scn.ron",
+            "assets",
+        ] {
+            let response = request(&mut editor, "scene.save", json!({ "path": path }));
+            assert_eq!(response["error"]["code"], RemoteError::INVALID_PARAMS);
+        }
+        let response = request(
+            &mut editor,
+            "scene.load",
+            json!({"path": "../level.scn.ron"}),
+        );
+        assert_eq!(response["error"]["code"], RemoteError::INVALID_PARAMS);
+
+        let response = request(
+            &mut editor,
+            "scene.save",
+            json!({"path": "./assets/scenes/level.scn.ron"}),
+        );
+        assert_eq!(response["result"], Value::Null);
+    }
+}
@@ -0,0 +1,312 @@
+use std::path::Path;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_panorbit_camera::PanOrbitCamera;
+use space_editor_core::prelude::*;
+use space_shared::*;
+use space_undo::AutoUndoResourceStorage;
+
+#[cfg(feature = "persistence_editor")]
+use space_persistence::*;
+
+use crate::{
+    hierarchy::HierarchyTabState,
+    settings::{GameMode, GameModeSettings},
+};
+
+/// Plugin which remembers camera, selection and hierarchy state of every opened scene
+/// and restores it when the scene is loaded again
+pub struct SceneViewStatePlugin;
+
+impl Plugin for SceneViewStatePlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneViewStates>()
+            .init_resource::<OpenedScene>()
+            .register_type::<SceneViewState>()
+            .register_type::<CameraViewState>()
+            .register_type::<Option<CameraViewState>>()
+            .register_type::<HashMap<String, SceneViewState>>();
+
+        app.add_systems(
+            Update,
+            (
+                store_view_state_on_scene_change,
+                restore_view_state.after(EditorLoadSet),
+            ),
+        );
+
+        #[cfg(feature = "persistence_editor")]
+        {
+            // View state is personal, so it is kept out of project file
+            app.persistence_resource::<SceneViewStates>()
+                .persistence_layer::<SceneViewStates>(PersistenceLayer::User);
+            app.add_systems(
+                Update,
+                store_view_state_before_save.in_set(PersistenceSet::EventReader),
+            );
+        }
+    }
+}
+
+/// View state of scenes by [`scene_view_key`]
+#[derive(Resource, Reflect, Default, Clone)]
+#[reflect(Resource, Default)]
+pub struct SceneViewStates {
+    pub scenes: HashMap<String, SceneViewState>,
+}
+
+#[derive(Reflect, Default, Clone, Debug, PartialEq)]
+#[reflect(Default)]
+pub struct SceneViewState {
+    pub camera: Option<CameraViewState>,
+    pub game_mode: GameMode,
+    /// Persistent ids of selected entities
+    pub selection: Vec<u64>,
+    /// Persistent ids of entities collapsed in hierarchy
+    pub collapsed: Vec<u64>,
+}
+
+/// Orbit of editor camera
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq)]
+#[reflect(Default)]
+pub struct CameraViewState {
+    pub focus: Vec3,
+    pub radius: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+#[derive(Resource, Default)]
+struct OpenedScene {
+    current: Option<String>,
+    /// Scene which view state will be restored after load
+    pending: Option<String>,
+}
+
+/// Key of scene in [`SceneViewStates`]. View states of all projects are kept in one user file,
+/// so scene path relative to assets folder is prefixed with canonicalized project root
+pub fn scene_view_key(path: &str) -> String {
+    let root = std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .unwrap_or_default();
+    project_scene_key(&root, path)
+}
+
+fn project_scene_key(root: &Path, path: &str) -> String {
+    format!(
+        "{}/{}",
+        root.to_string_lossy().replace('\\', "/"),
+        scene_asset_path(path)
+    )
+}
+
+#[derive(SystemParam)]
+struct SceneView<'w, 's> {
+    cameras: Query<'w, 's, &'static mut PanOrbitCamera, With<EditorCameraMarker>>,
+    game_mode: ResMut<'w, GameModeSettings>,
+    game_mode_undo: Option<ResMut<'w, AutoUndoResourceStorage<GameModeSettings>>>,
+    selected: Query<'w, 's, (Entity, Option<&'static PersistentId>), With<Selected>>,
+    ids: Query<'w, 's, (Entity, &'static PersistentId)>,
+    hierarchy: ResMut<'w, HierarchyTabState>,
+}
+
+impl SceneView<'_, '_> {
+    fn capture(&self) -> SceneViewState {
+        let id_of = |entity: &Entity| self.ids.get(*entity).ok().map(|(_, id)| id.0);
+        SceneViewState {
+            camera: self.cameras.iter().next().map(|camera| CameraViewState {
+                focus: camera.target_focus,
+                radius: camera.target_radius,
+                yaw: camera.target_yaw,
+                pitch: camera.target_pitch,
+            }),
+            game_mode: self.game_mode.mode.clone(),
+            selection: self
+                .selected
+                .iter()
+                .filter_map(|(_, id)| id.map(|id| id.0))
+                .collect(),
+            collapsed: self.hierarchy.collapsed.iter().filter_map(id_of).collect(),
+        }
+    }
+
+    fn apply(&mut self, commands: &mut Commands, state: &SceneViewState) {
+        let entities = self
+            .ids
+            .iter()
+            .map(|(entity, id)| (id.0, entity))
+            .collect::<HashMap<_, _>>();
+
+        if let Some(camera_state) = state.camera {
+            for mut camera in self.cameras.iter_mut() {
+                camera.focus = camera_state.focus;
+                camera.target_focus = camera_state.focus;
+                camera.radius = Some(camera_state.radius);
+                camera.target_radius = camera_state.radius;
+                camera.yaw = Some(camera_state.yaw);
+                camera.target_yaw = camera_state.yaw;
+                camera.pitch = Some(camera_state.pitch);
+                camera.target_pitch = camera_state.pitch;
+                camera.force_update = true;
+            }
+        }
+
+        if self.game_mode.mode != state.game_mode {
+            self.game_mode.mode = state.game_mode.clone();
+            // Restored view state is not a user change
+            if let Some(storage) = self.game_mode_undo.as_mut() {
+                storage.reset();
+            }
+        }
+
+        for (entity, _) in self.selected.iter() {
+            commands.entity(entity).remove::<Selected>();
+        }
+        for entity in state.selection.iter().filter_map(|id| entities.get(id)) {
+            commands.entity(*entity).insert(Selected);
+        }
+
+        self.hierarchy.collapsed = state
+            .collapsed
+            .iter()
+            .filter_map(|id| entities.get(id).copied())
+            .collect::<HashSet<_>>();
+    }
+}
+
+fn store_view_state_on_scene_change(
+    mut events: EventReader<EditorEvent>,
+    mut opened: ResMut<OpenedScene>,
+    mut states: ResMut<SceneViewStates>,
+    view: SceneView,
+) {
+    for event in events.read() {
+        match event {
+            EditorEvent::Load(EditorPrefabPath::File(path)) => {
+                // Previous scene is still in world until the new one is loaded
+                if let Some(current) = opened.current.take() {
+                    states.scenes.insert(current, view.capture());
+                }
+                let key = scene_view_key(path);
+                opened.current = Some(key.clone());
+                opened.pending = Some(key);
+            }
+            EditorEvent::Save(EditorPrefabPath::File(path)) => {
+                let key = scene_view_key(path);
+                states.scenes.insert(key.clone(), view.capture());
+                opened.current = Some(key);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn restore_view_state(
+    mut commands: Commands,
+    mut loaded: EventReader<SceneLoaded>,
+    mut opened: ResMut<OpenedScene>,
+    states: Res<SceneViewStates>,
+    mut view: SceneView,
+) {
+    if loaded.read().count() == 0 {
+        return;
+    }
+    let Some(scene) = opened.pending.take() else {
+        return;
+    };
+    if let Some(state) = states.scenes.get(&scene) {
+        view.apply(&mut commands, state);
+    }
+}
+
+#[cfg(feature = "persistence_editor")]
+fn store_view_state_before_save(
+    mut events: EventReader<PersistenceEvent>,
+    opened: Res<OpenedScene>,
+    mut states: ResMut<SceneViewStates>,
+    view: SceneView,
+) {
+    if !events
+        .read()
+        .any(|event| matches!(event, PersistenceEvent::Save))
+    {
+        return;
+    }
+    if let Some(current) = opened.current.clone() {
+        states.scenes.insert(current, view.capture());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_and_save_paths_have_same_key() {
+        assert_eq!(
+            scene_view_key("scenes/level.scn.ron"),
+            scene_view_key("./assets/scenes/level.scn.ron")
+        );
+        let root = Path::new("/home/user/project");
+        assert_eq!(
+            project_scene_key(root, "scenes/level.scn.ron"),
+            "/home/user/project/scenes/level.scn.ron"
+        );
+        assert_eq!(
+            project_scene_key(root, "C:\\project\\assets\\scenes\\level.scn.ron"),
+            "/home/user/project/scenes/level.scn.ron"
+        );
+    }
+
+    #[test]
+    fn projects_have_separate_keys() {
+        assert_ne!(
+            project_scene_key(Path::new("/home/user/game"), "scenes/level.scn.ron"),
+            project_scene_key(Path::new("/home/user/other_game"), "scenes/level.scn.ron")
+        );
+    }
+
+    #[test]
+    fn restores_selection_and_collapsed_entities() {
+        let mut app = App::new();
+        app.init_resource::<GameModeSettings>()
+            .init_resource::<HierarchyTabState>()
+            .add_event::<SceneLoaded>()
+            .init_resource::<OpenedScene>()
+            .add_systems(Update, restore_view_state);
+
+        let selected = app.world_mut().spawn(PersistentId(1)).id();
+        let collapsed = app.world_mut().spawn(PersistentId(2)).id();
+        let other = app.world_mut().spawn((PersistentId(3), Selected)).id();
+
+        let mut states = SceneViewStates::default();
+        states.scenes.insert(
+            "scenes/level.scn.ron".to_string(),
+            SceneViewState {
+                camera: None,
+                game_mode: GameMode::Game2D,
+                selection: vec![1],
+                collapsed: vec![2],
+            },
+        );
+        app.insert_resource(states);
+        app.world_mut().resource_mut::<OpenedScene>().pending =
+            Some("scenes/level.scn.ron".to_string());
+        app.world_mut().send_event(SceneLoaded);
+        app.update();
+
+        let world = app.world();
+        assert!(world.get::<Selected>(selected).is_some());
+        assert!(world.get::<Selected>(other).is_none());
+        assert!(world.resource::<GameModeSettings>().is_2d());
+        assert!(world
+            .resource::<HierarchyTabState>()
+            .collapsed
+            .contains(&collapsed));
+    }
+}
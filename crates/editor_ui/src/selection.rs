@@ -15,9 +15,14 @@ impl Plugin for EditorPickingPlugin {
             raycast_backend.require_markers = true;
         }
 
+        app.add_event::<HoverEvent>();
         app.add_systems(
             PostUpdate,
-            (auto_add_picking, select_listener.after(UiSystemSet))
+            (
+                auto_add_picking,
+                select_listener.after(UiSystemSet),
+                hover_listener,
+            )
                 .run_if(in_state(EditorState::Editor)),
         );
         app.add_systems(PostUpdate, auto_add_picking_dummy);
@@ -32,6 +37,9 @@ pub fn auto_add_picking(
         commands.entity(e).insert((
             PickableBundle::default(),
             On::<Pointer<Down>>::send_event::<SelectEvent>(),
+            // Pointer events of child meshes bubble up to prefab entity
+            On::<Pointer<Over>>::send_event::<HoverEvent>(),
+            On::<Pointer<Out>>::send_event::<HoverEvent>(),
             RaycastPickable,
         ));
     }
@@ -97,9 +105,37 @@ pub fn select_listener(
     }
 }
 
-pub fn delete_selected(
+/// Only innermost prefab under pointer is hovered. Events bubble through all parent prefabs,
+/// so hovered prefab is found from event target
+pub fn hover_listener(
     mut commands: Commands,
-    query: Query<Entity, With<Selected>>,
+    prefabs: Query<(), With<PrefabMarker>>,
+    parents: Query<&Parent>,
+    mut events: EventReader<HoverEvent>,
+) {
+    let innermost_prefab = |e: Entity| {
+        std::iter::once(e)
+            .chain(parents.iter_ancestors(e))
+            .find(|e| prefabs.contains(*e))
+    };
+    let events = events.read().copied().collect::<Vec<_>>();
+
+    // Moving pointer between meshes of one prefab sends both events in same frame
+    for event in events.iter().filter(|event| !event.hovered) {
+        if let Some(prefab) = innermost_prefab(event.e) {
+            commands.entity(prefab).remove::<Hovered>();
+        }
+    }
+    for event in events.iter().filter(|event| event.hovered) {
+        if let Some(prefab) = innermost_prefab(event.e) {
+            commands.entity(prefab).insert(Hovered);
+        }
+    }
+}
+
+/// Delete is done by editor command, so it is undoable as delete from hierarchy
+pub fn delete_selected(
+    mut run_command: EventWriter<RunEditorCommand>,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
     let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
@@ -107,10 +143,7 @@ pub fn delete_selected(
     let delete = keyboard.any_just_pressed([KeyCode::Backspace, KeyCode::Delete]);
 
     if ctrl && shift && delete {
-        for entity in query.iter() {
-            info!("Delete Entity: {entity:?}");
-            commands.entity(entity).despawn_recursive();
-        }
+        run_command.send(RunEditorCommand("edit.delete_selected".to_string()));
     }
 }
 
@@ -123,6 +156,32 @@ impl From<ListenerInput<Pointer<Down>>> for SelectEvent {
     }
 }
 
+impl From<ListenerInput<Pointer<Over>>> for HoverEvent {
+    fn from(value: ListenerInput<Pointer<Over>>) -> Self {
+        Self {
+            e: value.target(),
+            hovered: true,
+        }
+    }
+}
+
+impl From<ListenerInput<Pointer<Out>>> for HoverEvent {
+    fn from(value: ListenerInput<Pointer<Out>>) -> Self {
+        Self {
+            e: value.target(),
+            hovered: false,
+        }
+    }
+}
+
+/// This event used for hover highlight of prefab under pointer
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HoverEvent {
+    /// Entity under pointer, which can be child mesh of prefab
+    pub e: Entity,
+    pub hovered: bool,
+}
+
 /// This event used for selecting entities
 #[derive(Event, Clone, EntityEvent)]
 pub struct SelectEvent {
@@ -130,3 +189,42 @@ pub struct SelectEvent {
     e: Entity,
     event: ListenerInput<Pointer<Down>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hovers_only_innermost_prefab() {
+        let mut app = App::new();
+        app.add_event::<HoverEvent>()
+            .add_systems(Update, hover_listener);
+        let parent = app.world_mut().spawn(PrefabMarker).id();
+        let child = app.world_mut().spawn(PrefabMarker).set_parent(parent).id();
+        let mesh = app.world_mut().spawn_empty().set_parent(child).id();
+
+        // Event of child mesh bubbles to both prefabs
+        for _ in 0..2 {
+            app.world_mut().send_event(HoverEvent {
+                e: mesh,
+                hovered: true,
+            });
+        }
+        app.update();
+        assert!(app.world().get::<Hovered>(child).is_some());
+        assert!(app.world().get::<Hovered>(parent).is_none());
+
+        // Pointer moves from child mesh to parent
+        app.world_mut().send_event(HoverEvent {
+            e: parent,
+            hovered: true,
+        });
+        app.world_mut().send_event(HoverEvent {
+            e: mesh,
+            hovered: false,
+        });
+        app.update();
+        assert!(app.world().get::<Hovered>(child).is_none());
+        assert!(app.world().get::<Hovered>(parent).is_some());
+    }
+}
@@ -1,12 +1,23 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::Path};
 
 use bevy::{
     prelude::*,
     utils::{HashMap, HashSet},
 };
 use bevy_egui::*;
-use space_editor_core::hotkeys::AllHotkeys;
-use space_shared::ext::bevy_inspector_egui::bevy_inspector;
+use space_editor_core::{
+    autosave::AutosaveSettings,
+    hotkeys::{AllHotkeys, HotkeyChord, HotkeyInput, WheelDirection},
+    journal::ChangeJournalSettings,
+    keymap::{apply_keymap_preset, load_keymap, save_keymap, KeymapPresets, DEFAULT_KEYMAP_PATH},
+    live_link::{LiveLinkServer, LiveLinkSettings},
+    selected::SelectionHighlightSettings,
+    settings_undo::AppSettingsUndo,
+};
+use space_shared::{
+    ext::bevy_inspector_egui::bevy_inspector,
+    toast::{ToastKind, ToastMessage},
+};
 use space_undo::ChangeChainSettings;
 
 #[cfg(feature = "persistence_editor")]
@@ -15,7 +26,9 @@ use space_persistence::*;
 use space_editor_tabs::prelude::*;
 
 use crate::{
+    colors::WARN_COLOR,
     editor_tab_name::EditorTabName,
+    remote_control::{RemoteControlServer, RemoteControlSettings},
     sizing::{IconSize, Sizing},
 };
 
@@ -34,12 +47,24 @@ impl Plugin for SettingsWindowPlugin {
             .register_type::<IconSize>()
             .register_type::<NewTabBehaviour>()
             .init_resource::<NewWindowSettings>();
+        app.settings_undo::<NewWindowSettings>()
+            .settings_undo::<Sizing>()
+            .settings_undo::<ChangeChainSettings>()
+            .settings_undo::<GameModeSettings>()
+            .settings_undo::<AutosaveSettings>()
+            .settings_undo::<SelectionHighlightSettings>()
+            .settings_undo::<RemoteControlSettings>()
+            .settings_undo::<LiveLinkSettings>();
         #[cfg(feature = "persistence_editor")]
         {
             app.persistence_resource::<NewWindowSettings>()
                 .persistence_resource::<Sizing>()
                 .persistence_resource::<ChangeChainSettings>()
                 .persistence_resource::<GameModeSettings>();
+            // Personal preferences are kept out of project file
+            app.persistence_layer::<NewWindowSettings>(PersistenceLayer::User)
+                .persistence_layer::<Sizing>(PersistenceLayer::User)
+                .persistence_layer::<ChangeChainSettings>(PersistenceLayer::User);
         }
     }
 }
@@ -111,8 +136,10 @@ impl GameModeSettings {
 
 #[derive(Resource, Default)]
 pub struct SettingsWindow {
+    /// Hotkey in `set: hotkey` format which binding is captured
     read_input_for_hotkey: Option<String>,
-    all_pressed_hotkeys: HashSet<KeyCode>,
+    all_pressed_hotkeys: HashSet<HotkeyInput>,
+    keymap_path: String,
     sub_blocks: HashMap<
         String,
         Box<dyn FnMut(&mut egui::Ui, &mut Commands, &mut World) + Send + Sync + 'static>,
@@ -163,6 +190,79 @@ impl EditorTab for SettingsWindow {
                     .prefix("Max change chain size: "),
             );
         });
+        if let Some(mut journal_settings) = world.get_resource_mut::<ChangeJournalSettings>() {
+            ui.checkbox(&mut journal_settings.enabled, "Crash recovery journal");
+        }
+
+        ui.add_space(12.);
+        ui.heading("Autosave");
+        if let Some(mut autosave_settings) = world.get_resource_mut::<AutosaveSettings>() {
+            ui.checkbox(&mut autosave_settings.enabled, "Autosave scene");
+            ui.add_enabled_ui(autosave_settings.enabled, |ui| {
+                ui.add(
+                    egui::DragValue::new(&mut autosave_settings.interval)
+                        .range(10.0..=3600.0)
+                        .suffix(" s")
+                        .prefix("Interval: "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut autosave_settings.copies)
+                        .range(1..=20)
+                        .prefix("Copies: "),
+                );
+            });
+        }
+
+        ui.add_space(12.);
+        ui.heading("Remote Control");
+        if let Some(mut remote_settings) = world.get_resource_mut::<RemoteControlSettings>() {
+            ui.checkbox(&mut remote_settings.enabled, "Enable JSON-RPC server");
+            ui.add_enabled(
+                remote_settings.enabled,
+                egui::DragValue::new(&mut remote_settings.port).prefix("Port: "),
+            );
+        }
+        if let Some(RemoteControlServer { server, token }) =
+            world.get_resource::<RemoteControlServer>()
+        {
+            if let Some(addr) = server.local_addr {
+                ui.label(format!("Listening on {}", addr));
+                if ui.button("📋 Copy session token").clicked() {
+                    ui.output_mut(|output| output.copied_text.clone_from(token));
+                }
+            }
+            if let Some(error) = &server.error {
+                ui.colored_label(WARN_COLOR, format!("⚠ {}", error));
+            }
+        }
+
+        ui.add_space(12.);
+        ui.heading("Live Link");
+        if let Some(mut link_settings) = world.get_resource_mut::<LiveLinkSettings>() {
+            ui.checkbox(
+                &mut link_settings.enabled,
+                "Send scene edits to running game",
+            );
+            ui.add_enabled(
+                link_settings.enabled,
+                egui::DragValue::new(&mut link_settings.port).prefix("Port: "),
+            );
+        }
+        if let Some(LiveLinkServer { server, token }) = world.get_resource::<LiveLinkServer>() {
+            if let Some(addr) = server.local_addr {
+                ui.label(format!(
+                    "Listening on {}, {} games connected",
+                    addr,
+                    server.authenticated_connections()
+                ));
+                if ui.button("📋 Copy session token").clicked() {
+                    ui.output_mut(|output| output.copied_text.clone_from(token));
+                }
+            }
+            if let Some(error) = &server.error {
+                ui.colored_label(WARN_COLOR, format!("⚠ {}", error));
+            }
+        }
 
         ui.add_space(12.);
         ui.heading("New Tab Behaviour");
@@ -177,83 +277,98 @@ impl EditorTab for SettingsWindow {
         bevy_inspector::ui_for_resource::<Sizing>(world, ui);
 
         ui.add_space(12.);
-        ui.heading("Hotkeys in Game view tab");
+        ui.heading("Selection Highlight");
+        bevy_inspector::ui_for_resource::<SelectionHighlightSettings>(world, ui);
+
+        ui.add_space(12.);
+        ui.heading("Hotkeys");
         if world.contains_resource::<AllHotkeys>() {
+            self.keymap_ui(ui, world);
+
+            let conflicts = world
+                .resource_scope::<AllHotkeys, _>(|world, all_hotkeys| all_hotkeys.conflicts(world));
+            for conflict in &conflicts {
+                ui.colored_label(
+                    WARN_COLOR,
+                    format!(
+                        "⚠ {} is bound to {}",
+                        conflict.chord,
+                        conflict.hotkeys.join(", ")
+                    ),
+                );
+            }
+
             egui::Grid::new("hotkeys_grid")
-                .num_columns(2)
+                .num_columns(5)
                 .show(ui, |ui| {
                     world.resource_scope::<AllHotkeys, _>(|world, all_hotkeys| {
                         all_hotkeys.global_map(world, &mut |world, set| {
-                            ui.heading(set.get_name());
+                            let set_name = set.get_name().to_string();
+                            ui.heading(&set_name);
+                            let reset_set = ui.button("Reset set").clicked();
                             ui.end_row();
-                            let all_bindings = set.get_flat_bindings();
-                            for (hotkey_name, bindings) in all_bindings {
+
+                            let contexts = set.get_flat_contexts();
+                            let changed = contexts
+                                .keys()
+                                .filter(|name| !set.is_default(name))
+                                .cloned()
+                                .collect::<HashSet<_>>();
+                            let mut reset = vec![];
+                            for (hotkey_name, bindings) in set.get_flat_bindings() {
                                 ui.label(&hotkey_name);
+                                let hotkey = format!("{}: {}", set_name, hotkey_name);
+
+                                if self.read_input_for_hotkey.as_ref() == Some(&hotkey) {
+                                    self.capture_binding(ui, world, bindings);
+                                } else if ui
+                                    .button(HotkeyChord::new(bindings).to_string())
+                                    .on_hover_text("Click and press new keys")
+                                    .clicked()
+                                {
+                                    self.read_input_for_hotkey = Some(hotkey.clone());
+                                    self.all_pressed_hotkeys.clear();
+                                }
 
-                                if let Some(read_input_for_hotkey) = &self.read_input_for_hotkey {
-                                    if hotkey_name == *read_input_for_hotkey {
-                                        let mut key_text = String::new();
-
-                                        world.resource_scope::<ButtonInput<KeyCode>, _>(
-                                            |_world, input| {
-                                                let all_pressed = input
-                                                    .get_pressed()
-                                                    .copied()
-                                                    .collect::<Vec<_>>();
-                                                self.all_pressed_hotkeys.extend(all_pressed.iter());
-                                                let all_pressed = self
-                                                    .all_pressed_hotkeys
-                                                    .iter()
-                                                    .copied()
-                                                    .collect::<Vec<_>>();
-
-                                                if all_pressed.is_empty() {
-                                                    key_text = "Wait for input".to_string();
-                                                } else {
-                                                    key_text = format!("{:?}", all_pressed[0]);
-                                                    for key in all_pressed.iter().skip(1) {
-                                                        key_text =
-                                                            format!("{} + {:?}", key_text, key);
-                                                    }
-                                                }
-
-                                                if input.get_just_released().len() > 0 {
-                                                    bindings.clear();
-                                                    *bindings = all_pressed;
-                                                    self.read_input_for_hotkey = None;
-                                                    self.all_pressed_hotkeys.clear();
-                                                }
-
-                                                ui.add(egui::Button::new(
-                                                    egui::RichText::new(&key_text).strong(),
-                                                ));
-                                            },
-                                        );
-                                    } else {
-                                        let binding_text = if bindings.len() == 1 {
-                                            format!("{:?}", &bindings[0])
-                                        } else {
-                                            format!("{:?}", bindings)
-                                        };
-
-                                        if ui.button(binding_text).clicked() {
-                                            self.read_input_for_hotkey = Some(hotkey_name);
-                                        }
-                                    }
-                                } else {
-                                    let binding_text = if bindings.len() == 1 {
-                                        format!("{:?}", &bindings[0])
-                                    } else {
-                                        format!("{:?}", bindings)
-                                    };
-
-                                    if ui.button(binding_text).clicked() {
-                                        self.read_input_for_hotkey = Some(hotkey_name);
-                                    }
+                                let context =
+                                    contexts.get(&hotkey_name).copied().unwrap_or_default();
+                                ui.label(format!("{:?}", context));
+
+                                if ui
+                                    .add_enabled(
+                                        changed.contains(&hotkey_name),
+                                        egui::Button::new("↺"),
+                                    )
+                                    .on_hover_text("Reset to default")
+                                    .clicked()
+                                {
+                                    reset.push(hotkey_name);
                                 }
 
+                                if let Some(conflict) = conflicts
+                                    .iter()
+                                    .find(|conflict| conflict.hotkeys.contains(&hotkey))
+                                {
+                                    ui.colored_label(WARN_COLOR, "⚠").on_hover_text(format!(
+                                        "Conflicts with {}",
+                                        conflict
+                                            .hotkeys
+                                            .iter()
+                                            .filter(|other| **other != hotkey)
+                                            .cloned()
+                                            .collect::<Vec<_>>()
+                                            .join(", ")
+                                    ));
+                                }
                                 ui.end_row();
                             }
+
+                            if reset_set {
+                                set.reset_all();
+                            }
+                            for hotkey_name in reset {
+                                set.reset_binding(&hotkey_name);
+                            }
                         });
                     });
                 });
@@ -269,3 +384,146 @@ impl EditorTab for SettingsWindow {
         EditorTabName::Settings.into()
     }
 }
+
+impl SettingsWindow {
+    /// Presets, reset and import/export of all hotkey bindings
+    fn keymap_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+        let presets = world
+            .get_resource::<KeymapPresets>()
+            .map(|presets| {
+                presets
+                    .presets
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label("Preset:");
+            for preset in presets {
+                if ui.button(&preset).clicked() {
+                    apply_keymap_preset(world, &preset);
+                }
+            }
+            if ui.button("Reset all").clicked() {
+                world.resource_scope::<AllHotkeys, _>(|world, all_hotkeys| {
+                    all_hotkeys.reset_all(world);
+                });
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Keymap file:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.keymap_path).hint_text(DEFAULT_KEYMAP_PATH),
+            );
+            let path = if self.keymap_path.is_empty() {
+                DEFAULT_KEYMAP_PATH.to_string()
+            } else {
+                self.keymap_path.clone()
+            };
+            let result = if ui.button("Export").clicked() {
+                Some(
+                    save_keymap(world, Path::new(&path))
+                        .map(|_| format!("Keymap exported to {}", path)),
+                )
+            } else if ui.button("Import").clicked() {
+                Some(
+                    load_keymap(world, Path::new(&path))
+                        .map(|_| format!("Keymap imported from {}", path)),
+                )
+            } else {
+                None
+            };
+            match result {
+                Some(Ok(message)) => {
+                    world.send_event(ToastMessage::new(&message, ToastKind::Info));
+                }
+                Some(Err(err)) => {
+                    error!("Keymap {} failed: {}", path, err);
+                    world.send_event(ToastMessage::new(
+                        &format!("Keymap {} failed: {}", path, err),
+                        ToastKind::Error,
+                    ));
+                }
+                None => {}
+            }
+        });
+    }
+
+    /// Collect inputs pressed after click on binding. Binding is replaced when any input is released
+    /// or mouse wheel is scrolled
+    fn capture_binding(
+        &mut self,
+        ui: &mut egui::Ui,
+        world: &World,
+        binding: &mut Vec<HotkeyInput>,
+    ) {
+        let keyboard = world.resource::<ButtonInput<KeyCode>>();
+        if keyboard.just_pressed(KeyCode::Escape) {
+            self.read_input_for_hotkey = None;
+            self.all_pressed_hotkeys.clear();
+            return;
+        }
+        let mut released = keyboard.get_just_released().len() > 0;
+        self.all_pressed_hotkeys.extend(
+            keyboard
+                .get_pressed()
+                .map(|key| HotkeyInput::Keyboard(*key)),
+        );
+        if let Some(mouse) = world.get_resource::<ButtonInput<MouseButton>>() {
+            released |= mouse.get_just_released().len() > 0;
+            self.all_pressed_hotkeys.extend(
+                mouse
+                    .get_pressed()
+                    .map(|button| HotkeyInput::Mouse(*button)),
+            );
+        }
+        if let Some(gamepads) = world.get_resource::<ButtonInput<GamepadButton>>() {
+            released |= gamepads.get_just_released().len() > 0;
+            self.all_pressed_hotkeys.extend(
+                gamepads
+                    .get_pressed()
+                    .map(|button| HotkeyInput::Gamepad(button.button_type)),
+            );
+        }
+        let scroll = ui.input(|i| i.raw_scroll_delta);
+        for (delta, positive, negative) in [
+            (scroll.y, WheelDirection::Up, WheelDirection::Down),
+            (scroll.x, WheelDirection::Right, WheelDirection::Left),
+        ] {
+            if delta != 0.0 {
+                let direction = if delta > 0.0 { positive } else { negative };
+                self.all_pressed_hotkeys
+                    .insert(HotkeyInput::Wheel(direction));
+                released = true;
+            }
+        }
+        // Plain left and right clicks select entities and press UI buttons,
+        // so they are captured only together with another input
+        if released
+            && self.all_pressed_hotkeys.iter().all(|input| {
+                matches!(
+                    input,
+                    HotkeyInput::Mouse(MouseButton::Left | MouseButton::Right)
+                )
+            })
+        {
+            self.all_pressed_hotkeys.clear();
+        }
+        let captured = self.all_pressed_hotkeys.iter().copied().collect::<Vec<_>>();
+
+        let text = if captured.is_empty() {
+            "Press keys, buttons or scroll (Esc to cancel)".to_string()
+        } else {
+            HotkeyChord::new(&captured).to_string()
+        };
+        ui.add(egui::Button::new(egui::RichText::new(text).strong()));
+
+        if !captured.is_empty() && released {
+            *binding = captured;
+            self.read_input_for_hotkey = None;
+            self.all_pressed_hotkeys.clear();
+        }
+    }
+}
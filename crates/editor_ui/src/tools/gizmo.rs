@@ -25,17 +25,46 @@ impl Plugin for GizmoToolPlugin {
         }
         app.init_resource::<MultipleCenter>();
 
-        app.editor_hotkey(GizmoHotkey::Translate, vec![KeyCode::KeyG]);
-        app.editor_hotkey(GizmoHotkey::Rotate, vec![KeyCode::KeyR]);
-        app.editor_hotkey(GizmoHotkey::Scale, vec![KeyCode::KeyS]);
-        app.editor_hotkey(GizmoHotkey::Delete, vec![KeyCode::KeyX]);
-        app.editor_hotkey(GizmoHotkey::Multiple, vec![KeyCode::ShiftLeft]);
-        app.editor_hotkey(GizmoHotkey::Clone, vec![KeyCode::AltLeft]);
+        for (hotkey, binding) in [
+            (GizmoHotkey::Translate, KeyCode::KeyG),
+            (GizmoHotkey::Rotate, KeyCode::KeyR),
+            (GizmoHotkey::Scale, KeyCode::KeyS),
+            (GizmoHotkey::Delete, KeyCode::KeyX),
+            (GizmoHotkey::Multiple, KeyCode::ShiftLeft),
+            (GizmoHotkey::Clone, KeyCode::AltLeft),
+        ] {
+            app.editor_hotkey_in_context(hotkey, vec![binding], HotkeyContext::Viewport);
+        }
+        // Default bindings are Blender-like
+        app.editor_keymap_preset("Blender", Keymap::default());
+        app.editor_keymap_preset(
+            "Unity",
+            Keymap::default()
+                .with(GizmoHotkey::Translate, vec![KeyCode::KeyW])
+                .with(GizmoHotkey::Rotate, vec![KeyCode::KeyE])
+                .with(GizmoHotkey::Scale, vec![KeyCode::KeyR])
+                .with(GizmoHotkey::Delete, vec![KeyCode::Delete]),
+        );
+
+        app.init_resource::<GizmoModeCommand>();
+        app.editor_command("gizmo.translate", "Translate mode", "Gizmo", |world| {
+            world.resource_mut::<GizmoModeCommand>().0 = Some(GizmoMode::all_translate());
+        })
+        .editor_command("gizmo.rotate", "Rotate mode", "Gizmo", |world| {
+            world.resource_mut::<GizmoModeCommand>().0 = Some(GizmoMode::all_rotate());
+        })
+        .editor_command("gizmo.scale", "Scale mode", "Gizmo", |world| {
+            world.resource_mut::<GizmoModeCommand>().0 = Some(GizmoMode::all_scale());
+        });
 
         app.add_systems(Update, draw_lines_system.in_set(EditorSet::Editor));
     }
 }
 
+/// Gizmo mode set by editor command. Gizmo tool takes it when it is shown
+#[derive(Resource, Default)]
+pub struct GizmoModeCommand(pub Option<EnumSet<GizmoMode>>);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
 pub enum GizmoHotkey {
     Translate,
@@ -134,7 +163,7 @@ impl EditorTool for GizmoTool {
         "Gizmo"
     }
 
-    fn ui(&mut self, ui: &mut egui::Ui, commands: &mut Commands, world: &mut World) {
+    fn ui(&mut self, ui: &mut egui::Ui, _commands: &mut Commands, world: &mut World) {
         let sizing = world.resource::<Sizing>();
 
         ui.spacing();
@@ -159,6 +188,13 @@ impl EditorTool for GizmoTool {
             }
         });
 
+        if let Some(mode) = world
+            .get_resource_mut::<GizmoModeCommand>()
+            .and_then(|mut command| command.0.take())
+        {
+            self.gizmo_mode = mode;
+        }
+
         let Some(input) = world.get_resource::<ButtonInput<GizmoHotkey>>() else {
             warn!("Failed to retrieve gizmos hotkey button input");
             return;
@@ -192,10 +228,7 @@ impl EditorTool for GizmoTool {
         }
 
         if del {
-            let mut query = world.query_filtered::<Entity, With<Selected>>();
-            for e in query.iter(world) {
-                commands.entity(e).despawn_recursive();
-            }
+            world.send_event(RunEditorCommand("edit.delete_selected".to_string()));
             return;
         }
 
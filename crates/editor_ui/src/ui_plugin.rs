@@ -38,16 +38,24 @@ impl FlatPluginList for EditorUiPlugin {
     fn add_plugins_to_group(&self, group: PluginGroupBuilder) -> PluginGroupBuilder {
         let mut res = group
             .add(SelectedPlugin)
+            .add(SelectionToolsPlugin)
             .add(MeshlessVisualizerPlugin)
             .add(EditorUiCore::default())
+            .add(layouts::EditorLayoutsPlugin)
             .add(GameViewPlugin)
             .add(menu_toolbars::BottomMenuPlugin)
             .add(MouseCheck)
+            .add(hotkey_context::HotkeyContextPlugin)
             .add(CameraViewTabPlugin)
             .add(SpaceHierarchyPlugin::default())
+            .add(scene_view_state::SceneViewStatePlugin)
             .add(SpaceInspectorPlugin)
+            .add(animation_preview::AnimationPreviewPlugin)
             .add(GizmoToolPlugin)
             .add(ChangeChainViewPlugin)
+            .add(notifications::NotificationsTabPlugin)
+            .add(command_palette::CommandPalettePlugin)
+            .add(remote_control::RemoteControlPlugin)
             .add(settings::SettingsWindowPlugin);
 
         if self.use_standard_layout {
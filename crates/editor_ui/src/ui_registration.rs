@@ -1,12 +1,14 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
 use bevy::{
-    core_pipeline::tonemapping::DebandDither, ecs::system::EntityCommands,
+    core_pipeline::tonemapping::DebandDither,
+    ecs::{system::EntityCommands, world::CommandQueue},
     render::camera::CameraRenderGraph,
 };
 
 use space_prefab::{component::*, ext::*};
 use space_shared::{LightAreaToggle, PrefabMarker};
+use space_undo::{AddedEntity, LabeledChange, NewChange};
 
 /// Resource with bundles to spawn
 #[derive(Resource, Default)]
@@ -58,6 +60,30 @@ impl EditorBundleUntyped {
     }
 }
 
+/// Spawn bundle registered in [`BundleReg`] and record spawn for undo.
+/// Returns `None` if bundle is not registered
+pub fn spawn_editor_bundle(world: &mut World, category: &str, name: &str) -> Option<Entity> {
+    let entity = world
+        .contains_resource::<BundleReg>()
+        .then(|| {
+            world.resource_scope::<BundleReg, _>(|world, reg| {
+                let bundle = reg.bundles.get(category)?.get(name)?;
+                let mut queue = CommandQueue::default();
+                let entity = bundle.spawn(&mut Commands::new(&mut queue, world));
+                queue.apply(world);
+                Some(entity)
+            })
+        })
+        .flatten()?;
+    world.send_event(NewChange {
+        change: Arc::new(LabeledChange::new(
+            format!("Spawn {}", name),
+            AddedEntity { entity },
+        )),
+    });
+    Some(entity)
+}
+
 /// Trait to add `editor_bundle(..)` to App
 pub trait EditorUiExt {
     /// Register new bundle in editor ui
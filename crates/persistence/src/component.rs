@@ -0,0 +1,170 @@
+use bevy::{
+    prelude::*,
+    reflect::{serde::ReflectSerializer, GetTypeRegistration},
+};
+use space_shared::PersistentId;
+
+use crate::{
+    PersistenceError, PersistenceLoaded, PersistenceRegistry, PersistenceResourceBroadcastEvent,
+};
+
+/// Separates component type path and entity key in persistence entry key
+const ENTITY_KEY_SEPARATOR: char = '#';
+
+/// Marker of entity which components are persisted between sessions.
+/// Entity is identified by its [`PersistentId`] if it has one, otherwise by its [`Name`]
+#[derive(Component, Reflect, Default, Clone, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct Persistent;
+
+/// Key of entity in persistence data. Entity without id and name can't be persisted
+pub fn persistent_entity_key(id: Option<&PersistentId>, name: Option<&Name>) -> Option<String> {
+    match (id, name) {
+        (Some(id), _) => Some(format!("id:{}", id.0)),
+        (None, Some(name)) => Some(format!("name:{}", name)),
+        (None, None) => None,
+    }
+}
+
+impl PersistenceRegistry {
+    /// Remove saved components of entity with given [`persistent_entity_key`].
+    /// Entries of entities which are not spawned are kept on save otherwise
+    pub fn remove_persistent_entity(&mut self, key: &str) {
+        let is_entity_entry = |entry: &String| {
+            entry
+                .split_once(ENTITY_KEY_SEPARATOR)
+                .is_some_and(|(_, entity_key)| entity_key == key)
+        };
+        self.data.retain(|entry, _| !is_entity_entry(entry));
+        self.data_versions
+            .retain(|entry, _| !is_entity_entry(entry));
+    }
+}
+
+/// Prefix of all persistence entries of component type
+pub(crate) fn component_entry_prefix(type_path: &str) -> String {
+    format!("{}{}", type_path, ENTITY_KEY_SEPARATOR)
+}
+
+/// Type path of resource or component, which persistence entry belongs to
+pub(crate) fn entry_type_path(key: &str) -> &str {
+    key.split_once(ENTITY_KEY_SEPARATOR)
+        .map_or(key, |(type_path, _)| type_path)
+}
+
+pub(crate) fn persistence_component_system<
+    T: Component + Reflect + FromReflect + GetTypeRegistration,
+>(
+    mut commands: Commands,
+    mut events: EventReader<PersistenceResourceBroadcastEvent>,
+    mut persistence: ResMut<PersistenceRegistry>,
+    registry: Res<AppTypeRegistry>,
+    query: Query<(Entity, Option<&PersistentId>, Option<&Name>, Option<&T>), With<Persistent>>,
+    added: Query<(Entity, Option<&PersistentId>, Option<&Name>), Added<Persistent>>,
+    mut persistence_loaded: EventWriter<PersistenceLoaded<T>>,
+) {
+    let type_path = T::get_type_registration()
+        .type_info()
+        .type_path()
+        .to_string();
+    let prefix = component_entry_prefix(&type_path);
+
+    for event in events.read() {
+        match event {
+            PersistenceResourceBroadcastEvent::Pack => {
+                // Entries of entities which are not spawned now are kept
+                let version = persistence
+                    .versions
+                    .get(&type_path)
+                    .copied()
+                    .unwrap_or_default();
+                let type_registry = registry.read();
+                for (entity, id, name, component) in query.iter() {
+                    let Some(component) = component else {
+                        continue;
+                    };
+                    let Some(key) = persistent_entity_key(id, name) else {
+                        warn!("Persistent entity {:?} has no id and name", entity);
+                        continue;
+                    };
+                    let key = format!("{}{}", prefix, key);
+                    let serializer = ReflectSerializer::new(component, &type_registry);
+                    match ron::to_string(&serializer) {
+                        Ok(data) => {
+                            persistence.data.insert(key.clone(), data);
+                            persistence.data_versions.insert(key, version);
+                        }
+                        Err(err) => {
+                            error!("Persistence component {} could not be serialized", key);
+                            persistence.errors.push(PersistenceError::Resource {
+                                type_path: key,
+                                error: err.to_string(),
+                            });
+                        }
+                    }
+                }
+                persistence.save_counter += 1;
+            }
+            PersistenceResourceBroadcastEvent::Unpack => {
+                if !persistence.data.keys().any(|key| key.starts_with(&prefix)) {
+                    continue;
+                }
+                for (entity, id, name, _) in query.iter() {
+                    unpack_component::<T>(
+                        &mut commands,
+                        &mut persistence,
+                        &registry,
+                        &type_path,
+                        entity,
+                        persistent_entity_key(id, name),
+                    );
+                }
+                persistence_loaded.send(PersistenceLoaded::<T>::default());
+                persistence.load_counter += 1;
+            }
+        }
+    }
+
+    // Entities spawned after load get their saved state too
+    for (entity, id, name) in added.iter() {
+        unpack_component::<T>(
+            &mut commands,
+            &mut persistence,
+            &registry,
+            &type_path,
+            entity,
+            persistent_entity_key(id, name),
+        );
+    }
+}
+
+fn unpack_component<T: Component + FromReflect>(
+    commands: &mut Commands,
+    persistence: &mut PersistenceRegistry,
+    registry: &AppTypeRegistry,
+    type_path: &str,
+    entity: Entity,
+    key: Option<String>,
+) {
+    let Some(key) = key.map(|key| format!("{}{}", component_entry_prefix(type_path), key)) else {
+        return;
+    };
+    let Some(data) = persistence.data.get(&key).cloned() else {
+        return;
+    };
+    match persistence.unpack_resource::<T>(type_path, &key, data, registry) {
+        Ok(component) => {
+            commands.entity(entity).insert(component);
+        }
+        Err(error) => {
+            warn!(
+                "Persistence component {} could not be loaded: {}",
+                key, error
+            );
+            persistence.errors.push(PersistenceError::Resource {
+                type_path: key,
+                error,
+            });
+        }
+    }
+}
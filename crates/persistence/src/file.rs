@@ -0,0 +1,77 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::utils::HashMap;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+/// Persisted resource data with version of its format
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PersistenceEntry {
+    Versioned {
+        version: u32,
+        data: String,
+    },
+    /// Entry written before versions were stored. Treated as version 0
+    Legacy(String),
+}
+
+impl PersistenceEntry {
+    pub const fn version(&self) -> u32 {
+        match self {
+            Self::Versioned { version, .. } => *version,
+            Self::Legacy(_) => 0,
+        }
+    }
+
+    pub fn data(&self) -> &str {
+        match self {
+            Self::Versioned { data, .. } | Self::Legacy(data) => data,
+        }
+    }
+}
+
+/// Content of persistence file (resource type path -> entry)
+pub type PersistenceFileData = HashMap<String, PersistenceEntry>;
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path.as_os_str());
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Copy of last successfully read file
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Copy of file which could not be read, so it is not lost on next save
+pub fn corrupted_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".corrupted")
+}
+
+pub fn read_file(path: &Path) -> Result<PersistenceFileData, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    ron::from_str(&text).map_err(|e| e.to_string())
+}
+
+/// Write data to temporary file and move it over target file, so interrupted write can't corrupt it.
+/// Previous file is kept as backup if it is readable
+pub fn write_file(path: &Path, data: &PersistenceFileData) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    if read_file(path).is_ok() {
+        fs::copy(path, backup_path(path)).map_err(|e| e.to_string())?;
+    }
+
+    let text =
+        ron::ser::to_string_pretty(data, PrettyConfig::default()).map_err(|e| e.to_string())?;
+    let tmp_path = with_suffix(path, ".tmp");
+    fs::write(&tmp_path, text).map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
@@ -1,5 +1,9 @@
 #![allow(clippy::type_complexity)]
 
+/// Persistence of components on entities marked with [`Persistent`]
+pub mod component;
+/// Persistence file format and atomic file writing
+pub mod file;
 #[cfg(test)]
 mod tests;
 // This part of code is used for saving and loading settings and window state
@@ -9,11 +13,16 @@ use bevy::{
         serde::{ReflectDeserializer, ReflectSerializer},
         GetTypeRegistration,
     },
-    utils::HashMap,
+    utils::{HashMap, HashSet},
     window::WindowCloseRequested,
 };
-use ron::ser::PrettyConfig;
+use component::{entry_type_path, persistence_component_system};
+pub use component::{persistent_entity_key, Persistent};
+use file::{
+    backup_path, corrupted_path, read_file, write_file, PersistenceEntry, PersistenceFileData,
+};
 use serde::de::DeserializeSeed;
+use std::path::{Path, PathBuf};
 
 /// Plugin that enables persistence for marked entities
 pub struct PersistencePlugin;
@@ -32,6 +41,7 @@ impl Plugin for PersistencePlugin {
 
         app.add_event::<PersistenceEvent>();
         app.add_event::<PersistenceResourceBroadcastEvent>();
+        app.add_event::<PersistenceError>();
 
         app.configure_sets(
             Update,
@@ -50,7 +60,12 @@ impl Plugin for PersistencePlugin {
             Update,
             persistence_start.in_set(PersistenceSet::EventReader),
         );
-        app.add_systems(Update, persistence_end.in_set(PersistenceSet::Collect));
+        app.add_systems(
+            Update,
+            (persistence_end, persistence_send_errors)
+                .chain()
+                .in_set(PersistenceSet::Collect),
+        );
 
         app.persistence_resource::<PersistenceSettings>();
     }
@@ -88,19 +103,33 @@ fn persistence_start(
                 persistence.save_counter = 0;
             }
             PersistenceEvent::Load => {
-                match &persistence.source {
-                    PersistenceDataSource::File(path) => {
-                        let Ok(file) = std::fs::File::open(path) else {
-                            warn!("Persistence file not found at path {}", path);
-                            continue;
-                        };
-                        let data: HashMap<String, String> = ron::de::from_reader(file).unwrap();
-                        persistence.data = data;
-                    }
-                    PersistenceDataSource::Memory => {
-                        //do nothing
+                // Resources keep their current values if no layer has data
+                let mut found = false;
+                for layer in PersistenceLayer::LOAD_ORDER {
+                    match persistence.source(layer).clone() {
+                        PersistenceDataSource::File(path) => {
+                            if std::fs::metadata(&path).is_err() {
+                                warn!("Persistence file not found at path {}", path);
+                                persistence.layer_data.remove(&layer);
+                                continue;
+                            }
+                            let Some(data) = persistence.read_layer_file(Path::new(&path)) else {
+                                persistence.layer_data.remove(&layer);
+                                continue;
+                            };
+                            persistence.layer_data.insert(layer, data);
+                            found = true;
+                        }
+                        PersistenceDataSource::Memory => {
+                            // Memory layer has data only if it was saved before
+                            found |= persistence.layer_data.contains_key(&layer);
+                        }
                     }
                 }
+                if !found {
+                    continue;
+                }
+                persistence.merge_layers();
 
                 broadcast.send(PersistenceResourceBroadcastEvent::Unpack);
                 persistence.mode = PersistenceMode::Loading;
@@ -115,35 +144,49 @@ fn persistence_end(mut persistence: ResMut<PersistenceRegistry>) {
     match mode {
         PersistenceMode::Saving => {
             persistence.mode = PersistenceMode::None;
-            if persistence.save_counter != persistence.target_count {
+            if persistence.save_counter != persistence.targets.len() {
                 error!(
                     "Persistence saving error: {} of {} resources were saved",
-                    persistence.save_counter, persistence.target_count
+                    persistence.save_counter,
+                    persistence.targets.len()
                 );
             }
 
-            match &persistence.source {
-                PersistenceDataSource::File(path) => {
-                    let mut file = std::fs::File::create(path).unwrap();
-                    ron::ser::to_writer_pretty(
-                        &mut file,
-                        &persistence.data,
-                        PrettyConfig::default(),
-                    )
-                    .unwrap();
+            persistence.split_layers();
+            for layer in PersistenceLayer::LOAD_ORDER {
+                let PersistenceDataSource::File(path) = persistence.source(layer) else {
+                    continue;
+                };
+                let data = persistence
+                    .layer_data
+                    .get(&layer)
+                    .cloned()
+                    .unwrap_or_default();
+                // Per-user file is not created until something is saved to it.
+                // Empty layers are written otherwise, so entries moved to other layer are removed
+                if layer == PersistenceLayer::User
+                    && data.is_empty()
+                    && std::fs::metadata(path).is_err()
+                {
+                    continue;
                 }
-                PersistenceDataSource::Memory => {
-                    //do nothing
+                if let Err(error) = write_file(Path::new(path), &data) {
+                    error!("Failed to save persistence file {}: {}", path, error);
+                    let path = path.clone();
+                    persistence
+                        .errors
+                        .push(PersistenceError::Save { path, error });
                 }
             }
-            {}
         }
         PersistenceMode::Loading => {
             persistence.mode = PersistenceMode::None;
-            if persistence.load_counter != persistence.target_count {
+            // Resources without saved entries keep their values and are not counted
+            let present = persistence.present_target_count();
+            if persistence.load_counter != present {
                 error!(
                     "Persistence loading error: {} of {} resources were loaded",
-                    persistence.load_counter, persistence.target_count
+                    persistence.load_counter, present
                 );
             }
         }
@@ -151,6 +194,13 @@ fn persistence_end(mut persistence: ResMut<PersistenceRegistry>) {
     }
 }
 
+fn persistence_send_errors(
+    mut persistence: ResMut<PersistenceRegistry>,
+    mut errors: EventWriter<PersistenceError>,
+) {
+    errors.send_batch(persistence.errors.drain(..));
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct PersistenceSettings {
@@ -175,27 +225,259 @@ enum PersistenceMode {
     None,
 }
 
+/// Layer of persistence data. Layers are merged on load with precedence
+/// defaults < project < user, so personal preferences override project settings.
+///
+/// Built-in defaults are not stored in a layer: they are the `Default` impls of persisted
+/// resources, which are kept for entries missing in all layers
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PersistenceLayer {
+    /// Project file, which is intended to be committed to VCS
+    #[default]
+    Project,
+    /// Per-user file in platform config directory
+    User,
+}
+
+impl PersistenceLayer {
+    /// Layers from lowest to highest precedence
+    pub const LOAD_ORDER: [Self; 2] = [Self::Project, Self::User];
+}
+
 /// ['PersistenceRegistry'] contains lambda functions for loading/unloading editor state
 /// At the moment of closing the window or starting the game mode,
 /// all necessary data is saved to a file/memory, and then restored when the editor mode is opened.
 /// When the restored resource is loaded, the ['PersistenceLoaded<T>'] event is generated
 ///
 /// ['PersistenceLoaded<T>']: crate::editor::core::persistence::PersistenceLoaded
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct PersistenceRegistry {
     source: PersistenceDataSource,
+    user_source: PersistenceDataSource,
+    /// Merged data of all layers
     data: HashMap<String, String>,
+    /// Version of merged data entries
+    data_versions: HashMap<String, u32>,
+    layer_data: HashMap<PersistenceLayer, PersistenceFileData>,
+    /// Layer to which resource is saved (by type path)
+    layers: HashMap<String, PersistenceLayer>,
+    /// Current data version of resource (by type path)
+    versions: HashMap<String, u32>,
+    migrations: HashMap<String, PersistenceMigration>,
+    /// Errors which will be sent as [`PersistenceError`] events
+    errors: Vec<PersistenceError>,
     load_counter: usize,
     save_counter: usize,
-    target_count: usize,
+    /// Type paths of persisted resources and components
+    targets: HashSet<String>,
     mode: PersistenceMode,
 }
 
-#[derive(Event, Default)]
+impl Default for PersistenceRegistry {
+    fn default() -> Self {
+        Self {
+            source: PersistenceDataSource::default(),
+            // Editor sets per-user file explicitly, so tests never touch files of user
+            user_source: PersistenceDataSource::Memory,
+            data: HashMap::default(),
+            data_versions: HashMap::default(),
+            layer_data: HashMap::default(),
+            layers: HashMap::default(),
+            versions: HashMap::default(),
+            migrations: HashMap::default(),
+            errors: Vec::new(),
+            load_counter: 0,
+            save_counter: 0,
+            targets: HashSet::default(),
+            mode: PersistenceMode::default(),
+        }
+    }
+}
+
+impl PersistenceRegistry {
+    /// Data source of layer
+    pub const fn source(&self, layer: PersistenceLayer) -> &PersistenceDataSource {
+        match layer {
+            PersistenceLayer::Project => &self.source,
+            PersistenceLayer::User => &self.user_source,
+        }
+    }
+
+    pub fn set_source(&mut self, layer: PersistenceLayer, source: PersistenceDataSource) {
+        match layer {
+            PersistenceLayer::Project => self.source = source,
+            PersistenceLayer::User => self.user_source = source,
+        }
+    }
+
+    /// Layer to which resource `T` is saved
+    pub fn layer<T: GetTypeRegistration>(&self) -> PersistenceLayer {
+        self.layers
+            .get(T::get_type_registration().type_info().type_path())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_layer<T: GetTypeRegistration>(&mut self, layer: PersistenceLayer) {
+        self.layers.insert(
+            T::get_type_registration()
+                .type_info()
+                .type_path()
+                .to_string(),
+            layer,
+        );
+    }
+
+    /// Set current data version of resource `T`. Older data is passed to `migration` on load
+    pub fn set_version<T: GetTypeRegistration>(
+        &mut self,
+        version: u32,
+        migration: PersistenceMigration,
+    ) {
+        let type_path = T::get_type_registration()
+            .type_info()
+            .type_path()
+            .to_string();
+        self.versions.insert(type_path.clone(), version);
+        self.migrations.insert(type_path, migration);
+    }
+
+    fn add_target<T: GetTypeRegistration>(&mut self) {
+        self.targets.insert(
+            T::get_type_registration()
+                .type_info()
+                .type_path()
+                .to_string(),
+        );
+    }
+
+    /// Number of persisted resources and components which have entries in loaded data
+    fn present_target_count(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|type_path| {
+                self.data
+                    .keys()
+                    .any(|key| entry_type_path(key) == type_path.as_str())
+            })
+            .count()
+    }
+
+    /// Read file of layer. Corrupted file is replaced by data from backup if possible
+    fn read_layer_file(&mut self, path: &Path) -> Option<PersistenceFileData> {
+        let error = match read_file(path) {
+            Ok(data) => return Some(data),
+            Err(error) => error,
+        };
+        error!("Failed to load persistence file {:?}: {}", path, error);
+        self.errors.push(PersistenceError::Load {
+            path: path.to_string_lossy().to_string(),
+            error,
+        });
+        if let Err(err) = std::fs::copy(path, corrupted_path(path)) {
+            error!("Failed to keep copy of corrupted persistence file: {}", err);
+        }
+
+        let backup = backup_path(path);
+        let data = read_file(&backup).ok()?;
+        warn!("Persistence data restored from backup {:?}", backup);
+        Some(data)
+    }
+
+    /// Merge layer data in order of precedence
+    fn merge_layers(&mut self) {
+        self.data.clear();
+        self.data_versions.clear();
+        for layer in PersistenceLayer::LOAD_ORDER {
+            if let Some(data) = self.layer_data.get(&layer) {
+                for (key, entry) in data.iter() {
+                    self.data.insert(key.clone(), entry.data().to_string());
+                    self.data_versions.insert(key.clone(), entry.version());
+                }
+            }
+        }
+    }
+
+    /// Move packed data to layers. Unknown entries stay in layer they were loaded from
+    fn split_layers(&mut self) {
+        for (key, value) in self.data.iter() {
+            let layer = self
+                .layers
+                .get(entry_type_path(key))
+                .copied()
+                .unwrap_or_else(|| {
+                    PersistenceLayer::LOAD_ORDER
+                        .into_iter()
+                        .rev()
+                        .find(|layer| {
+                            self.layer_data
+                                .get(layer)
+                                .is_some_and(|data| data.contains_key(key))
+                        })
+                        .unwrap_or_default()
+                });
+            for other in PersistenceLayer::LOAD_ORDER {
+                if other != layer {
+                    if let Some(data) = self.layer_data.get_mut(&other) {
+                        data.remove(key);
+                    }
+                }
+            }
+            let version = self.data_versions.get(key).copied().unwrap_or_default();
+            self.layer_data.entry(layer).or_default().insert(
+                key.clone(),
+                PersistenceEntry::Versioned {
+                    version,
+                    data: value.clone(),
+                },
+            );
+        }
+    }
+}
+
+#[derive(Event)]
 pub struct PersistenceLoaded<T> {
     _phantom: std::marker::PhantomData<T>,
 }
 
+impl<T> Default for PersistenceLoaded<T> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Migration of persisted RON data from stored version to current version
+pub type PersistenceMigration = Box<dyn Fn(u32, String) -> Result<String, String> + Send + Sync>;
+
+/// Error of loading or saving persistence data
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum PersistenceError {
+    /// File could not be read or parsed
+    Load { path: String, error: String },
+    /// File could not be written
+    Save { path: String, error: String },
+    /// Resource could not be serialized, migrated or deserialized
+    Resource { type_path: String, error: String },
+}
+
+impl std::fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Load { path, error } => {
+                write!(f, "Failed to load settings from {}: {}", path, error)
+            }
+            Self::Save { path, error } => {
+                write!(f, "Failed to save settings to {}: {}", path, error)
+            }
+            Self::Resource { type_path, error } => {
+                write!(f, "Failed to persist {}: {}", type_path, error)
+            }
+        }
+    }
+}
+
 #[derive(Event)]
 pub enum PersistenceEvent {
     Save,
@@ -221,6 +503,37 @@ impl Default for PersistenceDataSource {
     }
 }
 
+impl PersistenceDataSource {
+    /// Per-user file in platform config directory. Falls back to memory if directory is unknown
+    pub fn user_config() -> Self {
+        user_config_dir().map_or(Self::Memory, |dir| {
+            Self::File(
+                dir.join("space_editor")
+                    .join("editor.ron")
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
 #[derive(Resource)]
 struct PersistenceLoadPipeline<T> {
     pub load_fn: Box<dyn Fn(&mut T, T) + Send + Sync>,
@@ -237,6 +550,7 @@ impl<T> Default for PersistenceLoadPipeline<T> {
 }
 
 pub trait AppPersistenceExt {
+    /// Persist resource `T`. Its `Default` value is kept if no layer has saved entry
     fn persistence_resource<T: Default + Reflect + FromReflect + Resource + GetTypeRegistration>(
         &mut self,
     ) -> &mut Self;
@@ -247,6 +561,24 @@ pub trait AppPersistenceExt {
         &mut self,
         load_function: Box<dyn Fn(&mut T, T) + Send + Sync>,
     ) -> &mut Self;
+
+    /// Persist component `T` of entities marked with [`Persistent`].
+    /// Saved value is restored to entity with the same [`PersistentId`](space_shared::PersistentId) or [`Name`],
+    /// also if it is spawned after load
+    fn persistence_component<T: Component + Reflect + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Save resource or component `T` to given layer (project layer by default)
+    fn persistence_layer<T: GetTypeRegistration>(&mut self, layer: PersistenceLayer) -> &mut Self;
+
+    /// Set current data version of resource `T` (0 by default).
+    /// Data saved with older version is passed to `migration` with its version before loading
+    fn persistence_version<T: GetTypeRegistration>(
+        &mut self,
+        version: u32,
+        migration: PersistenceMigration,
+    ) -> &mut Self;
 }
 
 impl AppPersistenceExt for App {
@@ -255,7 +587,7 @@ impl AppPersistenceExt for App {
     ) -> &mut Self {
         self.world_mut()
             .resource_mut::<PersistenceRegistry>()
-            .target_count += 1;
+            .add_target::<T>();
 
         self.register_type::<T>();
         self.add_event::<PersistenceLoaded<T>>();
@@ -278,7 +610,7 @@ impl AppPersistenceExt for App {
     ) -> &mut Self {
         self.world_mut()
             .resource_mut::<PersistenceRegistry>()
-            .target_count += 1;
+            .add_target::<T>();
 
         self.register_type::<T>();
         self.add_event::<PersistenceLoaded<T>>();
@@ -294,6 +626,42 @@ impl AppPersistenceExt for App {
 
         self
     }
+
+    fn persistence_component<T: Component + Reflect + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<PersistenceRegistry>()
+            .add_target::<T>();
+
+        self.register_type::<T>().register_type::<Persistent>();
+        self.add_event::<PersistenceLoaded<T>>();
+
+        self.add_systems(
+            Update,
+            persistence_component_system::<T>.in_set(PersistenceSet::ResourceProcess),
+        );
+
+        self
+    }
+
+    fn persistence_layer<T: GetTypeRegistration>(&mut self, layer: PersistenceLayer) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<PersistenceRegistry>()
+            .set_layer::<T>(layer);
+        self
+    }
+
+    fn persistence_version<T: GetTypeRegistration>(
+        &mut self,
+        version: u32,
+        migration: PersistenceMigration,
+    ) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<PersistenceRegistry>()
+            .set_version::<T>(version, migration);
+        self
+    }
 }
 
 fn persistence_resource_system<
@@ -306,50 +674,55 @@ fn persistence_resource_system<
     mut persistence_loaded: EventWriter<PersistenceLoaded<T>>,
     pipeline: ResMut<PersistenceLoadPipeline<T>>,
 ) {
+    let type_path = T::get_type_registration()
+        .type_info()
+        .type_path()
+        .to_string();
     for event in events.read() {
         match event {
             PersistenceResourceBroadcastEvent::Pack => {
                 let type_registry = registry.read();
                 let serializer = ReflectSerializer::new(resource.as_ref(), &type_registry);
-                let data = ron::to_string(&serializer).unwrap();
-                persistence.data.insert(
-                    T::get_type_registration()
-                        .type_info()
-                        .type_path()
-                        .to_string(),
-                    data,
-                );
-                persistence.save_counter += 1;
+                match ron::to_string(&serializer) {
+                    Ok(data) => {
+                        let version = persistence
+                            .versions
+                            .get(&type_path)
+                            .copied()
+                            .unwrap_or_default();
+                        persistence.data.insert(type_path.clone(), data);
+                        persistence.data_versions.insert(type_path.clone(), version);
+                        persistence.save_counter += 1;
+                    }
+                    Err(err) => {
+                        error!("Persistence resource {} could not be serialized", type_path);
+                        persistence.errors.push(PersistenceError::Resource {
+                            type_path: type_path.clone(),
+                            error: err.to_string(),
+                        });
+                    }
+                }
             }
             PersistenceResourceBroadcastEvent::Unpack => {
-                let Some(data) = persistence
-                    .data
-                    .get(T::get_type_registration().type_info().type_path())
-                else {
-                    warn!(
-                        "Persistence resource {} not found",
-                        T::get_type_registration().type_info().type_path()
-                    );
+                let Some(data) = persistence.data.get(&type_path).cloned() else {
+                    warn!("Persistence resource {} not found", type_path);
                     continue;
                 };
-                let type_registry = registry.read();
-                let deserializer = ReflectDeserializer::new(&type_registry);
-                let Ok(reflected_value) =
-                    deserializer.deserialize(&mut ron::Deserializer::from_str(data).unwrap())
-                else {
-                    warn!(
-                        "Persistence resource {} could not be deserialized",
-                        T::get_type_registration().type_info().type_path()
-                    );
-                    continue;
-                };
-
-                let Some(converted) = <T as FromReflect>::from_reflect(&*reflected_value) else {
-                    warn!(
-                        "Persistence resource {} could not be converted",
-                        T::get_type_registration().type_info().type_path()
-                    );
-                    continue;
+                let converted = match persistence
+                    .unpack_resource::<T>(&type_path, &type_path, data, &registry)
+                {
+                    Ok(converted) => converted,
+                    Err(error) => {
+                        warn!(
+                            "Persistence resource {} could not be loaded: {}",
+                            type_path, error
+                        );
+                        persistence.errors.push(PersistenceError::Resource {
+                            type_path: type_path.clone(),
+                            error,
+                        });
+                        continue;
+                    }
                 };
                 (pipeline.load_fn)(resource.as_mut(), converted);
                 resource.set_changed();
@@ -360,3 +733,36 @@ fn persistence_resource_system<
         }
     }
 }
+
+impl PersistenceRegistry {
+    /// Migrate stored data of entry `key` to current version of `type_path` and convert it to value
+    fn unpack_resource<T: FromReflect>(
+        &self,
+        type_path: &str,
+        key: &str,
+        mut data: String,
+        registry: &AppTypeRegistry,
+    ) -> Result<T, String> {
+        let stored_version = self.data_versions.get(key).copied().unwrap_or_default();
+        let version = self.versions.get(type_path).copied().unwrap_or_default();
+        if stored_version > version {
+            return Err(format!(
+                "data version {} is newer than supported version {}",
+                stored_version, version
+            ));
+        }
+        if stored_version < version {
+            if let Some(migration) = self.migrations.get(type_path) {
+                data = migration(stored_version, data)?;
+            }
+        }
+
+        let type_registry = registry.read();
+        let deserializer = ReflectDeserializer::new(&type_registry);
+        let reflected_value = deserializer
+            .deserialize(&mut ron::Deserializer::from_str(&data).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+        <T as FromReflect>::from_reflect(&*reflected_value)
+            .ok_or_else(|| "data could not be converted".to_string())
+    }
+}
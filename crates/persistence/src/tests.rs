@@ -1,4 +1,5 @@
 use super::*;
+use space_shared::PersistentId;
 
 #[test]
 fn save_on_close_triggers_event() {
@@ -95,7 +96,7 @@ fn persistence_starts_on_save() {
 }
 
 #[test]
-fn persistence_starts_on_load_mem() {
+fn persistence_not_starts_on_load_empty_mem() {
     let mut app = App::new();
     app.insert_resource(PersistenceRegistry {
         source: PersistenceDataSource::Memory,
@@ -112,14 +113,10 @@ fn persistence_starts_on_load_mem() {
         .world_mut()
         .get_resource::<Events<PersistenceResourceBroadcastEvent>>();
 
-    assert!(event.is_some());
-    assert_eq!(event.unwrap().len(), 1);
-
-    let persistence = app.world_mut().get_resource::<PersistenceRegistry>();
+    assert_eq!(event.unwrap().len(), 0);
 
-    assert!(persistence.is_some());
-    let reg = persistence.unwrap();
-    assert_eq!(reg.mode, PersistenceMode::Loading);
+    let reg = app.world().resource::<PersistenceRegistry>();
+    assert_eq!(reg.mode, PersistenceMode::None);
     assert_eq!(reg.load_counter, 0);
 }
 
@@ -160,6 +157,9 @@ fn persistence_starts_on_file_not_found() {
     let mut app = App::new();
     app.insert_resource(PersistenceRegistry {
         source: PersistenceDataSource::File(String::from("../../test_data/fake_editor.ron")),
+        user_source: PersistenceDataSource::File(String::from(
+            "../../test_data/fake_user_editor.ron",
+        )),
         ..Default::default()
     })
     .add_event::<PersistenceEvent>()
@@ -186,6 +186,7 @@ fn persistence_starts_on_load_from_memory() {
     let mut app = App::new();
     app.insert_resource(PersistenceRegistry {
         source: PersistenceDataSource::Memory,
+        layer_data: HashMap::from([(PersistenceLayer::Project, PersistenceFileData::default())]),
         ..Default::default()
     })
     .add_event::<PersistenceEvent>()
@@ -236,6 +237,29 @@ fn persistence_end_from_loading() {
     assert_eq!(persistence.mode, PersistenceMode::None);
 }
 
+#[test]
+fn persistence_load_expects_only_present_entries() {
+    let mut reg = PersistenceRegistry {
+        data: HashMap::from([
+            (
+                "space_persistence::PersistenceSettings".to_string(),
+                String::new(),
+            ),
+            (
+                "bevy_transform::components::transform::Transform#name:Bookmark".to_string(),
+                String::new(),
+            ),
+        ]),
+        ..Default::default()
+    };
+    reg.add_target::<PersistenceSettings>();
+    reg.add_target::<Transform>();
+    reg.add_target::<Name>();
+
+    assert_eq!(reg.targets.len(), 3);
+    assert_eq!(reg.present_target_count(), 2);
+}
+
 #[test]
 fn persistence_end_from_saving_mem() {
     let mut app = App::new();
@@ -301,7 +325,7 @@ fn persistence_system_unpack() {
     let settings = app.world().resource::<PersistenceSettings>();
     let count = app.world().resource::<PersistenceRegistry>();
 
-    assert_eq!(count.target_count, 1);
+    assert_eq!(count.targets.len(), 1);
     assert_eq!(count.load_counter, 1);
     assert_eq!(count.save_counter, 0);
     assert!(!settings.save_on_close);
@@ -338,3 +362,336 @@ fn persistence_system_pack() {
     let reg = app.world_mut().resource::<PersistenceRegistry>();
     assert_eq!(reg.save_counter, 1)
 }
+
+#[test]
+fn persistence_user_layer_overrides_project() {
+    std::fs::write(
+        "../../target/layers_project.ron",
+        "{\"shared\": \"project\", \"personal\": \"project\"}",
+    )
+    .unwrap();
+    std::fs::write("../../target/layers_user.ron", "{\"personal\": \"user\"}").unwrap();
+
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        source: PersistenceDataSource::File("../../target/layers_project.ron".to_string()),
+        user_source: PersistenceDataSource::File("../../target/layers_user.ron".to_string()),
+        ..Default::default()
+    })
+    .add_event::<PersistenceEvent>()
+    .add_event::<PersistenceResourceBroadcastEvent>()
+    .add_systems(PreUpdate, persistence_start);
+
+    app.world_mut().send_event(PersistenceEvent::Load);
+    app.update();
+
+    let reg = app.world().resource::<PersistenceRegistry>();
+    assert_eq!(reg.data["shared"], "project");
+    assert_eq!(reg.data["personal"], "user");
+}
+
+#[test]
+fn persistence_saves_resource_to_its_layer() {
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        mode: PersistenceMode::Saving,
+        source: PersistenceDataSource::File("../../target/save_layers_project.ron".to_string()),
+        user_source: PersistenceDataSource::File("../../target/save_layers_user.ron".to_string()),
+        data: HashMap::from([
+            (
+                "space_persistence::PersistenceSettings".to_string(),
+                "settings".to_string(),
+            ),
+            ("unknown".to_string(), "value".to_string()),
+        ]),
+        ..Default::default()
+    })
+    .add_systems(PreUpdate, persistence_end);
+    app.world_mut()
+        .resource_mut::<PersistenceRegistry>()
+        .set_layer::<PersistenceSettings>(PersistenceLayer::User);
+
+    app.update();
+
+    let read = |path: &str| file::read_file(std::path::Path::new(path)).unwrap();
+    let project = read("../../target/save_layers_project.ron");
+    let user = read("../../target/save_layers_user.ron");
+    assert_eq!(project.len(), 1);
+    assert_eq!(project["unknown"].data(), "value");
+    assert_eq!(user.len(), 1);
+    assert_eq!(
+        user["space_persistence::PersistenceSettings"].data(),
+        "settings"
+    );
+}
+
+#[test]
+fn persistence_corrupted_file_is_restored_from_backup() {
+    std::fs::write("../../target/corrupted_editor.ron", "{\"key\": (versio").unwrap();
+    std::fs::write(
+        "../../target/corrupted_editor.ron.bak",
+        "{\"key\": (version: 1, data: \"backup\")}",
+    )
+    .unwrap();
+
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        source: PersistenceDataSource::File("../../target/corrupted_editor.ron".to_string()),
+        user_source: PersistenceDataSource::Memory,
+        ..Default::default()
+    })
+    .add_event::<PersistenceEvent>()
+    .add_event::<PersistenceResourceBroadcastEvent>()
+    .add_event::<PersistenceError>()
+    .add_systems(
+        PreUpdate,
+        (persistence_start, persistence_send_errors).chain(),
+    );
+
+    app.world_mut().send_event(PersistenceEvent::Load);
+    app.update();
+
+    let reg = app.world().resource::<PersistenceRegistry>();
+    assert_eq!(reg.data["key"], "backup");
+    assert_eq!(reg.data_versions["key"], 1);
+    assert!(std::fs::metadata("../../target/corrupted_editor.ron.corrupted").is_ok());
+
+    let errors = app.world().resource::<Events<PersistenceError>>();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn persistence_save_keeps_backup_of_previous_file() {
+    let path = "../../target/backup_editor.ron";
+    let _ = std::fs::remove_file(format!("{}.bak", path));
+    std::fs::write(path, "{\"key\": \"old\"}").unwrap();
+
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        mode: PersistenceMode::Saving,
+        source: PersistenceDataSource::File(path.to_string()),
+        user_source: PersistenceDataSource::Memory,
+        data: HashMap::from([("key".to_string(), "new".to_string())]),
+        ..Default::default()
+    })
+    .add_systems(PreUpdate, persistence_end);
+    app.update();
+
+    let saved = file::read_file(std::path::Path::new(path)).unwrap();
+    let backup = file::read_file(&file::backup_path(std::path::Path::new(path))).unwrap();
+    assert_eq!(saved["key"].data(), "new");
+    assert_eq!(backup["key"].data(), "old");
+}
+
+#[test]
+fn persistence_migrates_old_data() {
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        mode: PersistenceMode::Loading,
+        source: PersistenceDataSource::Memory,
+        data: HashMap::from([(
+            "space_persistence::PersistenceSettings".to_string(),
+            "{\"space_persistence::PersistenceSettings\":(load_on_startup:true,close:false)}"
+                .to_string(),
+        )]),
+        ..Default::default()
+    })
+    .init_resource::<PersistenceSettings>()
+    .add_event::<PersistenceEvent>()
+    .add_event::<PersistenceResourceBroadcastEvent>();
+    app.configure_sets(
+        Update,
+        (
+            PersistenceSet::EventReader,
+            PersistenceSet::ResourceProcess,
+            PersistenceSet::Collect,
+        )
+            .chain(),
+    );
+    app.persistence_resource::<PersistenceSettings>()
+        .persistence_version::<PersistenceSettings>(
+            1,
+            Box::new(|version, data| {
+                assert_eq!(version, 0);
+                Ok(data.replace("close:", "save_on_close:"))
+            }),
+        );
+    app.update();
+    app.world_mut()
+        .send_event(PersistenceResourceBroadcastEvent::Unpack);
+    app.update();
+
+    let settings = app.world().resource::<PersistenceSettings>();
+    assert!(!settings.save_on_close);
+    assert_eq!(
+        app.world().resource::<PersistenceRegistry>().load_counter,
+        1
+    );
+}
+
+#[test]
+fn persistence_component_roundtrip_by_name_and_id() {
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        source: PersistenceDataSource::Memory,
+        user_source: PersistenceDataSource::Memory,
+        ..Default::default()
+    })
+    .add_event::<PersistenceEvent>()
+    .add_event::<PersistenceResourceBroadcastEvent>()
+    .add_event::<PersistenceError>();
+    app.configure_sets(
+        Update,
+        (
+            PersistenceSet::EventReader,
+            PersistenceSet::ResourceProcess,
+            PersistenceSet::Collect,
+        )
+            .chain(),
+    );
+    app.persistence_component::<Transform>();
+    let by_name = app
+        .world_mut()
+        .spawn((
+            Persistent,
+            Name::new("Bookmark"),
+            Transform::from_xyz(1.0, 2.0, 3.0),
+        ))
+        .id();
+    let by_id = app
+        .world_mut()
+        .spawn((
+            Persistent,
+            PersistentId(7),
+            Transform::from_xyz(4.0, 5.0, 6.0),
+        ))
+        .id();
+    app.update();
+    app.world_mut()
+        .send_event(PersistenceResourceBroadcastEvent::Pack);
+    app.update();
+
+    let reg = app.world().resource::<PersistenceRegistry>();
+    assert_eq!(reg.save_counter, 1);
+    assert!(reg
+        .data
+        .contains_key("bevy_transform::components::transform::Transform#name:Bookmark"));
+    assert!(reg
+        .data
+        .contains_key("bevy_transform::components::transform::Transform#id:7"));
+
+    app.world_mut().despawn(by_name);
+    app.world_mut()
+        .entity_mut(by_id)
+        .insert(Transform::default());
+    app.world_mut()
+        .send_event(PersistenceResourceBroadcastEvent::Unpack);
+    app.update();
+    assert_eq!(
+        app.world().get::<Transform>(by_id),
+        Some(&Transform::from_xyz(4.0, 5.0, 6.0))
+    );
+
+    // Entity spawned after load gets saved state
+    let respawned = app
+        .world_mut()
+        .spawn((Persistent, Name::new("Bookmark")))
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<Transform>(respawned),
+        Some(&Transform::from_xyz(1.0, 2.0, 3.0))
+    );
+}
+
+#[test]
+fn persistence_component_keeps_entries_of_not_spawned_entities() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<PersistenceRegistry>()
+        .add_event::<PersistenceResourceBroadcastEvent>()
+        .add_event::<PersistenceError>();
+    app.persistence_component::<Transform>();
+    let key = "bevy_transform::components::transform::Transform#name:Other scene bookmark";
+    app.world_mut()
+        .resource_mut::<PersistenceRegistry>()
+        .data
+        .insert(key.to_string(), "()".to_string());
+    app.world_mut()
+        .spawn((Persistent, Name::new("Bookmark"), Transform::default()));
+    app.update();
+    app.world_mut()
+        .send_event(PersistenceResourceBroadcastEvent::Pack);
+    app.update();
+
+    let mut reg = app.world_mut().resource_mut::<PersistenceRegistry>();
+    assert!(reg.data.contains_key(key));
+    assert!(reg
+        .data
+        .contains_key("bevy_transform::components::transform::Transform#name:Bookmark"));
+
+    reg.remove_persistent_entity(
+        &persistent_entity_key(None, Some(&Name::new("Other scene bookmark"))).unwrap(),
+    );
+    assert!(!reg.data.contains_key(key));
+    assert!(reg
+        .data
+        .contains_key("bevy_transform::components::transform::Transform#name:Bookmark"));
+}
+
+#[test]
+fn persistence_component_entries_use_layer_of_type() {
+    let mut registry = PersistenceRegistry::default();
+    registry.set_layer::<Transform>(PersistenceLayer::User);
+    registry.data.insert(
+        "bevy_transform::components::transform::Transform#name:Bookmark".to_string(),
+        "()".to_string(),
+    );
+    registry.split_layers();
+    assert!(registry.layer_data[&PersistenceLayer::User]
+        .contains_key("bevy_transform::components::transform::Transform#name:Bookmark"));
+}
+
+#[test]
+fn persistence_moved_resource_is_removed_from_old_layer() {
+    std::fs::write(
+        "../../target/moved_layers_project.ron",
+        "{\"space_persistence::PersistenceSettings\": \"old\"}",
+    )
+    .unwrap();
+    let _ = std::fs::remove_file("../../target/moved_layers_user.ron");
+
+    let mut app = App::new();
+    app.insert_resource(PersistenceRegistry {
+        source: PersistenceDataSource::File("../../target/moved_layers_project.ron".to_string()),
+        user_source: PersistenceDataSource::File("../../target/moved_layers_user.ron".to_string()),
+        ..Default::default()
+    })
+    .add_event::<PersistenceEvent>()
+    .add_event::<PersistenceResourceBroadcastEvent>()
+    .add_systems(PreUpdate, (persistence_start, persistence_end).chain());
+    app.world_mut()
+        .resource_mut::<PersistenceRegistry>()
+        .set_layer::<PersistenceSettings>(PersistenceLayer::User);
+
+    app.world_mut().send_event(PersistenceEvent::Load);
+    app.update();
+    app.world_mut().send_event(PersistenceEvent::Save);
+    app.update();
+    app.update();
+
+    let read = |path: &str| file::read_file(std::path::Path::new(path)).unwrap();
+    let project = read("../../target/moved_layers_project.ron");
+    let user = read("../../target/moved_layers_user.ron");
+    assert!(project.is_empty());
+    assert_eq!(user["space_persistence::PersistenceSettings"].data(), "old");
+}
+
+#[test]
+fn persistence_default_user_layer_is_in_memory() {
+    let reg = PersistenceRegistry::default();
+    assert!(matches!(
+        reg.source(PersistenceLayer::User),
+        PersistenceDataSource::Memory
+    ));
+}
@@ -0,0 +1,330 @@
+use bevy::{
+    animation::{AnimationTarget, AnimationTargetId},
+    utils::HashMap,
+};
+
+use crate::{ext::*, EditorState};
+
+use super::GltfPrefab;
+
+/// Animation clip of gltf file referenced by asset label
+#[derive(Reflect, Clone, Default, Debug, PartialEq, Eq)]
+#[reflect(Default)]
+pub struct GltfAnimationClip {
+    /// Name of animation in gltf
+    pub name: String,
+    /// Asset label of clip, for example `Animation0`
+    pub label: String,
+}
+
+/// Prefab component that references animation clips of gltf file.
+/// [`AnimationGraph`] and [`AnimationPlayer`] are created after prefab spawn
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+pub struct GltfAnimationPrefab {
+    /// Path to gltf file. Path of [`GltfPrefab`] on the same entity is used if empty
+    pub path: String,
+    /// Clips are filled from gltf file by editor if empty
+    pub clips: Vec<GltfAnimationClip>,
+    /// Name of clip played in play mode. Nothing is played if empty
+    pub autoplay: String,
+    pub repeat: bool,
+    pub speed: f32,
+}
+
+impl Default for GltfAnimationPrefab {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            clips: vec![],
+            autoplay: String::new(),
+            repeat: true,
+            speed: 1.0,
+        }
+    }
+}
+
+impl GltfAnimationPrefab {
+    /// Asset path of clip
+    pub fn clip_path(&self, clip: &GltfAnimationClip) -> String {
+        format!("{}#{}", self.path, clip.label)
+    }
+}
+
+/// Prefab component for animated node of unpacked gltf. Stores names from
+/// the nearest parent with [`GltfAnimationPrefab`] to this node
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct GltfAnimationTargetPrefab {
+    pub names: Vec<String>,
+}
+
+impl GltfAnimationTargetPrefab {
+    pub fn target_id(&self) -> AnimationTargetId {
+        let names = self
+            .names
+            .iter()
+            .cloned()
+            .map(Name::new)
+            .collect::<Vec<_>>();
+        AnimationTargetId::from_names(names.iter())
+    }
+}
+
+/// Animation graph created from [`GltfAnimationPrefab`]
+#[derive(Component, Clone)]
+pub struct GltfAnimationGraph {
+    pub graph: Handle<AnimationGraph>,
+    /// Graph node and clip by clip name
+    pub clips: HashMap<String, (AnimationNodeIndex, Handle<AnimationClip>)>,
+    /// Entity with [`AnimationPlayer`] which uses the graph
+    pub player: Option<Entity>,
+}
+
+impl GltfAnimationGraph {
+    pub fn node(&self, clip: &str) -> Option<AnimationNodeIndex> {
+        self.clips.get(clip).map(|(node, _)| *node)
+    }
+}
+
+/// Start autoplay clip of prefab
+pub fn autoplay_gltf_animation(
+    prefab: &GltfAnimationPrefab,
+    graph: &GltfAnimationGraph,
+    player: &mut AnimationPlayer,
+) {
+    let Some(node) = graph.node(&prefab.autoplay) else {
+        return;
+    };
+    let animation = player.start(node);
+    animation.set_speed(prefab.speed);
+    if prefab.repeat {
+        animation.repeat();
+    }
+}
+
+/// Build [`AnimationGraph`] after [`GltfAnimationPrefab`] spawn or change
+pub fn sync_gltf_animation(
+    mut commands: Commands,
+    query: Query<(Entity, &GltfAnimationPrefab, Option<&GltfPrefab>), Changed<GltfAnimationPrefab>>,
+    asset_server: Res<AssetServer>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    for (e, prefab, gltf) in query.iter() {
+        let mut prefab = prefab.clone();
+        if prefab.path.is_empty() {
+            if let Some(gltf) = gltf {
+                prefab.path.clone_from(&gltf.path);
+            }
+        }
+
+        let mut graph = AnimationGraph::new();
+        let mut clips = HashMap::new();
+        for clip in prefab.clips.iter() {
+            let handle = asset_server.load::<AnimationClip>(prefab.clip_path(clip));
+            let node = graph.add_clip(handle.clone(), 1.0, graph.root);
+            clips.insert(clip.name.clone(), (node, handle));
+        }
+
+        commands.entity(e).insert(GltfAnimationGraph {
+            graph: graphs.add(graph),
+            clips,
+            player: None,
+        });
+        // Unpacked gltf has no player, its nodes are targets of prefab entity
+        if gltf.is_none() {
+            commands.entity(e).insert(AnimationPlayer::default());
+        }
+    }
+}
+
+/// Attach [`AnimationGraph`] to player of prefab when player is spawned
+pub fn attach_gltf_animation_graph(
+    mut commands: Commands,
+    mut prefabs: Query<(Entity, &GltfAnimationPrefab, &mut GltfAnimationGraph)>,
+    mut players: Query<&mut AnimationPlayer>,
+    children: Query<&Children>,
+    state: Option<Res<State<EditorState>>>,
+) {
+    for (e, prefab, mut graph) in prefabs.iter_mut() {
+        // Player of gltf scene is respawned with scene
+        if graph.player.is_some_and(|player| players.contains(player)) {
+            continue;
+        }
+        let player = std::iter::once(e)
+            .chain(children.iter_descendants(e))
+            .find(|e| players.contains(*e));
+        let Some(player) = player else {
+            continue;
+        };
+
+        commands.entity(player).insert(graph.graph.clone());
+        graph.player = Some(player);
+
+        let state = state.as_ref().map(|s| s.get().clone()).unwrap_or_default();
+        if state == EditorState::Game {
+            if let Ok(mut player) = players.get_mut(player) {
+                player.stop_all();
+                autoplay_gltf_animation(prefab, &graph, &mut player);
+            }
+        }
+    }
+}
+
+/// Link animated nodes of unpacked gltf to player of their animation root
+pub fn sync_gltf_animation_target(
+    mut commands: Commands,
+    query: Query<(Entity, &GltfAnimationTargetPrefab), Changed<GltfAnimationTargetPrefab>>,
+    parents: Query<&Parent>,
+    roots: Query<(), With<GltfAnimationPrefab>>,
+) {
+    for (e, target) in query.iter() {
+        let root = std::iter::once(e)
+            .chain(parents.iter_ancestors(e))
+            .find(|e| roots.contains(*e));
+        let Some(root) = root else {
+            continue;
+        };
+        commands.entity(e).insert(AnimationTarget {
+            id: target.target_id(),
+            player: root,
+        });
+    }
+}
+
+/// Autoplay clips of already spawned prefabs on play mode start
+pub fn autoplay_gltf_animations(
+    prefabs: Query<(&GltfAnimationPrefab, &GltfAnimationGraph)>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    for (prefab, graph) in prefabs.iter() {
+        let Some(Ok(mut player)) = graph.player.map(|player| players.get_mut(player)) else {
+            continue;
+        };
+        player.stop_all();
+        autoplay_gltf_animation(prefab, graph, &mut player);
+    }
+}
+
+/// Stop all gltf animations on play mode exit
+pub fn stop_gltf_animations(
+    prefabs: Query<&GltfAnimationGraph>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    for graph in prefabs.iter() {
+        if let Some(Ok(mut player)) = graph.player.map(|player| players.get_mut(player)) {
+            player.stop_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<AnimationClip>()
+            .init_asset::<AnimationGraph>()
+            .add_systems(
+                Update,
+                (
+                    sync_gltf_animation,
+                    sync_gltf_animation_target,
+                    apply_deferred,
+                    attach_gltf_animation_graph,
+                )
+                    .chain(),
+            );
+        app
+    }
+
+    fn prefab() -> GltfAnimationPrefab {
+        GltfAnimationPrefab {
+            path: "models/character.gltf".to_string(),
+            clips: vec![
+                GltfAnimationClip {
+                    name: "Idle".to_string(),
+                    label: "Animation0".to_string(),
+                },
+                GltfAnimationClip {
+                    name: "Walk".to_string(),
+                    label: "Animation1".to_string(),
+                },
+            ],
+            autoplay: "Walk".to_string(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn unpacked_animation_builds_graph_and_autoplays() {
+        let mut app = app();
+        let bone = app
+            .world_mut()
+            .spawn(GltfAnimationTargetPrefab {
+                names: vec!["Armature".to_string(), "Bone".to_string()],
+            })
+            .id();
+        let root = app.world_mut().spawn(prefab()).add_child(bone).id();
+
+        app.update();
+        app.update();
+
+        let graph = app.world().get::<GltfAnimationGraph>(root).unwrap();
+        assert_eq!(graph.player, Some(root));
+        assert_eq!(
+            graph.clips["Walk"].1.path().unwrap().to_string(),
+            "models/character.gltf#Animation1"
+        );
+        assert!(app.world().get::<Handle<AnimationGraph>>(root).is_some());
+
+        let walk = graph.node("Walk").unwrap();
+        let player = app.world().get::<AnimationPlayer>(root).unwrap();
+        assert!(player.is_playing_animation(walk));
+
+        let target = app.world().get::<AnimationTarget>(bone).unwrap();
+        assert_eq!(target.player, root);
+        assert_eq!(
+            target.id,
+            AnimationTargetId::from_names([Name::new("Armature"), Name::new("Bone")].iter())
+        );
+    }
+
+    #[test]
+    fn gltf_prefab_animation_uses_scene_player() {
+        let mut app = app();
+        let root = app
+            .world_mut()
+            .spawn((
+                GltfPrefab {
+                    path: "models/character.gltf".to_string(),
+                    ..default()
+                },
+                GltfAnimationPrefab {
+                    path: String::new(),
+                    ..prefab()
+                },
+            ))
+            .id();
+
+        app.update();
+        let graph = app.world().get::<GltfAnimationGraph>(root).unwrap();
+        assert_eq!(graph.player, None);
+        assert!(app.world().get::<AnimationPlayer>(root).is_none());
+
+        // player is spawned with gltf scene
+        let player = app.world_mut().spawn(AnimationPlayer::default()).id();
+        app.world_mut().entity_mut(root).add_child(player);
+        app.update();
+
+        let graph = app.world().get::<GltfAnimationGraph>(root).unwrap();
+        assert_eq!(graph.player, Some(player));
+        assert_eq!(
+            graph.clips["Idle"].1.path().unwrap().to_string(),
+            "models/character.gltf#Animation0"
+        );
+        assert!(app.world().get::<Handle<AnimationGraph>>(player).is_some());
+    }
+}
@@ -96,6 +96,39 @@ impl MaterialPrefab {
             ..Default::default()
         }
     }
+
+    /// Create [`MaterialPrefab`] from [`StandardMaterial`]. Textures are stored by their asset paths
+    pub fn from_material(material: &StandardMaterial) -> Self {
+        Self {
+            base_color: material.base_color,
+            base_color_texture: texture_path(material.base_color_texture.as_ref()),
+            emissive: material.emissive.into(),
+            emissive_texture: texture_path(material.emissive_texture.as_ref()),
+            perceptual_roughness: material.perceptual_roughness,
+            metallic: material.metallic,
+            metallic_roughness_texture: texture_path(material.metallic_roughness_texture.as_ref()),
+            reflectance: material.reflectance,
+            normal_map_texture: texture_path(material.normal_map_texture.as_ref()),
+            flip_normal_map_y: material.flip_normal_map_y,
+            occlusion_texture: texture_path(material.occlusion_texture.as_ref()),
+            double_sided: material.double_sided,
+            unlit: material.unlit,
+            fog_enabled: material.fog_enabled,
+            alpha_mode: material.alpha_mode,
+            depth_bias: material.depth_bias,
+            depth_map: texture_path(material.depth_map.as_ref()),
+            parallax_depth_scale: material.parallax_depth_scale,
+            parallax_mapping_method: material.parallax_mapping_method,
+            max_parallax_layer_count: material.max_parallax_layer_count,
+        }
+    }
+}
+
+fn texture_path(handle: Option<&Handle<Image>>) -> String {
+    handle
+        .and_then(|handle| handle.path())
+        .map(ToString::to_string)
+        .unwrap_or_default()
 }
 
 /// Prefab component that store parameters and asset paths for creating [`StandardMaterial`]
@@ -251,4 +284,21 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn material_prefab_from_std_material() {
+        let material = StandardMaterial {
+            base_color: Color::srgb(1.0, 0.0, 0.0),
+            metallic: 0.7,
+            double_sided: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        };
+        let prefab = MaterialPrefab::from_material(&material);
+        assert_eq!(prefab.base_color, Color::srgb(1.0, 0.0, 0.0));
+        assert_eq!(prefab.metallic, 0.7);
+        assert!(prefab.double_sided);
+        assert_eq!(prefab.alpha_mode, AlphaMode::Blend);
+        assert_eq!(prefab.base_color_texture, String::new());
+    }
 }
@@ -0,0 +1,228 @@
+use bevy::{
+    asset::LoadState,
+    prelude::*,
+    render::{
+        mesh::{Indices, MeshVertexAttributeId, PrimitiveTopology},
+        primitives::Aabb,
+        render_resource::VertexFormat,
+    },
+};
+
+/// Mesh merged from several mesh assets, for example static gltf primitives which share material.
+/// Only paths and transforms of parts are saved, merged mesh is built after all parts are loaded
+#[derive(Component, Reflect, Clone, Default, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct MergedAssetMesh {
+    pub parts: Vec<MergedMeshPart>,
+}
+
+/// Mesh asset and its transform relative to entity with [`MergedAssetMesh`]
+#[derive(Reflect, Clone, Default, Debug, PartialEq)]
+#[reflect(Default)]
+pub struct MergedMeshPart {
+    pub path: String,
+    pub transform: Transform,
+}
+
+/// Topology and vertex attributes of mesh. Only meshes with the same layout can be merged
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MeshLayout {
+    topology: PrimitiveTopology,
+    attributes: Vec<(MeshVertexAttributeId, VertexFormat)>,
+}
+
+impl MeshLayout {
+    /// Layout of mesh, or `None` for strip topologies, which can not be merged
+    pub fn of(mesh: &Mesh) -> Option<Self> {
+        let topology = mesh.primitive_topology();
+        if matches!(
+            topology,
+            PrimitiveTopology::LineStrip | PrimitiveTopology::TriangleStrip
+        ) {
+            return None;
+        }
+        Some(Self {
+            topology,
+            attributes: mesh
+                .attributes()
+                .map(|(id, values)| (id, VertexFormat::from(values)))
+                .collect(),
+        })
+    }
+}
+
+/// Merge meshes with the same [`MeshLayout`] into one mesh. Each part is moved by its transform
+pub fn merge_meshes<'a>(
+    parts: impl IntoIterator<Item = (&'a Mesh, Transform)>,
+) -> Result<Mesh, String> {
+    let mut merged: Option<(Mesh, MeshLayout)> = None;
+    for (mesh, transform) in parts {
+        let layout = MeshLayout::of(mesh).ok_or("Strip meshes can not be merged")?;
+        let mut part = mesh.clone().transformed_by(transform);
+        // Merged mesh can have more vertices than u16 indices can address
+        let indices = match part.indices() {
+            Some(Indices::U16(indices)) => indices.iter().map(|idx| u32::from(*idx)).collect(),
+            Some(Indices::U32(indices)) => indices.clone(),
+            None => (0..part.count_vertices() as u32).collect(),
+        };
+        part.insert_indices(Indices::U32(indices));
+
+        match &mut merged {
+            None => merged = Some((part, layout)),
+            Some((mesh, merged_layout)) => {
+                if *merged_layout != layout {
+                    return Err("Meshes have different vertex attributes".to_string());
+                }
+                mesh.merge(&part);
+            }
+        }
+    }
+    merged
+        .map(|(mesh, _)| mesh)
+        .ok_or_else(|| "No meshes to merge".to_string())
+}
+
+/// Loading parts of [`MergedAssetMesh`]
+#[derive(Component)]
+pub struct MergedMeshParts(Vec<(Handle<Mesh>, Transform)>);
+
+pub fn sync_merged_asset_mesh(
+    mut commands: Commands,
+    changed: Query<(Entity, &MergedAssetMesh), Changed<MergedAssetMesh>>,
+    mut deleted: RemovedComponents<MergedAssetMesh>,
+    assets: Res<AssetServer>,
+) {
+    for (e, merged) in changed.iter() {
+        let parts = merged
+            .parts
+            .iter()
+            .map(|part| (assets.load::<Mesh>(&part.path), part.transform))
+            .collect();
+        commands.entity(e).insert(MergedMeshParts(parts));
+    }
+
+    for e in deleted.read() {
+        if let Some(mut cmd) = commands.get_entity(e) {
+            cmd.remove::<(Handle<Mesh>, MergedMeshParts)>();
+        }
+    }
+}
+
+/// Build merged mesh when all its parts are loaded
+pub fn build_merged_asset_mesh(
+    mut commands: Commands,
+    pending: Query<(Entity, &MergedMeshParts)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<AssetServer>,
+) {
+    for (e, parts) in pending.iter() {
+        if parts
+            .0
+            .iter()
+            .any(|(handle, _)| matches!(assets.get_load_state(handle), Some(LoadState::Failed(_))))
+        {
+            error!("Failed to load parts of merged mesh of {:?}", e);
+            commands.entity(e).remove::<MergedMeshParts>();
+            continue;
+        }
+        let Some(loaded) = parts
+            .0
+            .iter()
+            .map(|(handle, transform)| Some((meshes.get(handle)?, *transform)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let merged = merge_meshes(loaded);
+        let mut cmd = commands.entity(e);
+        cmd.remove::<MergedMeshParts>();
+        match merged {
+            Ok(mesh) => {
+                // Bounds are recalculated for new mesh
+                cmd.remove::<Aabb>().insert(meshes.add(mesh));
+            }
+            Err(err) => error!("Failed to merge mesh of {:?}: {}", e, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::render_asset::RenderAssetUsages;
+
+    use super::*;
+
+    fn triangle(indices: Indices) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        )
+        .with_inserted_indices(indices)
+    }
+
+    #[test]
+    fn merges_transformed_parts() {
+        let first = triangle(Indices::U16(vec![0, 1, 2]));
+        let second = triangle(Indices::U32(vec![0, 1, 2]));
+        let merged = merge_meshes([
+            (&first, Transform::IDENTITY),
+            (&second, Transform::from_xyz(0.0, 0.0, 5.0)),
+        ])
+        .unwrap();
+
+        assert_eq!(merged.count_vertices(), 6);
+        let Some(Indices::U32(indices)) = merged.indices() else {
+            panic!("Merged mesh must have u32 indices");
+        };
+        assert_eq!(indices, &vec![0, 1, 2, 3, 4, 5]);
+        let positions = merged
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+            .unwrap();
+        assert_eq!(positions[3], [0.0, 0.0, 5.0]);
+    }
+
+    #[test]
+    fn does_not_merge_different_layouts() {
+        let first = triangle(Indices::U16(vec![0, 1, 2]));
+        let second = triangle(Indices::U16(vec![0, 1, 2]))
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+        assert_ne!(MeshLayout::of(&first), MeshLayout::of(&second));
+        assert!(merge_meshes([
+            (&first, Transform::IDENTITY),
+            (&second, Transform::IDENTITY)
+        ])
+        .is_err());
+        assert!(merge_meshes([]).is_err());
+    }
+
+    #[test]
+    fn builds_mesh_when_parts_are_loaded() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .add_systems(Update, build_merged_asset_mesh);
+
+        let mut meshes = app.world_mut().resource_mut::<Assets<Mesh>>();
+        let part = meshes.add(triangle(Indices::U16(vec![0, 1, 2])));
+        let entity = app
+            .world_mut()
+            .spawn(MergedMeshParts(vec![
+                (part.clone(), Transform::IDENTITY),
+                (part, Transform::from_xyz(1.0, 0.0, 0.0)),
+            ]))
+            .id();
+        app.update();
+
+        let world = app.world();
+        assert!(world.get::<MergedMeshParts>(entity).is_none());
+        let handle = world.get::<Handle<Mesh>>(entity).unwrap();
+        let mesh = world.resource::<Assets<Mesh>>().get(handle).unwrap();
+        assert_eq!(mesh.count_vertices(), 6);
+    }
+}
@@ -14,6 +14,14 @@ pub use sprite::*;
 pub mod camera;
 pub use camera::*;
 
+/// Module contatins structures for playing gltf animations
+pub mod animation;
+pub use animation::*;
+
+/// Module contatins structures for merging several mesh assets into one mesh
+pub mod merged_mesh;
+pub use merged_mesh::*;
+
 /// Module contatins structures for determining player start
 pub mod player_start;
 pub use player_start::*;
@@ -166,6 +166,20 @@ impl EditorRegistry {
         T: Component + Reflect + FromReflect + Default + Send + 'static + GetTypeRegistration,
     >(
         &mut self,
+    ) {
+        self.silent_register_without_default::<T>();
+        self.spawn_components.insert(
+            T::get_type_registration().type_id(),
+            AddDefaultComponent::new::<T>(),
+        );
+    }
+
+    /// Register new component without meaningful default value, for example random id.
+    /// It is hidden in editor UI and saved in prefab, but can not be added from editor UI
+    pub fn silent_register_without_default<
+        T: Component + Reflect + FromReflect + Send + 'static + GetTypeRegistration,
+    >(
+        &mut self,
     ) {
         info!(
             "Silent registering component: {}",
@@ -174,10 +188,6 @@ impl EditorRegistry {
         self.registry
             .write()
             .add_registration(T::get_type_registration());
-        self.spawn_components.insert(
-            T::get_type_registration().type_id(),
-            AddDefaultComponent::new::<T>(),
-        );
         self.clone_components.push(CloneComponent::new::<T>());
         self.silent.insert(T::get_type_registration().type_id());
         self.remove_components.insert(
@@ -4,12 +4,20 @@
 
 /// Contains all component for prefab logic
 pub mod component;
+/// Receiving of scene edits from editor in separately launched game
+pub mod live_link;
 /// Contains systems for loading prefab from file
 pub mod load;
+/// Partial scene loading with diagnostics of not loaded components
+pub mod partial_load;
+/// Stable entity ids, which survive save/load and undo/redo
+pub mod persistent_id;
 /// Module contains all prefab plugin extensions
 pub mod plugins;
 /// Contains systems for saving prefab
 pub mod save;
+/// Scene changes in serializable form, which can be replayed in other world
+pub mod scene_change;
 /// Contains systems for spawning prefabs
 pub mod spawn_system;
 
@@ -31,9 +39,13 @@ pub mod ext {
 pub mod prelude {
     pub use crate::component::*;
     pub use crate::editor_registry::*;
+    pub use crate::live_link::*;
     pub use crate::load::PrefabBundle;
+    pub use crate::partial_load::*;
+    pub use crate::persistent_id::*;
     pub use crate::plugins::*;
     pub use crate::save::*;
+    pub use crate::scene_change::*;
     pub use crate::sub_scene::*;
     pub use crate::PrefabSet;
     pub use space_shared::PrefabMarker;
@@ -0,0 +1,167 @@
+use bevy::{prelude::*, utils::HashMap};
+use space_shared::{local_socket::LocalClient, PersistentId};
+
+use crate::scene_change::SceneChange;
+
+pub const DEFAULT_LIVE_LINK_PORT: u16 = 15711;
+/// Environment variable with live link session token of editor and game.
+/// Editor generates random token if it is not set
+pub const LIVE_LINK_TOKEN_ENV: &str = "SPACE_EDITOR_LIVE_LINK_TOKEN";
+
+/// Plugin for separately launched game, which receives scene edits from editor in real time
+///
+/// Changes are applied to entities with the same [`PersistentId`].
+/// Live link must be enabled in editor settings
+pub struct LiveLinkClientPlugin {
+    pub port: u16,
+    /// Session token of editor, which is sent as first line of connection.
+    /// Read from [`LIVE_LINK_TOKEN_ENV`] by default
+    pub token: Option<String>,
+}
+
+impl Default for LiveLinkClientPlugin {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_LIVE_LINK_PORT,
+            token: std::env::var(LIVE_LINK_TOKEN_ENV)
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+impl Plugin for LiveLinkClientPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        let client = match &self.token {
+            Some(token) => LocalClient::with_handshake(token.clone()),
+            None => {
+                warn!(
+                    "{} is not set, editor will not send scene edits to live link",
+                    LIVE_LINK_TOKEN_ENV
+                );
+                LocalClient::default()
+            }
+        };
+        app.insert_resource(LiveLinkClient {
+            port: self.port,
+            client,
+        });
+        app.add_systems(Update, receive_live_link);
+    }
+}
+
+/// Connection of game to editor live link. Client reconnects when editor is restarted
+#[derive(Resource)]
+pub struct LiveLinkClient {
+    pub port: u16,
+    client: LocalClient,
+}
+
+impl LiveLinkClient {
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+}
+
+fn receive_live_link(world: &mut World) {
+    let lines = {
+        let mut link = world.resource_mut::<LiveLinkClient>();
+        let port = link.port;
+        link.client.read_lines(port)
+    };
+    let Some(lines) = lines.filter(|lines| !lines.is_empty()) else {
+        return;
+    };
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut query = world.query::<(Entity, &PersistentId)>();
+    let mut entities = query
+        .iter(world)
+        .map(|(entity, id)| (id.0, entity))
+        .collect::<HashMap<_, _>>();
+
+    for line in lines {
+        let applied = ron::from_str::<SceneChange>(&line)
+            .map_err(|e| e.to_string())
+            .and_then(|change| change.apply(world, &mut entities, &registry));
+        if let Err(err) = applied {
+            warn!("Failed to apply live link change: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use space_shared::local_socket::LocalServer;
+    use space_undo::{reflect_patch::diff_reflect, ChangeRecord};
+
+    use super::*;
+
+    #[test]
+    fn applies_changes_from_editor() {
+        let mut server = LocalServer::default();
+        server.sync(Some(0));
+        let port = server.local_addr.unwrap().port();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .register_type::<Transform>()
+            .add_plugins(LiveLinkClientPlugin {
+                port,
+                token: Some("token".to_string()),
+            });
+        let entity = app
+            .world_mut()
+            .spawn((Transform::default(), PersistentId(7)))
+            .id();
+        app.update();
+        assert!(app.world().resource::<LiveLinkClient>().is_connected());
+
+        let mut lines = vec![];
+        for _ in 0..100 {
+            server.accept();
+            lines.extend(server.read_lines());
+            if !lines.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        // Token is the first line of connection
+        assert_eq!(lines, vec![(0, "token".to_string())]);
+        server.authenticate(0);
+        let record = ChangeRecord::ComponentPatch {
+            entity,
+            type_id: std::any::TypeId::of::<Transform>(),
+            patches: diff_reflect(
+                &Transform::default(),
+                &Transform::from_xyz(2.0, 0.0, 0.0),
+                10,
+            ),
+        };
+        let change = {
+            let registry = app.world().resource::<AppTypeRegistry>().read();
+            SceneChange::from_change(&record, &|_| Some(7), &registry).unwrap()
+        };
+        server.broadcast_authenticated(&ron::to_string(&change).unwrap());
+        server.broadcast_authenticated("AddedEntity(8)");
+        server.flush();
+
+        for _ in 0..100 {
+            app.update();
+            if app.world().get::<Transform>(entity).unwrap().translation.x == 2.0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            app.world().get::<Transform>(entity).unwrap().translation.x,
+            2.0
+        );
+        let mut query = app.world_mut().query::<&PersistentId>();
+        assert!(query.iter(app.world()).any(|id| id.0 == 8));
+    }
+}
@@ -0,0 +1,571 @@
+use bevy::{
+    ecs::{entity::EntityHashMap, reflect::ReflectResource},
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        ReflectFromReflect, TypePath, TypeRegistry,
+    },
+    scene::DynamicEntity,
+};
+use serde::{
+    de::{DeserializeSeed, IgnoredAny},
+    Deserialize,
+};
+
+/// Component data from scene file which could not be loaded to world.
+/// Written back to scene file on save
+#[derive(Reflect, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[reflect(Default)]
+pub struct OpaqueComponent {
+    /// Type path of component in scene file
+    pub type_path: String,
+    /// Component value as ron text
+    pub ron: String,
+}
+
+/// Components of entity which were not loaded from scene file
+#[derive(Component, Reflect, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct OpaqueComponents(pub Vec<OpaqueComponent>);
+
+/// Reason why component or resource was not loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadIssueKind {
+    /// Type is not registered in type registry
+    UnregisteredType,
+    /// Type is registered without `#[reflect(Component)]`
+    MissingReflectComponent,
+    /// Type is registered without `#[reflect(Resource)]`
+    MissingReflectResource,
+    /// Value does not match registered type
+    InvalidData(String),
+}
+
+impl std::fmt::Display for LoadIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnregisteredType => write!(f, "type is not registered"),
+            Self::MissingReflectComponent => write!(f, "type is not registered as component"),
+            Self::MissingReflectResource => write!(f, "type is not registered as resource"),
+            Self::InvalidData(err) => write!(f, "invalid data: {}", err),
+        }
+    }
+}
+
+/// Component or resource of scene which was not loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadIssue {
+    /// Entity of component, `None` for resources
+    pub entity: Option<Entity>,
+    pub type_path: String,
+    pub kind: LoadIssueKind,
+}
+
+/// Scene with everything which can be written to world
+#[derive(Default)]
+pub struct PartialScene {
+    pub scene: DynamicScene,
+    /// Components which were not loaded by scene entity
+    pub opaque: EntityHashMap<Vec<OpaqueComponent>>,
+    pub issues: Vec<LoadIssue>,
+}
+
+impl PartialScene {
+    fn add_issue(&mut self, entity: Option<Entity>, type_path: String, kind: LoadIssueKind) {
+        self.issues.push(LoadIssue {
+            entity,
+            type_path,
+            kind,
+        });
+    }
+}
+
+/// Parse scene file, components which can not be loaded are reported and kept as [`OpaqueComponent`].
+/// Fails only if text is not a scene
+pub fn parse_partial_scene(text: &str, registry: &TypeRegistry) -> Result<PartialScene, String> {
+    let raw = RawScene::parse(text)?;
+    let mut result = PartialScene::default();
+
+    for resource in raw.resources {
+        match load_value(&resource.type_path, &resource.ron, registry, true) {
+            Ok(value) => result.scene.resources.push(value),
+            Err(kind) => result.add_issue(None, resource.type_path, kind),
+        }
+    }
+
+    for raw_entity in raw.entities {
+        let entity = Entity::try_from_bits(raw_entity.bits)
+            .map_err(|_| format!("Invalid entity id {}", raw_entity.bits))?;
+        let mut components = vec![];
+        for component in raw_entity.components {
+            match load_value(&component.type_path, &component.ron, registry, false) {
+                Ok(value) => components.push(value),
+                Err(kind) => {
+                    result.add_issue(Some(entity), component.type_path.clone(), kind);
+                    result
+                        .opaque
+                        .entry(entity)
+                        .or_default()
+                        .push(OpaqueComponent {
+                            type_path: component.type_path,
+                            ron: component.ron,
+                        });
+                }
+            }
+        }
+        result
+            .scene
+            .entities
+            .push(DynamicEntity { entity, components });
+    }
+
+    Ok(result)
+}
+
+fn load_value(
+    type_path: &str,
+    ron: &str,
+    registry: &TypeRegistry,
+    resource: bool,
+) -> Result<Box<dyn Reflect>, LoadIssueKind> {
+    let registration = registry
+        .get_with_type_path(type_path)
+        .ok_or(LoadIssueKind::UnregisteredType)?;
+    if resource && registration.data::<ReflectResource>().is_none() {
+        return Err(LoadIssueKind::MissingReflectResource);
+    }
+    if !resource && registration.data::<ReflectComponent>().is_none() {
+        return Err(LoadIssueKind::MissingReflectComponent);
+    }
+
+    let mut deserializer = ron::Deserializer::from_str(ron)
+        .map_err(|err| LoadIssueKind::InvalidData(err.to_string()))?;
+    let value = TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(|err| LoadIssueKind::InvalidData(err.to_string()))?;
+
+    match registration.data::<ReflectFromReflect>() {
+        Some(from_reflect) => from_reflect.from_reflect(&*value).ok_or_else(|| {
+            LoadIssueKind::InvalidData(format!("failed to convert value to {}", type_path))
+        }),
+        None => Ok(value),
+    }
+}
+
+/// Copy of loaded scene without components which can not be written to world
+pub fn check_scene(scene: &DynamicScene, registry: &TypeRegistry) -> PartialScene {
+    let mut result = PartialScene::default();
+
+    for resource in scene.resources.iter() {
+        match check_value(&**resource, registry, true) {
+            Ok(()) => result.scene.resources.push(resource.clone_value()),
+            Err(kind) => result.add_issue(None, type_path_of(&**resource), kind),
+        }
+    }
+
+    for dyn_entity in scene.entities.iter() {
+        let mut components = vec![];
+        for component in dyn_entity.components.iter() {
+            match check_value(&**component, registry, false) {
+                Ok(()) => components.push(component.clone_value()),
+                Err(kind) => {
+                    let type_path = type_path_of(&**component);
+                    result.add_issue(Some(dyn_entity.entity), type_path.clone(), kind);
+                    if let Ok(ron) =
+                        ron::to_string(&TypedReflectSerializer::new(&**component, registry))
+                    {
+                        result
+                            .opaque
+                            .entry(dyn_entity.entity)
+                            .or_default()
+                            .push(OpaqueComponent { type_path, ron });
+                    }
+                }
+            }
+        }
+        result.scene.entities.push(DynamicEntity {
+            entity: dyn_entity.entity,
+            components,
+        });
+    }
+
+    result
+}
+
+fn type_path_of(value: &dyn Reflect) -> String {
+    value
+        .get_represented_type_info()
+        .map_or_else(|| value.reflect_type_path(), |info| info.type_path())
+        .to_string()
+}
+
+fn check_value(
+    value: &dyn Reflect,
+    registry: &TypeRegistry,
+    resource: bool,
+) -> Result<(), LoadIssueKind> {
+    let registration = value
+        .get_represented_type_info()
+        .and_then(|info| registry.get(info.type_id()))
+        .ok_or(LoadIssueKind::UnregisteredType)?;
+    if resource && registration.data::<ReflectResource>().is_none() {
+        return Err(LoadIssueKind::MissingReflectResource);
+    }
+    if !resource && registration.data::<ReflectComponent>().is_none() {
+        return Err(LoadIssueKind::MissingReflectComponent);
+    }
+    Ok(())
+}
+
+/// Replace serialized [`OpaqueComponents`] of saved scene with original components
+pub fn expand_opaque_components(text: &str) -> Result<String, String> {
+    let opaque_path = OpaqueComponents::type_path();
+    if !text.contains(opaque_path) {
+        return Ok(text.to_string());
+    }
+
+    let raw = RawScene::parse(text)?;
+    let mut replacements = vec![];
+    for component in raw.entities.iter().flat_map(|e| e.components.iter()) {
+        if component.type_path != opaque_path {
+            continue;
+        }
+        let opaque = ron::from_str::<OpaqueComponents>(&component.ron)
+            .map_err(|err| format!("Failed to read opaque components: {}", err))?;
+
+        let line_start = text[..component.span.start]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let indent = &text[line_start..component.span.start];
+        let separator = if indent.trim().is_empty() {
+            format!("\n{}", indent)
+        } else {
+            " ".to_string()
+        };
+
+        let mut entries = vec![];
+        for component in opaque.0 {
+            let key = ron::to_string(&component.type_path).map_err(|err| err.to_string())?;
+            entries.push(format!("{}: {}", key, component.ron));
+        }
+        let mut span = component.span.clone();
+        if entries.is_empty() {
+            // Remove line and comma of entry too
+            if indent.trim().is_empty() {
+                span.start = line_start.saturating_sub(1);
+            }
+            let rest = &text[span.end..];
+            if let Some(after_comma) = rest.trim_start().strip_prefix(',') {
+                span.end = text.len() - after_comma.len();
+            }
+        }
+        replacements.push((span, entries.join(&format!(",{}", separator))));
+    }
+
+    // Replace from the end to keep positions of previous entries
+    let mut result = text.to_string();
+    for (span, replacement) in replacements.into_iter().rev() {
+        result.replace_range(span, &replacement);
+    }
+    Ok(result)
+}
+
+/// Scene file with not parsed component values
+#[derive(Default)]
+struct RawScene {
+    resources: Vec<RawValue>,
+    entities: Vec<RawEntity>,
+}
+
+struct RawEntity {
+    bits: u64,
+    components: Vec<RawValue>,
+}
+
+struct RawValue {
+    type_path: String,
+    ron: String,
+    /// Position of entry from key to end of value
+    span: std::ops::Range<usize>,
+}
+
+impl RawScene {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut cursor = Cursor { text, pos: 0 };
+        let mut scene = Self::default();
+
+        cursor.skip_whitespace();
+        while cursor.rest().starts_with("#![") {
+            let end = cursor
+                .rest()
+                .find(']')
+                .ok_or_else(|| cursor.error("unclosed extension attribute"))?;
+            cursor.pos += end + 1;
+            cursor.skip_whitespace();
+        }
+
+        cursor.struct_fields(|cursor, field| match field {
+            "resources" => cursor.map_entries(|cursor, start, key| {
+                scene.resources.push(cursor.raw_value(start, key)?);
+                Ok(())
+            }),
+            "entities" => cursor.map_entries(|cursor, _, key| {
+                let bits =
+                    ron::from_str::<u64>(key).map_err(|err| cursor.error(&err.to_string()))?;
+                let mut entity = RawEntity {
+                    bits,
+                    components: vec![],
+                };
+                cursor.struct_fields(|cursor, field| match field {
+                    "components" => cursor.map_entries(|cursor, start, key| {
+                        entity.components.push(cursor.raw_value(start, key)?);
+                        Ok(())
+                    }),
+                    _ => cursor.value().map(|_| ()),
+                })?;
+                scene.entities.push(entity);
+                Ok(())
+            }),
+            _ => cursor.value().map(|_| ()),
+        })?;
+
+        Ok(scene)
+    }
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        format!("Scene file line {}: {}", line, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", c)))
+        }
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// Skip any ron value and return its text
+    fn value(&mut self) -> Result<&'a str, String> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let mut deserializer =
+            ron::Deserializer::from_str(rest).map_err(|err| self.error(&err.to_string()))?;
+        IgnoredAny::deserialize(&mut deserializer).map_err(|err| self.error(&err.to_string()))?;
+        let len = rest.len() - deserializer.remainder().len();
+        self.pos += len;
+        Ok(rest[..len].trim_end())
+    }
+
+    /// Value of type path key
+    fn raw_value(&mut self, start: usize, key: &str) -> Result<RawValue, String> {
+        let type_path = ron::from_str::<String>(key).map_err(|err| self.error(&err.to_string()))?;
+        let ron = self.value()?.to_string();
+        Ok(RawValue {
+            type_path,
+            ron,
+            span: start..self.pos,
+        })
+    }
+
+    /// Parse `Name(field: value, ...)`, name is optional
+    fn struct_fields(
+        &mut self,
+        mut field: impl FnMut(&mut Self, &'a str) -> Result<(), String>,
+    ) -> Result<(), String> {
+        self.identifier();
+        self.expect('(')?;
+        while !self.consume(')') {
+            let name = self
+                .identifier()
+                .ok_or_else(|| self.error("expected field name"))?;
+            self.expect(':')?;
+            field(self, name)?;
+            if !self.consume(',') {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse `{key: value, ...}`. Entry callback receives start position and text of key
+    fn map_entries(
+        &mut self,
+        mut entry: impl FnMut(&mut Self, usize, &'a str) -> Result<(), String>,
+    ) -> Result<(), String> {
+        self.expect('{')?;
+        loop {
+            if self.consume('}') {
+                break;
+            }
+            let start = self.pos;
+            let key = self.value()?;
+            self.expect(':')?;
+            entry(self, start, key)?;
+            if !self.consume(',') {
+                self.expect('}')?;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Health(f32);
+
+    #[derive(Reflect, Default)]
+    struct NotComponent;
+
+    const SCENE: &str = r#"(
+  resources: {},
+  entities: {
+    4294967296: (
+      components: {
+        "bevy_core::name::Name": "player",
+        "space_prefab::partial_load::tests::Health": (5.0),
+        "my_game::Inventory": (items: ["sword", "shield"]),
+        "space_prefab::partial_load::tests::NotComponent": (),
+      },
+    ),
+    4294967297: (
+      components: {
+        "space_prefab::partial_load::tests::Health": ("full"),
+      },
+    ),
+  },
+)"#;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Name>();
+        registry.register::<Health>();
+        registry.register::<NotComponent>();
+        registry.register::<OpaqueComponents>();
+        registry
+    }
+
+    #[test]
+    fn partial_scene_skips_broken_components() {
+        let registry = registry();
+        let scene = parse_partial_scene(SCENE, &registry).unwrap();
+
+        assert_eq!(scene.scene.entities.len(), 2);
+        assert_eq!(scene.scene.entities[0].components.len(), 2);
+        assert!(scene.scene.entities[1].components.is_empty());
+
+        let first = Entity::from_bits(4294967296);
+        let second = Entity::from_bits(4294967297);
+        let kinds = scene
+            .issues
+            .iter()
+            .map(|issue| (issue.entity, issue.type_path.as_str(), issue.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds[0],
+            (
+                Some(first),
+                "my_game::Inventory",
+                LoadIssueKind::UnregisteredType
+            )
+        );
+        assert_eq!(
+            kinds[1],
+            (
+                Some(first),
+                "space_prefab::partial_load::tests::NotComponent",
+                LoadIssueKind::MissingReflectComponent
+            )
+        );
+        assert_eq!(kinds[2].0, Some(second));
+        assert!(matches!(kinds[2].2, LoadIssueKind::InvalidData(_)));
+
+        assert_eq!(
+            scene.opaque[&first][0],
+            OpaqueComponent {
+                type_path: "my_game::Inventory".to_string(),
+                ron: r#"(items: ["sword", "shield"])"#.to_string(),
+            }
+        );
+        assert_eq!(scene.opaque[&second][0].ron, r#"("full")"#);
+    }
+
+    #[test]
+    fn not_scene_fails() {
+        assert!(parse_partial_scene("[1, 2, 3]", &registry()).is_err());
+    }
+
+    #[test]
+    fn opaque_components_are_written_back() {
+        let registry = registry();
+        let mut scene = parse_partial_scene(SCENE, &registry).unwrap();
+        let first = Entity::from_bits(4294967296);
+
+        // opaque data is saved as regular component
+        let opaque = OpaqueComponents(scene.opaque.remove(&first).unwrap());
+        scene.scene.entities[0].components = vec![Box::new(opaque.clone()) as Box<dyn Reflect>];
+        scene.scene.entities.truncate(1);
+        let saved = scene.scene.serialize(&registry).unwrap();
+        assert!(saved.contains(OpaqueComponents::type_path()));
+
+        let expanded = expand_opaque_components(&saved).unwrap();
+        assert!(!expanded.contains(OpaqueComponents::type_path()));
+        let reloaded = parse_partial_scene(&expanded, &registry).unwrap();
+        assert_eq!(reloaded.opaque[&first], opaque.0);
+    }
+}
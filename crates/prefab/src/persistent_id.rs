@@ -0,0 +1,139 @@
+use bevy::{prelude::*, utils::HashMap};
+use space_shared::{PersistentId, PrefabMarker};
+use space_undo::ChangeChain;
+
+use crate::editor_registry::EditorRegistry;
+
+/// Plugin which gives [`PersistentId`] to every prefab entity
+pub struct PersistentIdPlugin;
+
+impl Plugin for PersistentIdPlugin {
+    #[cfg(not(tarpaulin_include))]
+    fn build(&self, app: &mut App) {
+        // Random id has no default value, so it is registered without spawn command
+        if let Some(mut registry) = app.world_mut().get_resource_mut::<EditorRegistry>() {
+            registry.silent_register_without_default::<PersistentId>();
+        }
+        app.register_type::<PersistentId>();
+        app.init_resource::<PersistentIdMap>();
+        app.add_systems(Update, assign_persistent_ids);
+    }
+}
+
+/// Mapping between entities and their persistent ids.
+/// Despawned entities are kept, so changes of already deleted entities can still be resolved
+#[derive(Resource, Default)]
+pub struct PersistentIdMap {
+    by_id: HashMap<u64, Entity>,
+    by_entity: HashMap<Entity, u64>,
+}
+
+impl PersistentIdMap {
+    /// Last entity which had this persistent id
+    pub fn entity(&self, id: u64) -> Option<Entity> {
+        self.by_id.get(&id).copied()
+    }
+
+    /// Persistent id of entity (including already despawned entities)
+    pub fn id(&self, entity: Entity) -> Option<u64> {
+        self.by_entity.get(&entity).copied()
+    }
+
+    fn insert(&mut self, entity: Entity, id: u64) {
+        self.by_id.insert(id, entity);
+        self.by_entity.insert(entity, id);
+    }
+}
+
+fn assign_persistent_ids(
+    mut commands: Commands,
+    mut map: ResMut<PersistentIdMap>,
+    change_chain: Option<Res<ChangeChain>>,
+    changed: Query<(Entity, &PersistentId), Changed<PersistentId>>,
+    missing: Query<Entity, (With<PrefabMarker>, Without<PersistentId>)>,
+    ids: Query<&PersistentId>,
+) {
+    for (entity, id) in changed.iter() {
+        // Cloned entities copy id of source entity, so they need a new one
+        let duplicate = map.entity(id.0).is_some_and(|other| {
+            other != entity && ids.get(other).is_ok_and(|other_id| other_id == id)
+        });
+        if duplicate {
+            let new_id = PersistentId::new_random();
+            commands.entity(entity).insert(new_id);
+            map.insert(entity, new_id.0);
+        } else {
+            map.insert(entity, id.0);
+        }
+    }
+
+    for entity in missing.iter() {
+        // Entity recreated by undo gets id of original entity
+        let restored = change_chain.as_ref().and_then(|chain| {
+            chain
+                .entity_remap()
+                .iter()
+                .find(|(_, recreated)| **recreated == entity)
+                .and_then(|(original, _)| map.id(*original))
+        });
+        let id = restored.map_or_else(PersistentId::new_random, PersistentId);
+        commands.entity(entity).insert(id);
+        map.insert(entity, id.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::EditorRegistryPlugin;
+
+    fn configure_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EditorRegistryPlugin, PersistentIdPlugin));
+        app
+    }
+
+    #[test]
+    fn assigns_ids_to_prefab_entities() {
+        let mut app = configure_app();
+        let prefab = app.world_mut().spawn(PrefabMarker).id();
+        let other = app.world_mut().spawn_empty().id();
+        app.update();
+
+        let id = *app.world().get::<PersistentId>(prefab).unwrap();
+        assert!(app.world().get::<PersistentId>(other).is_none());
+        assert_eq!(
+            app.world().resource::<PersistentIdMap>().entity(id.0),
+            Some(prefab)
+        );
+    }
+
+    #[test]
+    fn reassigns_duplicated_ids() {
+        let mut app = configure_app();
+        let source = app.world_mut().spawn((PrefabMarker, PersistentId(42))).id();
+        app.update();
+
+        let clone = app.world_mut().spawn((PrefabMarker, PersistentId(42))).id();
+        app.update();
+
+        assert_eq!(app.world().get::<PersistentId>(source).unwrap().0, 42);
+        assert_ne!(app.world().get::<PersistentId>(clone).unwrap().0, 42);
+    }
+
+    #[test]
+    fn keeps_id_of_reloaded_entity() {
+        let mut app = configure_app();
+        let old = app.world_mut().spawn((PrefabMarker, PersistentId(7))).id();
+        app.update();
+
+        app.world_mut().despawn(old);
+        let new = app.world_mut().spawn((PrefabMarker, PersistentId(7))).id();
+        app.update();
+
+        assert_eq!(app.world().get::<PersistentId>(new).unwrap().0, 7);
+        let map = app.world().resource::<PersistentIdMap>();
+        assert_eq!(map.entity(7), Some(new));
+        assert_eq!(map.id(old), Some(7));
+    }
+}
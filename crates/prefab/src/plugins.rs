@@ -13,11 +13,11 @@ use bevy::{
 };
 use bevy_scene_hook::HookPlugin;
 use space_shared::toast::ToastMessage;
-use space_shared::{LightAreaToggle, PrefabMarker};
+use space_shared::{LightAreaToggle, PrefabMarker, SelectionSets};
 
 use crate::{
-    component, editor_registry::EditorRegistryExt, load, prelude::EditorRegistryPlugin, save,
-    spawn_system, EditorState, PrefabSet,
+    component, editor_registry::EditorRegistryExt, load, partial_load,
+    prelude::EditorRegistryPlugin, save, spawn_system, EditorState, PrefabSet,
 };
 
 use component::*;
@@ -73,8 +73,14 @@ impl Plugin for BasePrefabPlugin {
         app.editor_registry::<Transform>();
         app.editor_registry::<Name>();
         app.editor_registry::<Visibility>();
+        app.editor_silent_registry::<SelectionSets>();
+        app.register_type::<partial_load::OpaqueComponent>();
+        app.editor_silent_registry::<partial_load::OpaqueComponents>();
 
         app.editor_registry::<GltfPrefab>();
+        app.editor_registry::<GltfAnimationPrefab>();
+        app.editor_silent_registry::<GltfAnimationTargetPrefab>();
+        app.register_type::<GltfAnimationClip>();
         app.editor_registry::<MaterialPrefab>();
         app.editor_registry::<ColorMaterialPrefab>();
 
@@ -126,6 +132,15 @@ impl Plugin for BasePrefabPlugin {
             sync_asset_mesh.in_set(PrefabSet::DetectPrefabChange),
         );
 
+        app.editor_registry::<MergedAssetMesh>();
+        app.register_type::<MergedMeshPart>();
+        app.add_systems(
+            Update,
+            (sync_merged_asset_mesh, build_merged_asset_mesh)
+                .chain()
+                .in_set(PrefabSet::DetectPrefabChange),
+        );
+
         app.editor_registry::<AssetMaterial>();
         app.add_systems(
             Update,
@@ -236,9 +251,23 @@ impl Plugin for BasePrefabPlugin {
         );
         app.add_systems(Update, animate_sprite);
 
+        app.add_systems(
+            Update,
+            (
+                sync_gltf_animation,
+                sync_gltf_animation_target,
+                attach_gltf_animation_graph,
+            )
+                .chain()
+                .in_set(PrefabSet::DetectPrefabChange),
+        );
+        app.add_systems(OnEnter(EditorState::Game), autoplay_gltf_animations);
+        app.add_systems(OnExit(EditorState::Game), stop_gltf_animations);
+
         app.add_plugins(SavePrefabPlugin);
         app.add_plugins(LoadPlugin);
         app.add_plugins(crate::sub_scene::SceneUnpackPlugin);
+        app.add_plugins(crate::persistent_id::PersistentIdPlugin);
     }
 }
 
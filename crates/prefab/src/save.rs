@@ -1,13 +1,15 @@
 use bevy::{
     ecs::{entity::MapEntities, reflect::ReflectMapEntities},
     prelude::*,
-    tasks::IoTaskPool,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
     utils::HashSet,
 };
+#[cfg(feature = "editor")]
+use space_shared::toast::ToastMessage;
 use space_shared::{EditorPrefabPath, PrefabMarker, PrefabMemoryCache};
 use std::{any::TypeId, fs, io::Write};
 
-use crate::prelude::{EditorRegistry, EditorRegistryExt, SceneAutoChild};
+use crate::prelude::{expand_opaque_components, EditorRegistry, EditorRegistryExt, SceneAutoChild};
 
 #[derive(Reflect, Default, Component, Clone)]
 #[reflect(Component, MapEntities)]
@@ -38,7 +40,11 @@ impl Plugin for SaveResourcesPrefabPlugin {
     fn build(&self, app: &mut App) {
         app.editor_registry::<ChildrenPrefab>();
 
-        app.init_resource::<SaveConfig>().init_state::<SaveState>();
+        app.init_resource::<SaveConfig>()
+            .init_resource::<SaveOverride>()
+            .init_resource::<SceneWriteTasks>()
+            .add_event::<SceneWriteFailed>()
+            .init_state::<SaveState>();
     }
 }
 
@@ -59,6 +65,8 @@ impl Plugin for SavePrefabPlugin {
             )
                 .chain(),
         );
+        app.add_systems(OnExit(SaveState::Save), clear_save_override);
+        app.add_systems(Update, poll_scene_writes);
     }
 }
 
@@ -69,6 +77,35 @@ pub struct SaveConfig {
     pub path: Option<EditorPrefabPath>,
 }
 
+/// One-shot save targets for next save, which do not change [`SaveConfig::path`].
+/// Reset when save is finished
+#[derive(Resource, Clone, Default, Debug)]
+pub struct SaveOverride {
+    /// Files which receive a copy of saved scene
+    pub files: Vec<String>,
+    /// Do not write scene to [`SaveConfig::path`]
+    pub skip_config_path: bool,
+}
+
+/// Scene files which are written in background
+#[derive(Resource, Default)]
+pub struct SceneWriteTasks {
+    tasks: Vec<Task<Result<String, SceneWriteFailed>>>,
+}
+
+impl SceneWriteTasks {
+    pub const fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+/// Scene file could not be written. Previous content of the file is kept
+#[derive(Event, Clone, Debug)]
+pub struct SceneWriteFailed {
+    pub path: String,
+    pub error: String,
+}
+
 /// State system using to enable slow logic of saving
 #[cfg(not(tarpaulin_include))]
 #[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
@@ -89,6 +126,72 @@ fn prepare_children(
     }
 }
 
+fn clear_save_override(mut save_override: ResMut<SaveOverride>) {
+    *save_override = SaveOverride::default();
+}
+
+fn write_scene_file(world: &mut World, path: String, data: String) {
+    let task = IoTaskPool::get().spawn(async move {
+        match write_file_replacing(&path, data.as_bytes()) {
+            Ok(()) => Ok(path),
+            Err(err) => Err(SceneWriteFailed {
+                path,
+                error: err.to_string(),
+            }),
+        }
+    });
+    world
+        .get_resource_or_insert_with(SceneWriteTasks::default)
+        .tasks
+        .push(task);
+}
+
+/// Write data to temporary file and rename it to `path`,
+/// so failed write never leaves truncated file behind
+fn write_file_replacing(path: &str, data: &[u8]) -> std::io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
+}
+
+fn poll_scene_writes(
+    mut tasks: ResMut<SceneWriteTasks>,
+    mut failed: EventWriter<SceneWriteFailed>,
+    #[cfg(feature = "editor")] mut toast: EventWriter<ToastMessage>,
+) {
+    tasks.tasks.retain_mut(|task| {
+        let Some(result) = block_on(future::poll_once(task)) else {
+            return true;
+        };
+        match result {
+            Ok(path) => info!("Saved prefab to file {}", path),
+            Err(err) => {
+                error!(
+                    "Error while writing scene to file {}: {}",
+                    err.path, err.error
+                );
+                #[cfg(feature = "editor")]
+                toast.send(
+                    ToastMessage::new(
+                        &format!("Failed to save scene to {}", err.path),
+                        space_shared::toast::ToastKind::Error,
+                    )
+                    .with_details(err.error.clone()),
+                );
+                failed.send(err);
+            }
+        }
+        false
+    });
+}
+
 fn delete_prepared_children(mut commands: Commands, query: Query<Entity, With<ChildrenPrefab>>) {
     for entity in query.iter() {
         commands.entity(entity).remove::<ChildrenPrefab>();
@@ -157,26 +260,33 @@ pub fn serialize_scene(world: &mut World) {
 
     let res = scene.serialize(&app_registry.read());
 
-    if let Ok(str) = res {
+    if let Ok(mut str) = res {
+        // Components which were not loaded are written back to scene file as they were
+        match expand_opaque_components(&str) {
+            Ok(expanded) => str = expanded,
+            Err(err) => {
+                #[cfg(feature = "editor")]
+                world.send_event(space_shared::toast::ToastMessage::new(
+                    &format!("Failed to write not loaded components: {}", err),
+                    space_shared::toast::ToastKind::Error,
+                ));
+                error!("Failed to write not loaded components: {}", err);
+            }
+        }
+        let save_override = world
+            .get_resource::<SaveOverride>()
+            .cloned()
+            .unwrap_or_default();
+        for file in save_override.files {
+            write_scene_file(world, file, str.clone());
+        }
+
         // Write the scene RON data to file
-        let path = config.path;
+        let path = config.path.filter(|_| !save_override.skip_config_path);
         if let Some(path) = path {
             match path {
                 EditorPrefabPath::File(path) => {
-                    IoTaskPool::get()
-                        .spawn(async move {
-                            fs::OpenOptions::new()
-                                .create(true)
-                                .truncate(true)
-                                .append(false)
-                                .write(true)
-                                .open(&path)
-                                .and_then(|mut file| file.write(str.as_bytes()))
-                                .inspect_err(|e| error!("Error while writing scene to file: {e}"))
-                                .expect("Error while writing scene to file");
-                            info!("Saved prefab to file {}", path);
-                        })
-                        .detach();
+                    write_scene_file(world, path, str);
                 }
                 EditorPrefabPath::MemoryCache => {
                     let handle = world
@@ -290,6 +400,41 @@ mod tests {
             .is_some());
     }
 
+    #[test]
+    fn save_override_skips_config_path() {
+        let save_config = SaveConfig {
+            path: Some(EditorPrefabPath::MemoryCache),
+        };
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+            bevy::scene::ScenePlugin,
+            EditorRegistryPlugin {},
+            SaveResourcesPrefabPlugin {},
+        ))
+        .insert_resource(save_config)
+        .insert_resource(SaveOverride {
+            files: vec![],
+            skip_config_path: true,
+        })
+        .init_resource::<PrefabMemoryCache>()
+        .editor_registry::<PrefabMarker>()
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(PrefabMarker);
+        });
+
+        app.update();
+
+        serialize_scene(&mut app.world_mut());
+        assert!(app
+            .world_mut()
+            .resource_mut::<PrefabMemoryCache>()
+            .scene
+            .is_none());
+    }
+
     #[test]
     fn inserts_prepared_children_component() {
         let mut app = App::new();
@@ -396,4 +541,44 @@ mod tests {
             .query_filtered::<Entity, With<ChildrenPrefab>>();
         assert_eq!(query.iter(&app.world_mut()).count(), 1);
     }
+
+    #[test]
+    fn failed_scene_write_keeps_file_and_sends_event() {
+        let dir = std::env::temp_dir().join("space_editor_failed_scene_write");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("level.scn.ron").to_str().unwrap().to_string();
+        write_file_replacing(&file, b"old").unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<SceneWriteTasks>()
+            .add_event::<SceneWriteFailed>()
+            .add_event::<space_shared::toast::ToastMessage>()
+            .add_systems(Update, poll_scene_writes);
+        // Directory can not be written as file
+        let missing = dir.join("missing").join("level.scn.ron");
+        let missing = missing.to_str().unwrap().to_string();
+        write_scene_file(app.world_mut(), missing.clone(), "new".to_string());
+        write_scene_file(app.world_mut(), file.clone(), "new".to_string());
+
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<SceneWriteTasks>().is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        let events = app.world().resource::<Events<SceneWriteFailed>>();
+        let failed = events
+            .get_reader()
+            .read(events)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, missing);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert!(!std::path::Path::new(&format!("{}.tmp", missing)).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
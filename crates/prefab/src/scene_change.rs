@@ -0,0 +1,411 @@
+use std::cell::RefCell;
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{ReflectDeserializer, ReflectSerializer},
+        TypeRegistry,
+    },
+    utils::HashMap,
+};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+use space_shared::{PersistentId, PrefabMarker};
+use space_undo::{
+    apply_for_every_typed_field,
+    reflect_patch::{apply_patches, ReflectPatch},
+    ChangeRecord, OneFrameUndoIgnore,
+};
+
+const MAX_REFLECT_RECURSION: i32 = 10;
+
+/// Change of scene, which can be written to file or sent to other process.
+/// Entities are referenced by [`PersistentId`]
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SceneChange {
+    AddedEntity(u64),
+    RemovedEntity(u64),
+    ComponentPatch {
+        entity: u64,
+        component: String,
+        fields: Vec<SceneChangeField>,
+    },
+    AddedComponent {
+        entity: u64,
+        value: SceneChangeValue,
+    },
+    RemovedComponent {
+        entity: u64,
+        component: String,
+    },
+    Many(Vec<Self>),
+    /// Change which can't be replayed, with its description
+    Unrecorded(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SceneChangeField {
+    pub path: String,
+    pub value: SceneChangeValue,
+}
+
+/// Reflected value in RON format.
+/// Entities inside value are stored as indices in `entities` list of persistent ids
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SceneChangeValue {
+    pub ron: String,
+    pub entities: Vec<u64>,
+}
+
+impl SceneChangeValue {
+    fn new(
+        value: &dyn Reflect,
+        id_of: &dyn Fn(Entity) -> Option<u64>,
+        registry: &TypeRegistry,
+    ) -> Result<Self, String> {
+        let mut value = value.clone_value();
+        let entities = RefCell::new(Vec::<u64>::new());
+        let unknown = RefCell::new(None);
+        apply_for_every_typed_field::<Entity>(
+            value.as_mut(),
+            &|entity| {
+                let Some(id) = id_of(*entity) else {
+                    *unknown.borrow_mut() = Some(*entity);
+                    return;
+                };
+                let mut entities = entities.borrow_mut();
+                let index = entities.iter().position(|e| *e == id).unwrap_or_else(|| {
+                    entities.push(id);
+                    entities.len() - 1
+                });
+                *entity = Entity::from_raw(index as u32);
+            },
+            MAX_REFLECT_RECURSION,
+        );
+        if let Some(entity) = unknown.into_inner() {
+            return Err(format!("Entity {:?} has no persistent id", entity));
+        }
+
+        let ron = ron::to_string(&ReflectSerializer::new(value.as_ref(), registry))
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            ron,
+            entities: entities.into_inner(),
+        })
+    }
+
+    fn load(
+        &self,
+        entity_of: &dyn Fn(u64) -> Option<Entity>,
+        registry: &TypeRegistry,
+    ) -> Result<Box<dyn Reflect>, String> {
+        let mut deserializer = ron::Deserializer::from_str(&self.ron).map_err(|e| e.to_string())?;
+        let mut value = ReflectDeserializer::new(registry)
+            .deserialize(&mut deserializer)
+            .map_err(|e| e.to_string())?;
+
+        let entities = self
+            .entities
+            .iter()
+            .map(|id| entity_of(*id))
+            .collect::<Vec<_>>();
+        apply_for_every_typed_field::<Entity>(
+            value.as_mut(),
+            &|entity| {
+                *entity = entities
+                    .get(entity.index() as usize)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(Entity::PLACEHOLDER);
+            },
+            MAX_REFLECT_RECURSION,
+        );
+        Ok(value)
+    }
+}
+
+impl SceneChange {
+    /// Convert undo change record to scene change.
+    /// `id_of` must return persistent id of entity (also for already despawned entities)
+    pub fn from_change(
+        record: &ChangeRecord,
+        id_of: &dyn Fn(Entity) -> Option<u64>,
+        registry: &TypeRegistry,
+    ) -> Result<Self, String> {
+        let persistent_id = |entity: Entity| {
+            id_of(entity).ok_or_else(|| format!("Entity {:?} has no persistent id", entity))
+        };
+        let type_path = |type_id: std::any::TypeId| {
+            registry
+                .get(type_id)
+                .map(|registration| registration.type_info().type_path().to_string())
+                .ok_or_else(|| "Component type is not registered".to_string())
+        };
+
+        Ok(match record {
+            ChangeRecord::AddedEntity(entity) => Self::AddedEntity(persistent_id(*entity)?),
+            ChangeRecord::RemovedEntity(entity) => Self::RemovedEntity(persistent_id(*entity)?),
+            ChangeRecord::ComponentPatch {
+                entity,
+                type_id,
+                patches,
+            } => Self::ComponentPatch {
+                entity: persistent_id(*entity)?,
+                component: type_path(*type_id)?,
+                fields: patches
+                    .iter()
+                    .map(|patch| {
+                        Ok(SceneChangeField {
+                            path: patch.path.clone(),
+                            value: SceneChangeValue::new(
+                                patch.new_value.as_ref(),
+                                id_of,
+                                registry,
+                            )?,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+            },
+            ChangeRecord::AddedComponent { entity, value } => Self::AddedComponent {
+                entity: persistent_id(*entity)?,
+                value: SceneChangeValue::new(value.as_ref(), id_of, registry)?,
+            },
+            ChangeRecord::RemovedComponent { entity, type_id } => Self::RemovedComponent {
+                entity: persistent_id(*entity)?,
+                component: type_path(*type_id)?,
+            },
+            ChangeRecord::Many(records) => Self::Many(
+                records
+                    .iter()
+                    .map(|record| Self::from_change(record, id_of, registry))
+                    .collect::<Result<_, String>>()?,
+            ),
+            ChangeRecord::Unrecorded(text) => Self::Unrecorded(text.clone()),
+        })
+    }
+
+    /// Description of the first change which can't be replayed
+    pub fn unrecorded(&self) -> Option<&str> {
+        match self {
+            Self::Unrecorded(text) => Some(text),
+            Self::Many(changes) => changes.iter().find_map(Self::unrecorded),
+            _ => None,
+        }
+    }
+
+    /// Apply record to world. Changed entities are excluded from auto undo for some frames
+    pub fn apply(
+        &self,
+        world: &mut World,
+        entities: &mut HashMap<u64, Entity>,
+        registry: &TypeRegistry,
+    ) -> Result<(), String> {
+        let find_entity = |entities: &HashMap<u64, Entity>, id: u64| {
+            entities
+                .get(&id)
+                .copied()
+                .ok_or_else(|| format!("Entity with persistent id {} not found", id))
+        };
+        let component_data = |component: &str| {
+            registry
+                .get_with_type_path(component)
+                .and_then(|registration| registration.data::<ReflectComponent>())
+                .ok_or_else(|| format!("Component {} is not registered", component))
+        };
+
+        match self {
+            Self::AddedEntity(id) => {
+                ensure_entity(world, entities, *id);
+            }
+            Self::RemovedEntity(id) => {
+                if let Some(entity) = entities.remove(id) {
+                    if let Some(entity) = world.get_entity_mut(entity) {
+                        entity.despawn_recursive();
+                    }
+                }
+            }
+            Self::ComponentPatch {
+                entity,
+                component,
+                fields,
+            } => {
+                let entity = find_entity(entities, *entity)?;
+                let reflect_component = component_data(component)?;
+                let patches = fields
+                    .iter()
+                    .map(|field| {
+                        let value = field
+                            .value
+                            .load(&|id| entities.get(&id).copied(), registry)?;
+                        Ok(ReflectPatch {
+                            path: field.path.clone(),
+                            old_value: value.clone_value(),
+                            new_value: value,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+
+                let mut entity_mut = world
+                    .get_entity_mut(entity)
+                    .ok_or_else(|| format!("Entity {:?} not found", entity))?;
+                let mut target = reflect_component
+                    .reflect_mut(&mut entity_mut)
+                    .ok_or_else(|| format!("Component {} not found", component))?;
+                apply_patches(target.as_reflect_mut(), &patches, false)?;
+                entity_mut.insert(OneFrameUndoIgnore::default());
+            }
+            Self::AddedComponent { entity, value } => {
+                let entity = ensure_entity(world, entities, *entity);
+                let value = value.load(&|id| entities.get(&id).copied(), registry)?;
+                let reflect_component = value
+                    .get_represented_type_info()
+                    .and_then(|info| registry.get(info.type_id()))
+                    .and_then(|registration| registration.data::<ReflectComponent>())
+                    .ok_or_else(|| {
+                        format!("Component {} is not registered", value.reflect_type_path())
+                    })?;
+                let mut entity_mut = world.entity_mut(entity);
+                reflect_component.insert(&mut entity_mut, value.as_ref(), registry);
+                entity_mut.insert(OneFrameUndoIgnore::default());
+            }
+            Self::RemovedComponent { entity, component } => {
+                // Components of removed entities are removed together with entity
+                let Some(entity) = entities.get(entity).copied() else {
+                    return Ok(());
+                };
+                let reflect_component = component_data(component)?;
+                if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                    reflect_component.remove(&mut entity_mut);
+                    entity_mut.insert(OneFrameUndoIgnore::default());
+                }
+            }
+            Self::Unrecorded(text) => {
+                return Err(format!("Change `{}` can't be replayed", text));
+            }
+            Self::Many(records) => {
+                for record in records {
+                    if let Err(err) = record.apply(world, entities, registry) {
+                        warn!("Failed to apply scene change: {}", err);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn ensure_entity(world: &mut World, entities: &mut HashMap<u64, Entity>, id: u64) -> Entity {
+    if let Some(entity) = entities.get(&id) {
+        if world.get_entity(*entity).is_some() {
+            return *entity;
+        }
+    }
+    let entity = world
+        .spawn((
+            PrefabMarker,
+            PersistentId(id),
+            OneFrameUndoIgnore::default(),
+        ))
+        .id();
+    entities.insert(id, entity);
+    entity
+}
+
+#[cfg(test)]
+mod tests {
+    use space_undo::reflect_patch::diff_reflect;
+
+    use super::*;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Transform>();
+        registry.register::<Parent>();
+        registry.register::<PersistentId>();
+        registry.register::<Entity>();
+        registry
+    }
+
+    #[test]
+    fn replays_component_patch() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn((Transform::default(), PersistentId(5))).id();
+
+        let new_value = Transform::from_xyz(1.0, 2.0, 3.0);
+        let record = ChangeRecord::ComponentPatch {
+            entity,
+            type_id: std::any::TypeId::of::<Transform>(),
+            patches: diff_reflect(&Transform::default(), &new_value, 10),
+        };
+        let change =
+            SceneChange::from_change(&record, &|e| (e == entity).then_some(5), &registry).unwrap();
+        let line = ron::to_string(&change).unwrap();
+        let change = ron::from_str::<SceneChange>(&line).unwrap();
+
+        let mut entities = HashMap::from_iter([(5, entity)]);
+        change.apply(&mut world, &mut entities, &registry).unwrap();
+        assert_eq!(*world.get::<Transform>(entity).unwrap(), new_value);
+    }
+
+    #[test]
+    fn replays_added_entity_with_entity_references() {
+        let registry = registry();
+        let mut world = World::new();
+        let parent = world.spawn(PersistentId(1)).id();
+        let child = world.spawn_empty().id();
+
+        let record = ChangeRecord::Many(vec![
+            ChangeRecord::AddedEntity(child),
+            ChangeRecord::ComponentPatch {
+                entity: child,
+                type_id: std::any::TypeId::of::<Transform>(),
+                patches: vec![],
+            },
+        ]);
+        let id_of = |e: Entity| {
+            if e == parent {
+                Some(1)
+            } else if e == child {
+                Some(2)
+            } else {
+                None
+            }
+        };
+        let change = SceneChange::from_change(&record, &id_of, &registry).unwrap();
+        let value = SceneChangeValue::new(&parent, &id_of, &registry).unwrap();
+        assert_eq!(value.entities, vec![1]);
+
+        let mut entities = HashMap::from_iter([(1, parent)]);
+        // Patch of missing component is reported, but entity is still created
+        change.apply(&mut world, &mut entities, &registry).unwrap();
+        let restored = entities[&2];
+        assert_eq!(world.get::<PersistentId>(restored), Some(&PersistentId(2)));
+        assert!(world.get::<PrefabMarker>(restored).is_some());
+
+        let loaded = value
+            .load(&|id| entities.get(&id).copied(), &registry)
+            .unwrap();
+        assert_eq!(loaded.downcast_ref::<Entity>(), Some(&parent));
+    }
+
+    #[test]
+    fn unrecorded_change_is_not_replayed() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+
+        let record = ChangeRecord::Many(vec![
+            ChangeRecord::AddedEntity(entity),
+            ChangeRecord::Unrecorded("ComponentChange".to_string()),
+        ]);
+        let change = SceneChange::from_change(&record, &|_| Some(3), &registry).unwrap();
+        let line = ron::to_string(&change).unwrap();
+        let change = ron::from_str::<SceneChange>(&line).unwrap();
+        assert_eq!(change.unrecorded(), Some("ComponentChange"));
+
+        let unrecorded = SceneChange::Unrecorded("ComponentChange".to_string());
+        assert!(unrecorded
+            .apply(&mut world, &mut HashMap::new(), &registry)
+            .is_err());
+    }
+}
@@ -235,10 +235,7 @@ pub fn spawn_player_start(
     for (e, prefab) in query.iter() {
         let msg = format!("Spawning player start: {:?} with \"{}\"", e, &prefab.prefab);
         #[cfg(feature = "editor")]
-        toast.send(ToastMessage::new(
-            &msg,
-            space_shared::toast::ToastKind::Info,
-        ));
+        toast.send(ToastMessage::new(&msg, space_shared::toast::ToastKind::Info).with_entity(e));
         info!(msg);
         let child = commands
             .spawn(DynamicSceneBundle {
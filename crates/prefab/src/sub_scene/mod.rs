@@ -153,30 +153,51 @@ fn decompress_scene(
         let scene_deserializer = SceneDeserializer {
             type_registry: &type_registry.read(),
         };
-        let Ok(mut deserializer) = ron::de::Deserializer::from_str(root.0.as_str()) else {
-            #[cfg(feature = "editor")]
-            toast.send(ToastMessage::new(
-                "Failed create Deserializer for sub scene",
-                space_shared::toast::ToastKind::Error,
-            ));
-            continue;
+        let mut deserializer = match ron::de::Deserializer::from_str(root.0.as_str()) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Failed create Deserializer for sub scene: {err}");
+                #[cfg(feature = "editor")]
+                toast.send(
+                    ToastMessage::new(
+                        &format!("Failed create Deserializer for sub scene: {err}"),
+                        space_shared::toast::ToastKind::Error,
+                    )
+                    .with_entity(root_entity),
+                );
+                continue;
+            }
         };
-        let Ok(dyn_scene) = scene_deserializer.deserialize(&mut deserializer) else {
-            #[cfg(feature = "editor")]
-            toast.send(ToastMessage::new(
-                "Failed to deserialize sub scene",
-                space_shared::toast::ToastKind::Error,
-            ));
-            continue;
+        let dyn_scene = match scene_deserializer.deserialize(&mut deserializer) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Failed to deserialize sub scene: {err}");
+                #[cfg(feature = "editor")]
+                toast.send(
+                    ToastMessage::new(
+                        &format!("Failed to deserialize sub scene: {err}"),
+                        space_shared::toast::ToastKind::Error,
+                    )
+                    .with_entity(root_entity),
+                );
+                continue;
+            }
         };
 
-        let Ok(scene) = Scene::from_dynamic_scene(&dyn_scene, &type_registry) else {
-            #[cfg(feature = "editor")]
-            toast.send(ToastMessage::new(
-                "Decompress scene does not exist",
-                space_shared::toast::ToastKind::Error,
-            ));
-            continue;
+        let scene = match Scene::from_dynamic_scene(&dyn_scene, &type_registry) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Decompress scene does not exist: {err}");
+                #[cfg(feature = "editor")]
+                toast.send(
+                    ToastMessage::new(
+                        &format!("Decompress scene does not exist: {err}"),
+                        space_shared::toast::ToastKind::Error,
+                    )
+                    .with_entity(root_entity),
+                );
+                continue;
+            }
         };
 
         commands
@@ -320,6 +341,6 @@ mod tests {
 
         let mut iter = events.get_reader();
         let iter = iter.read(events);
-        iter.for_each(|e| assert_eq!(e.text, "Failed to deserialize sub scene"));
+        iter.for_each(|e| assert!(e.text.starts_with("Failed to deserialize sub scene: ")));
     }
 }
@@ -7,20 +7,63 @@ pub mod ext {
 
 pub mod prelude {
     pub use crate::{
-        EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorSet, EditorState, PrefabMarker,
-        PrefabMemoryCache, SelectParent,
+        EditorCameraMarker, EditorEvent, EditorPrefabPath, EditorSet, EditorState, PersistentId,
+        PrefabMarker, PrefabMemoryCache, SelectParent, SelectionSets,
     };
 }
 
 pub mod asset_fs;
 pub(crate) mod gizmos;
+/// Non-blocking line based TCP server and client on localhost
+pub mod local_socket;
 pub mod toast;
 
+/// Asset folder, relative to working directory, which scene and asset paths are resolved in
+pub const ASSETS_DIR: &str = "assets";
+
+/// Path relative to [`ASSETS_DIR`] with `/` separators. Asset paths of loaded scenes
+/// and file paths of saved scenes give the same result. Paths outside of assets folder are only normalized
+pub fn scene_asset_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    let prefix = format!("{ASSETS_DIR}/");
+    path.strip_prefix(&prefix)
+        .or_else(|| {
+            path.split_once(&format!("/{prefix}"))
+                .map(|(_, scene)| scene)
+        })
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// File path of asset, relative to working directory
+pub fn asset_file_path(asset_path: &str) -> String {
+    format!("{}/{}", ASSETS_DIR, scene_asset_path(asset_path))
+}
+
 /// Component Marker to display entity in Editor
 #[derive(Component, Default, Clone, Reflect)]
 #[reflect(Component)]
 pub struct PrefabMarker;
 
+/// Stable id of prefab entity, which is saved with scene and survives reloading and undo/redo
+#[derive(Component, Clone, Copy, Reflect, PartialEq, Eq, Hash, Debug)]
+#[reflect(Component)]
+pub struct PersistentId(pub u64);
+
+impl PersistentId {
+    /// Generate new random id
+    pub fn new_random() -> Self {
+        Self(rand::random())
+    }
+}
+
+/// Names of editor selection sets which entity belongs to.
+/// Saved with scene, but used only by editor
+#[derive(Component, Default, Clone, Reflect, PartialEq, Eq, Debug)]
+#[reflect(Component, Default)]
+pub struct SelectionSets(pub Vec<String>);
+
 /// Component marker that manages editor only camera
 /// A camera tagged with this component will not be in use during playmode
 #[derive(Component, Default, Clone, Reflect)]
@@ -82,3 +125,25 @@ pub struct SelectParent {
 #[derive(Component, Default, Clone, Reflect)]
 #[reflect(Component)]
 pub struct LightAreaToggle(pub bool);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_and_save_paths_have_same_asset_path() {
+        for path in [
+            "scenes/level.scn.ron",
+            "assets/scenes/level.scn.ron",
+            "./assets/scenes/level.scn.ron",
+            "C:\\project\\assets\\scenes\\level.scn.ron",
+        ] {
+            assert_eq!(scene_asset_path(path), "scenes/level.scn.ron");
+            assert_eq!(asset_file_path(path), "assets/scenes/level.scn.ron");
+        }
+        assert_eq!(
+            scene_asset_path("my_assets/level.scn.ron"),
+            "my_assets/level.scn.ron"
+        );
+    }
+}
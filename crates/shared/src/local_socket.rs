@@ -0,0 +1,392 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use bevy::log::{debug, error, info, warn};
+
+/// Time between connection attempts of [`LocalClient`]
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(100);
+/// Longest accepted line in bytes. Connection which sends longer line is closed
+pub const MAX_LINE_LEN: usize = 16 * 1024 * 1024;
+
+/// Random hex token which authenticates clients of one editor session
+pub fn random_token() -> String {
+    use rand::Rng;
+    rand::thread_rng()
+        .gen::<[u8; 16]>()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Compare tokens in constant time, so response time does not tell how many bytes are correct
+pub fn tokens_match(token: &str, expected: &str) -> bool {
+    token.len() == expected.len()
+        && token
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Line based TCP server on localhost. It never blocks and must be polled every frame
+#[derive(Default)]
+pub struct LocalServer {
+    listener: Option<TcpListener>,
+    connections: Vec<LineStream>,
+    /// Port of started server, or `None` if server is stopped
+    started: Option<Option<u16>>,
+    /// Address of listening socket
+    pub local_addr: Option<SocketAddr>,
+    /// Error of last server start
+    pub error: Option<String>,
+}
+
+impl LocalServer {
+    /// Start, restart or stop server when port is changed. `None` stops server
+    pub fn sync(&mut self, port: Option<u16>) {
+        if self.started == Some(port) {
+            return;
+        }
+        self.started = Some(port);
+        self.listener = None;
+        self.local_addr = None;
+        self.error = None;
+        self.connections.clear();
+        let Some(port) = port else {
+            return;
+        };
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener));
+        match listener {
+            Ok(listener) => {
+                self.local_addr = listener.local_addr().ok();
+                info!("Listening on {:?}", self.local_addr);
+                self.listener = Some(listener);
+            }
+            Err(err) => {
+                error!("Failed to listen on port {}: {}", port, err);
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
+    /// Accept waiting clients. Returns number of new connections
+    pub fn accept(&mut self) -> usize {
+        let Some(listener) = &self.listener else {
+            return 0;
+        };
+        let mut accepted = 0;
+        loop {
+            match listener.accept() {
+                Ok((stream, addr)) => match LineStream::new(stream) {
+                    Ok(stream) => {
+                        info!("Client connected from {}", addr);
+                        self.connections.push(stream);
+                        accepted += 1;
+                    }
+                    Err(err) => warn!("Failed to accept client {}: {}", addr, err),
+                },
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    warn!("Failed to accept client: {}", err);
+                    break;
+                }
+            }
+        }
+        accepted
+    }
+
+    /// Complete lines received from clients with index of their connection.
+    /// Indices are valid until [`LocalServer::flush`]
+    pub fn read_lines(&mut self) -> Vec<(usize, String)> {
+        self.connections
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(idx, connection)| {
+                connection
+                    .read_lines()
+                    .into_iter()
+                    .map(move |line| (idx, line))
+            })
+            .collect()
+    }
+
+    pub fn send(&mut self, connection: usize, line: &str) {
+        if let Some(connection) = self.connections.get_mut(connection) {
+            connection.send(line);
+        }
+    }
+
+    /// Connection was marked by [`LocalServer::authenticate`]. New connections are not authenticated
+    pub fn is_authenticated(&self, connection: usize) -> bool {
+        self.connections
+            .get(connection)
+            .is_some_and(|connection| connection.authenticated && !connection.closed)
+    }
+
+    pub fn authenticate(&mut self, connection: usize) {
+        if let Some(connection) = self.connections.get_mut(connection) {
+            connection.authenticated = true;
+        }
+    }
+
+    /// Close connection. Lines sent before are still written, connection is dropped on [`LocalServer::flush`]
+    pub fn disconnect(&mut self, connection: usize) {
+        if let Some(connection) = self.connections.get_mut(connection) {
+            connection.flush();
+            connection.closed = true;
+        }
+    }
+
+    pub fn broadcast(&mut self, line: &str) {
+        for connection in self.connections.iter_mut() {
+            connection.send(line);
+        }
+    }
+
+    /// Send line only to connections marked by [`LocalServer::authenticate`]
+    pub fn broadcast_authenticated(&mut self, line: &str) {
+        for connection in self.connections.iter_mut() {
+            if connection.authenticated {
+                connection.send(line);
+            }
+        }
+    }
+
+    /// Write pending lines and drop closed connections
+    pub fn flush(&mut self) {
+        for connection in self.connections.iter_mut() {
+            connection.flush();
+        }
+        self.connections.retain(|connection| !connection.closed);
+    }
+
+    pub fn connections(&self) -> usize {
+        self.connections.len()
+    }
+
+    pub fn authenticated_connections(&self) -> usize {
+        self.connections
+            .iter()
+            .filter(|connection| connection.authenticated && !connection.closed)
+            .count()
+    }
+}
+
+/// Line based TCP client, which connects to [`LocalServer`] on localhost and reconnects when connection is lost.
+/// It never blocks for long and must be polled every frame
+#[derive(Default)]
+pub struct LocalClient {
+    stream: Option<LineStream>,
+    last_attempt: Option<Instant>,
+    /// Line sent first on every connection, for example session token
+    handshake: Option<String>,
+}
+
+impl LocalClient {
+    /// Client which sends `handshake` line first after every connection and reconnection
+    pub fn with_handshake(handshake: String) -> Self {
+        Self {
+            handshake: Some(handshake),
+            ..Default::default()
+        }
+    }
+
+    /// Lines received from server. Returns `None` if client is not connected
+    pub fn read_lines(&mut self, port: u16) -> Option<Vec<String>> {
+        if !self.is_connected() {
+            self.stream = None;
+            if self
+                .last_attempt
+                .is_some_and(|last| last.elapsed() < RECONNECT_INTERVAL)
+            {
+                return None;
+            }
+            self.last_attempt = Some(Instant::now());
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).and_then(LineStream::new) {
+                Ok(mut stream) => {
+                    info!("Connected to {}", addr);
+                    if let Some(handshake) = &self.handshake {
+                        stream.send(handshake);
+                    }
+                    self.stream = Some(stream);
+                }
+                Err(err) => {
+                    debug!("Failed to connect to {}: {}", addr, err);
+                    return None;
+                }
+            }
+        }
+        self.stream.as_mut().map(LineStream::read_lines)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.stream.as_ref().is_some_and(|stream| !stream.closed)
+    }
+}
+
+/// Non-blocking stream of lines
+struct LineStream {
+    stream: TcpStream,
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
+    closed: bool,
+    authenticated: bool,
+}
+
+impl LineStream {
+    fn new(stream: TcpStream) -> std::io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            stream,
+            read_buf: vec![],
+            write_buf: vec![],
+            closed: false,
+            authenticated: false,
+        })
+    }
+
+    fn read_lines(&mut self) -> Vec<String> {
+        let mut buf = [0; 4096];
+        while !self.closed && self.read_buf.len() <= MAX_LINE_LEN {
+            match self.stream.read(&mut buf) {
+                Ok(0) => self.closed = true,
+                Ok(len) => self.read_buf.extend_from_slice(&buf[..len]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(_) => self.closed = true,
+            }
+        }
+
+        let mut lines = vec![];
+        while let Some(end) = self.read_buf.iter().position(|b| *b == b'\n') {
+            if end > MAX_LINE_LEN {
+                break;
+            }
+            let line = self.read_buf.drain(..=end).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        if self.read_buf.len() > MAX_LINE_LEN {
+            warn!(
+                "Received line is longer than {} bytes, closing connection",
+                MAX_LINE_LEN
+            );
+            self.read_buf.clear();
+            self.closed = true;
+        }
+        lines
+    }
+
+    fn send(&mut self, line: &str) {
+        self.write_buf.extend_from_slice(line.as_bytes());
+        self.write_buf.push(b'\n');
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        while !self.write_buf.is_empty() {
+            match self.stream.write(&self.write_buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(len) => {
+                    self.write_buf.drain(..len);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(_) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_and_server_exchange_lines() {
+        let mut server = LocalServer::default();
+        server.sync(Some(0));
+        let port = server.local_addr.unwrap().port();
+
+        let mut client = LocalClient::default();
+        assert_eq!(client.read_lines(port), Some(vec![]));
+        assert!(client.is_connected());
+
+        let mut accepted = 0;
+        for _ in 0..100 {
+            accepted += server.accept();
+            if accepted > 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(server.connections(), 1);
+
+        server.broadcast("hello");
+        server.flush();
+        let mut lines = vec![];
+        for _ in 0..100 {
+            lines.extend(client.read_lines(port).unwrap());
+            if !lines.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(lines, vec!["hello".to_string()]);
+
+        server.sync(None);
+        assert_eq!(server.local_addr, None);
+        assert_eq!(server.connections(), 0);
+    }
+
+    #[test]
+    fn random_tokens_differ_and_match_only_themselves() {
+        let token = random_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, random_token());
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, &random_token()));
+        assert!(!tokens_match(&token, &token[1..]));
+    }
+
+    #[test]
+    fn too_long_line_closes_connection() {
+        let mut server = LocalServer::default();
+        server.sync(Some(0));
+        let addr = server.local_addr.unwrap();
+        let writer = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // Fails when server closes connection before everything is written
+            let _ = stream.write_all(&vec![b'a'; MAX_LINE_LEN + 1]);
+        });
+
+        let mut accepted = 0;
+        for _ in 0..1000 {
+            accepted += server.accept();
+            assert!(server.read_lines().is_empty());
+            server.flush();
+            if accepted > 0 && server.connections() == 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(accepted, 1);
+        assert_eq!(server.connections(), 0);
+        writer.join().unwrap();
+    }
+}
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 pub use egui_toast::*;
 
-#[derive(Event)]
+#[derive(Event, Clone)]
 pub struct ToastMessage {
     pub text: String,
     pub kind: ToastKind,
+    /// Entity which message refers to
+    pub entity: Option<Entity>,
+    /// Full description shown in notification log
+    pub details: Option<String>,
 }
 
 impl ToastMessage {
@@ -12,8 +16,20 @@ impl ToastMessage {
         Self {
             text: text.to_string(),
             kind,
+            entity: None,
+            details: None,
         }
     }
+
+    pub const fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
 }
 
 impl From<&ToastMessage> for Toast {
@@ -50,6 +66,16 @@ mod tests {
         assert_eq!(message.kind, ToastKind::Info);
     }
 
+    #[test]
+    fn toast_message_with_entity_and_details() {
+        let entity = Entity::from_raw(7);
+        let message = ToastMessage::new("Test message", ToastKind::Error)
+            .with_entity(entity)
+            .with_details("Full error");
+        assert_eq!(message.entity, Some(entity));
+        assert_eq!(message.details.as_deref(), Some("Full error"));
+    }
+
     #[test]
     fn from_toast_message() {
         let message = ToastMessage::new("Test message", ToastKind::Info);
@@ -3,9 +3,13 @@
 #[cfg(test)]
 mod tests;
 
-use std::sync::Arc;
+/// Field-level diffs of reflected values
+pub mod reflect_patch;
+
+use std::{any::TypeId, sync::Arc};
 
 use bevy::{prelude::*, utils::HashMap};
+use reflect_patch::{apply_patches, diff_reflect, ReflectPatch};
 
 const MAX_REFLECT_RECURSION: i32 = 10;
 const AUTO_UNDO_LATENCY: i32 = 2;
@@ -25,6 +29,7 @@ impl Plugin for UndoPlugin {
 
         app.add_event::<NewChange>();
         app.add_event::<UndoRedo>();
+        app.add_event::<ChangeCommitted>();
 
         app.configure_sets(
             PostUpdate,
@@ -109,6 +114,7 @@ fn update_change_chain(
     settings: Res<ChangeChainSettings>,
     mut change_chain: ResMut<ChangeChain>,
     mut events: EventReader<NewChange>,
+    mut committed: EventWriter<ChangeCommitted>,
 ) {
     //collect buffer
     let mut events_on_current_frame = 0;
@@ -135,17 +141,15 @@ fn update_change_chain(
     //Clear buffer
     buffer.clear();
 
-    match new_changes.len().cmp(&1) {
-        std::cmp::Ordering::Less => {}
-        std::cmp::Ordering::Equal => {
-            change_chain.changes.push(new_changes[0].clone());
-        }
-        std::cmp::Ordering::Greater => {
-            change_chain.changes.push(Arc::new(ManyChanges {
-                changes: new_changes,
-            }));
-        }
+    let change: Arc<dyn EditorChange + Send + Sync> = match new_changes.len().cmp(&1) {
+        std::cmp::Ordering::Less => return,
+        std::cmp::Ordering::Equal => new_changes[0].clone(),
+        std::cmp::Ordering::Greater => Arc::new(ManyChanges {
+            changes: new_changes,
+        }),
     };
+    change_chain.changes.push(change.clone());
+    committed.send(ChangeCommitted { change });
 
     if change_chain.changes.len() > settings.max_change_chain_size {
         let count = change_chain.changes.len() - settings.max_change_chain_size;
@@ -174,9 +178,21 @@ fn undo_redo_logic(world: &mut World) {
                     match event {
                         UndoRedo::Undo => {
                             if let Some(change) = change_chain.changes.pop() {
-                                let res = change.revert(world, &change_chain.entity_remap).unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
+                                match change.revert(world, &change_chain.entity_remap) {
+                                    Ok(ChangeResult::SuccessWithRemap(remap)) => {
+                                        change_chain.entity_remap.extend(remap);
+                                        world.send_event(ChangeCommitted {
+                                            change: change.get_inverse(),
+                                        });
+                                    }
+                                    Ok(ChangeResult::Success) => {
+                                        world.send_event(ChangeCommitted {
+                                            change: change.get_inverse(),
+                                        });
+                                    }
+                                    Err(err) => {
+                                        error!("Failed to undo `{}`: {}", change.debug_text(), err);
+                                    }
                                 }
                                 change_chain.changes_for_redo.push(change);
                             }
@@ -184,11 +200,21 @@ fn undo_redo_logic(world: &mut World) {
                         UndoRedo::Redo => {
                             if let Some(change) = change_chain.changes_for_redo.pop() {
                                 let inverse_change = change.get_inverse();
-                                let res = inverse_change
-                                    .revert(world, &change_chain.entity_remap)
-                                    .unwrap();
-                                if let ChangeResult::SuccessWithRemap(remap) = res {
-                                    change_chain.entity_remap.extend(remap);
+                                match inverse_change.revert(world, &change_chain.entity_remap) {
+                                    Ok(ChangeResult::SuccessWithRemap(remap)) => {
+                                        change_chain.entity_remap.extend(remap);
+                                        world.send_event(ChangeCommitted {
+                                            change: change.clone(),
+                                        });
+                                    }
+                                    Ok(ChangeResult::Success) => {
+                                        world.send_event(ChangeCommitted {
+                                            change: change.clone(),
+                                        });
+                                    }
+                                    Err(err) => {
+                                        error!("Failed to redo `{}`: {}", change.debug_text(), err);
+                                    }
                                 }
                                 change_chain.changes.push(change);
                             }
@@ -208,6 +234,27 @@ pub struct ChangeChain {
     entity_remap: HashMap<Entity, Entity>,
 }
 
+impl ChangeChain {
+    /// Entities which were recreated by undo/redo (original entity -> recreated entity)
+    pub const fn entity_remap(&self) -> &HashMap<Entity, Entity> {
+        &self.entity_remap
+    }
+
+    /// Label of change which will be reverted by next undo
+    pub fn undo_label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> Option<String> {
+        let name_of = |entity| name_of(get_entity_with_remap(entity, &self.entity_remap));
+        self.changes.last().map(|change| change.label(&name_of))
+    }
+
+    /// Label of change which will be applied by next redo
+    pub fn redo_label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> Option<String> {
+        let name_of = |entity| name_of(get_entity_with_remap(entity, &self.entity_remap));
+        self.changes_for_redo
+            .last()
+            .map(|change| change.label(&name_of))
+    }
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource, Default)]
 pub struct ChangeChainSettings {
@@ -226,6 +273,11 @@ pub fn get_entity_with_remap(entity: Entity, entity_remap: &HashMap<Entity, Enti
     *entity_remap.get(&entity).unwrap_or(&entity)
 }
 
+/// Entity name for change labels, falls back to entity index
+pub fn entity_label(entity: Entity, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+    name_of(entity).unwrap_or_else(|| format!("Entity {}", entity.index()))
+}
+
 pub trait EditorChange {
     fn revert(
         &self,
@@ -236,6 +288,41 @@ pub trait EditorChange {
     fn debug_text(&self) -> String;
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync>;
+
+    /// User-facing description of change like `Move Crate`.
+    /// `name_of` returns [`Name`] of entity if it has one
+    fn label(&self, _name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        self.debug_text()
+    }
+
+    /// Reflection based description of change for change journal and live link.
+    /// `None` means that change does not touch the scene
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::Unrecorded(self.debug_text()))
+    }
+}
+
+/// Reflection based description of change, which allows to store change outside of memory
+pub enum ChangeRecord {
+    AddedEntity(Entity),
+    RemovedEntity(Entity),
+    ComponentPatch {
+        entity: Entity,
+        type_id: TypeId,
+        patches: Vec<ReflectPatch>,
+    },
+    AddedComponent {
+        entity: Entity,
+        value: Box<dyn Reflect>,
+    },
+    RemovedComponent {
+        entity: Entity,
+        type_id: TypeId,
+    },
+    Many(Vec<Self>),
+    /// Scene change which can't be described with reflection (for example change of
+    /// component without reflection). Replay of changes must stop at it
+    Unrecorded(String),
 }
 
 pub enum ChangeResult {
@@ -254,6 +341,12 @@ pub struct NewChange {
     pub change: Arc<dyn EditorChange + Send + Sync>,
 }
 
+/// Sent when change was pushed to change chain or applied to world by undo/redo
+#[derive(Event, Clone)]
+pub struct ChangeCommitted {
+    pub change: Arc<dyn EditorChange + Send + Sync>,
+}
+
 pub struct AddedEntity {
     pub entity: Entity,
 }
@@ -278,11 +371,19 @@ impl EditorChange for AddedEntity {
         format!("Added Entity: {}", self.entity.index())
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!("Add {}", entity_label(self.entity, name_of))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(RemovedEntity {
             entity: self.entity,
         })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::AddedEntity(self.entity))
+    }
 }
 
 pub struct RemovedEntity {
@@ -321,11 +422,19 @@ impl EditorChange for RemovedEntity {
         format!("Removed Entity: {}", self.entity.index())
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!("Delete {}", entity_label(self.entity, name_of))
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(AddedEntity {
             entity: self.entity,
         })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::RemovedEntity(self.entity))
+    }
 }
 
 pub struct ComponentChange<T: Component> {
@@ -353,6 +462,14 @@ impl<T: Component + Clone> EditorChange for ComponentChange<T> {
         format!("ComponentChange for entity {:?}", self.entity)
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!(
+            "Change {} of {}",
+            pretty_type_name::pretty_type_name::<T>(),
+            entity_label(self.entity, name_of)
+        )
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(Self {
             old_value: self.new_value.clone(),
@@ -362,10 +479,32 @@ impl<T: Component + Clone> EditorChange for ComponentChange<T> {
     }
 }
 
+/// Reflected component change, which stores only changed fields of component
 pub struct ReflectedComponentChange<T: Component + Reflect + FromReflect> {
-    old_value: T,
-    new_value: T,
+    patches: Vec<ReflectPatch>,
     entity: Entity,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: Component + Reflect + FromReflect> ReflectedComponentChange<T> {
+    /// Create change from difference between old and new component values.
+    /// Returns `None` if values are equal
+    pub fn from_diff(old_value: &T, new_value: &T, entity: Entity) -> Option<Self> {
+        let patches = diff_reflect(old_value, new_value, MAX_REFLECT_RECURSION);
+        if patches.is_empty() {
+            None
+        } else {
+            Some(Self {
+                patches,
+                entity,
+                _phantom: std::marker::PhantomData,
+            })
+        }
+    }
+
+    pub fn patches(&self) -> &[ReflectPatch] {
+        &self.patches
+    }
 }
 
 impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentChange<T> {
@@ -376,11 +515,19 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentCh
     ) -> Result<ChangeResult, String> {
         let e = get_entity_with_remap(self.entity, entity_remap);
 
-        world.entity_mut(e).insert((
-            <T as FromReflect>::from_reflect(&self.old_value)
-                .ok_or(format!("Failed to revert reflected entity `{:?}`", e))?,
-            OneFrameUndoIgnore::default(),
-        ));
+        let mut entity = world
+            .get_entity_mut(e)
+            .ok_or_else(|| format!("Failed to revert reflected entity `{:?}`", e))?;
+        let mut component = entity.get_mut::<T>().ok_or_else(|| {
+            format!(
+                "Failed to revert reflected entity `{:?}`: component {} not found",
+                e,
+                pretty_type_name::pretty_type_name::<T>()
+            )
+        })?;
+        apply_patches(component.as_reflect_mut(), &self.patches, true)?;
+        entity.insert(OneFrameUndoIgnore::default());
+
         world.send_event(UndoRedoApplied::<T> {
             entity: e,
             _phantom: std::marker::PhantomData,
@@ -394,18 +541,48 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedComponentCh
     }
 
     fn debug_text(&self) -> String {
-        format!(
-            "{:?} changed for entity {:?}",
-            pretty_type_name::pretty_type_name::<T>(),
-            self.entity
+        let type_name = pretty_type_name::pretty_type_name::<T>();
+        let fields = self
+            .patches
+            .iter()
+            .map(|patch| format!("{}{}", type_name, patch.description()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{} for entity {:?}", fields, self.entity)
+    }
+
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        let name = entity_label(self.entity, name_of);
+        let verb = if TypeId::of::<T>() == TypeId::of::<Transform>() {
+            transform_verb(&self.patches)
+        } else {
+            None
+        };
+        verb.map_or_else(
+            || {
+                format!(
+                    "Change {} of {}",
+                    pretty_type_name::pretty_type_name::<T>(),
+                    name
+                )
+            },
+            |verb| format!("{} {}", verb, name),
         )
     }
 
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(Self {
-            old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
-            new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
+            patches: self.patches.iter().map(|patch| patch.inverse()).collect(),
+            entity: self.entity,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::ComponentPatch {
             entity: self.entity,
+            type_id: TypeId::of::<T>(),
+            patches: self.patches.clone(),
         })
     }
 }
@@ -443,6 +620,14 @@ impl<T: Component + Clone> EditorChange for AddedComponent<T> {
         format!("AddedComponent for entity {:?}", self.entity)
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!(
+            "Add {} to {}",
+            pretty_type_name::pretty_type_name::<T>(),
+            entity_label(self.entity, name_of)
+        )
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(RemovedComponent {
             entity: self.entity,
@@ -489,12 +674,27 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedAddedCompon
         format!("ReflectedAddedComponent for entity {:?}", self.entity)
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!(
+            "Add {} to {}",
+            pretty_type_name::pretty_type_name::<T>(),
+            entity_label(self.entity, name_of)
+        )
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ReflectedRemovedComponent {
             old_value: <T as FromReflect>::from_reflect(&self.new_value).unwrap(),
             entity: self.entity,
         })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::AddedComponent {
+            entity: self.entity,
+            value: self.new_value.clone_value(),
+        })
+    }
 }
 
 pub struct RemovedComponent<T: Component + Clone> {
@@ -535,12 +735,27 @@ impl<T: Component + Clone> EditorChange for RemovedComponent<T> {
         format!("RemovedComponent for entity {:?}", self.entity)
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!(
+            "Remove {} from {}",
+            pretty_type_name::pretty_type_name::<T>(),
+            entity_label(self.entity, name_of)
+        )
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(AddedComponent {
             new_value: self.old_value.clone(),
             entity: self.entity,
         })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::RemovedComponent {
+            entity: self.entity,
+            type_id: TypeId::of::<T>(),
+        })
+    }
 }
 
 pub struct ReflectedRemovedComponent<T: Component + Reflect> {
@@ -592,12 +807,105 @@ impl<T: Component + Reflect + FromReflect> EditorChange for ReflectedRemovedComp
         format!("ReflectedRemovedComponent for entity {:?}", self.entity)
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!(
+            "Remove {} from {}",
+            pretty_type_name::pretty_type_name::<T>(),
+            entity_label(self.entity, name_of)
+        )
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         Arc::new(ReflectedAddedComponent {
             new_value: <T as FromReflect>::from_reflect(&self.old_value).unwrap(),
             entity: self.entity,
         })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        Some(ChangeRecord::RemovedComponent {
+            entity: self.entity,
+            type_id: TypeId::of::<T>(),
+        })
+    }
+}
+
+/// Reflected resource change, which stores only changed fields of resource
+pub struct ResourceChange<R: Resource + Reflect + FromReflect> {
+    patches: Vec<ReflectPatch>,
+    _phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: Resource + Reflect + FromReflect> ResourceChange<R> {
+    /// Create change from difference between old and new resource values.
+    /// Returns `None` if values are equal
+    pub fn from_diff(old_value: &R, new_value: &R) -> Option<Self> {
+        let patches = diff_reflect(old_value, new_value, MAX_REFLECT_RECURSION);
+        if patches.is_empty() {
+            None
+        } else {
+            Some(Self {
+                patches,
+                _phantom: std::marker::PhantomData,
+            })
+        }
+    }
+
+    pub fn patches(&self) -> &[ReflectPatch] {
+        &self.patches
+    }
+}
+
+impl<R: Resource + Reflect + FromReflect> EditorChange for ResourceChange<R> {
+    fn revert(
+        &self,
+        world: &mut World,
+        _entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        let mut resource = world.get_resource_mut::<R>().ok_or_else(|| {
+            format!(
+                "Failed to revert resource {}: resource not found",
+                pretty_type_name::pretty_type_name::<R>()
+            )
+        })?;
+        apply_patches(resource.as_reflect_mut(), &self.patches, true)?;
+
+        // Snapshot will be taken again, so reverted value will not be detected as new change
+        if let Some(mut storage) = world.get_resource_mut::<AutoUndoResourceStorage<R>>() {
+            storage.reset();
+        }
+
+        info!(
+            "Reverted ResourceChange for {}",
+            pretty_type_name::pretty_type_name::<R>()
+        );
+        Ok(ChangeResult::Success)
+    }
+
+    fn debug_text(&self) -> String {
+        let type_name = pretty_type_name::pretty_type_name::<R>();
+        self.patches
+            .iter()
+            .map(|patch| format!("{}{}", type_name, patch.description()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn label(&self, _name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        format!("Change {}", pretty_type_name::pretty_type_name::<R>())
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(Self {
+            patches: self.patches.iter().map(|patch| patch.inverse()).collect(),
+            _phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Resources are not part of the scene
+    fn record(&self) -> Option<ChangeRecord> {
+        None
+    }
 }
 
 pub struct ManyChanges {
@@ -632,6 +940,49 @@ impl EditorChange for ManyChanges {
         "ManyChanges".to_string()
     }
 
+    fn label(&self, name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        // Component changes of added or deleted entities are part of entity change
+        let records = self
+            .changes
+            .iter()
+            .map(|change| change.record())
+            .collect::<Vec<_>>();
+        let structural = records
+            .iter()
+            .filter_map(|record| match record {
+                Some(ChangeRecord::AddedEntity(entity) | ChangeRecord::RemovedEntity(entity)) => {
+                    Some(*entity)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut labels: Vec<String> = vec![];
+        for (change, record) in self.changes.iter().zip(records.iter()) {
+            let part_of_entity_change = match record {
+                Some(
+                    ChangeRecord::ComponentPatch { entity, .. }
+                    | ChangeRecord::AddedComponent { entity, .. }
+                    | ChangeRecord::RemovedComponent { entity, .. },
+                ) => structural.contains(entity),
+                _ => false,
+            };
+            if part_of_entity_change {
+                continue;
+            }
+            let label = change.label(name_of);
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+
+        match labels.len() {
+            0 => "Empty change".to_string(),
+            1 => labels.remove(0),
+            len => format!("{} and {} more", labels[0], len - 1),
+        }
+    }
+
     fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
         let mut old_changes = self.changes.clone();
         old_changes.reverse();
@@ -642,6 +993,91 @@ impl EditorChange for ManyChanges {
 
         Arc::new(Self { changes })
     }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        let records = self
+            .changes
+            .iter()
+            .filter_map(|change| change.record())
+            .collect::<Vec<_>>();
+        if records.is_empty() {
+            None
+        } else {
+            Some(ChangeRecord::Many(records))
+        }
+    }
+}
+
+/// Change with custom label. Tabs can use it to describe their own changes
+pub struct LabeledChange {
+    pub label: String,
+    pub change: Arc<dyn EditorChange + Send + Sync>,
+}
+
+impl LabeledChange {
+    pub fn new(
+        label: impl Into<String>,
+        change: impl EditorChange + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            change: Arc::new(change),
+        }
+    }
+}
+
+impl EditorChange for LabeledChange {
+    fn revert(
+        &self,
+        world: &mut World,
+        entity_remap: &HashMap<Entity, Entity>,
+    ) -> Result<ChangeResult, String> {
+        self.change.revert(world, entity_remap)
+    }
+
+    fn debug_text(&self) -> String {
+        format!("{}: {}", self.label, self.change.debug_text())
+    }
+
+    fn get_inverse(&self) -> Arc<dyn EditorChange + Send + Sync> {
+        Arc::new(Self {
+            label: self.label.clone(),
+            change: self.change.get_inverse(),
+        })
+    }
+
+    fn label(&self, _name_of: &dyn Fn(Entity) -> Option<String>) -> String {
+        self.label.clone()
+    }
+
+    fn record(&self) -> Option<ChangeRecord> {
+        self.change.record()
+    }
+}
+
+/// Verb for transform change, if only one of translation, rotation or scale was changed
+fn transform_verb(patches: &[ReflectPatch]) -> Option<&'static str> {
+    let field = |patch: &ReflectPatch| {
+        patch
+            .path
+            .trim_start_matches('.')
+            .split(['.', '['])
+            .next()
+            .map(str::to_string)
+    };
+    let first = field(patches.first()?)?;
+    if patches
+        .iter()
+        .any(|patch| field(patch).as_ref() != Some(&first))
+    {
+        return None;
+    }
+    match first.as_str() {
+        "translation" => Some("Move"),
+        "rotation" => Some("Rotate"),
+        "scale" => Some("Scale"),
+        _ => None,
+    }
 }
 
 #[derive(Component)]
@@ -677,11 +1113,39 @@ impl<T: Component> Default for AutoUndoStorage<T> {
     }
 }
 
+/// Last known value of resource for auto undo
+#[derive(Resource)]
+pub struct AutoUndoResourceStorage<R: Resource> {
+    pub value: Option<R>,
+    latency: Option<i32>,
+}
+
+impl<R: Resource> Default for AutoUndoResourceStorage<R> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            latency: None,
+        }
+    }
+}
+
+impl<R: Resource> AutoUndoResourceStorage<R> {
+    /// Drop stored value, so current resource value will be taken as new snapshot
+    /// without creating undo change (useful after loading resource from disk)
+    pub fn reset(&mut self) {
+        self.value = None;
+        self.latency = None;
+    }
+}
+
 pub trait AppAutoUndo {
     fn auto_undo<T: Component + Clone>(&mut self) -> &mut Self;
 
     //Allow more complex undo and auto entity remapping
     fn auto_reflected_undo<T: Component + Reflect + FromReflect>(&mut self) -> &mut Self;
+
+    /// Track changes of reflected resource and push them to [`ChangeChain`]
+    fn auto_resource_undo<R: Resource + Reflect + FromReflect>(&mut self) -> &mut Self;
 }
 
 impl AppAutoUndo for App {
@@ -741,9 +1205,28 @@ impl AppAutoUndo for App {
 
         self
     }
+
+    fn auto_resource_undo<R: Resource + Reflect + FromReflect>(&mut self) -> &mut Self {
+        if !self.world_mut().contains_resource::<ChangeChain>() {
+            return self;
+        }
+
+        self.world_mut()
+            .insert_resource(AutoUndoResourceStorage::<R>::default());
+
+        self.add_systems(
+            PostUpdate,
+            auto_undo_resource_system::<R>
+                .run_if(resource_exists::<R>)
+                .in_set(UndoSet::PerType),
+        );
+
+        self
+    }
 }
 
-fn apply_for_every_typed_field<D: Reflect>(
+/// Call `applyer` for every field of type `D` inside reflected value
+pub fn apply_for_every_typed_field<D: Reflect>(
     value: &mut dyn Reflect,
     applyer: &dyn Fn(&mut D),
     max_recursion: i32,
@@ -1018,22 +1501,73 @@ fn auto_undo_reflected_system<T: Component + Reflect + FromReflect>(
 
             commands.entity(e).remove::<ChangedMarker<T>>();
 
-            if let Some(prev_value) = storage.storage.get(&e) {
-                new_change.send(NewChange {
-                    change: Arc::new(ReflectedComponentChange {
-                        old_value: <T as FromReflect>::from_reflect(prev_value).unwrap(),
-                        new_value: <T as FromReflect>::from_reflect(data.as_ref()).unwrap(),
-                        entity: e,
-                    }),
-                });
-                debug!("Auto undo change for entity {:?}", e);
+            if let Some(prev_value) = storage.storage.get_mut(&e) {
+                if let Some(change) =
+                    ReflectedComponentChange::<T>::from_diff(prev_value, data.as_ref(), e)
+                {
+                    // Keep cached value in sync by applying only changed fields
+                    if let Err(err) =
+                        apply_patches(prev_value.as_reflect_mut(), change.patches(), false)
+                    {
+                        warn!("Failed to update undo cache for entity {:?}: {}", e, err);
+                        *prev_value = <T as FromReflect>::from_reflect(data.as_ref()).unwrap();
+                    }
+                    new_change.send(NewChange {
+                        change: Arc::new(change),
+                    });
+                    debug!("Auto undo change for entity {:?}", e);
+                }
+            } else {
+                storage
+                    .storage
+                    .insert(e, <T as FromReflect>::from_reflect(data.as_ref()).unwrap());
             }
-
-            storage
-                .storage
-                .insert(e, <T as FromReflect>::from_reflect(data.as_ref()).unwrap());
         } else {
             marker.latency = AUTO_UNDO_LATENCY;
         }
     }
 }
+
+fn auto_undo_resource_system<R: Resource + Reflect + FromReflect>(
+    resource: Res<R>,
+    mut storage: ResMut<AutoUndoResourceStorage<R>>,
+    mut new_change: EventWriter<NewChange>,
+) {
+    let storage = &mut *storage;
+    let Some(prev_value) = storage.value.as_mut() else {
+        storage.value = <R as FromReflect>::from_reflect(resource.as_ref());
+        return;
+    };
+
+    if resource.is_changed() {
+        storage.latency = Some(AUTO_UNDO_LATENCY);
+        return;
+    }
+
+    let Some(latency) = storage.latency.as_mut() else {
+        return;
+    };
+    *latency -= 1;
+    if *latency > 0 {
+        return;
+    }
+    storage.latency = None;
+
+    if let Some(change) = ResourceChange::<R>::from_diff(prev_value, resource.as_ref()) {
+        if let Err(err) = apply_patches(prev_value.as_reflect_mut(), change.patches(), false) {
+            warn!(
+                "Failed to update undo cache for resource {}: {}",
+                pretty_type_name::pretty_type_name::<R>(),
+                err
+            );
+            storage.value = <R as FromReflect>::from_reflect(resource.as_ref());
+        }
+        new_change.send(NewChange {
+            change: Arc::new(change),
+        });
+        debug!(
+            "Auto undo change for resource {}",
+            pretty_type_name::pretty_type_name::<R>()
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use bevy::{
+    prelude::*,
+    reflect::{GetPath, ReflectMut, ReflectRef, VariantType},
+};
+
+/// Single field-level difference between two reflected values.
+/// `path` is a reflect path relative to the root value (empty for the whole value)
+pub struct ReflectPatch {
+    pub path: String,
+    pub old_value: Box<dyn Reflect>,
+    pub new_value: Box<dyn Reflect>,
+}
+
+impl Clone for ReflectPatch {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            old_value: self.old_value.clone_value(),
+            new_value: self.new_value.clone_value(),
+        }
+    }
+}
+
+impl ReflectPatch {
+    /// Patch with swapped old and new values
+    pub fn inverse(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            old_value: self.new_value.clone_value(),
+            new_value: self.old_value.clone_value(),
+        }
+    }
+
+    /// Human readable form like `.translation.x: 1.0 → 2.5`
+    pub fn description(&self) -> String {
+        format!(
+            "{}: {:?} → {:?}",
+            self.path,
+            self.old_value.as_ref(),
+            self.new_value.as_ref()
+        )
+    }
+}
+
+/// Collect all changed fields between `old` and `new` values.
+/// Recursion stops at `max_recursion` depth, deeper differences are recorded as a whole value
+pub fn diff_reflect(old: &dyn Reflect, new: &dyn Reflect, max_recursion: i32) -> Vec<ReflectPatch> {
+    let mut patches = vec![];
+    diff_reflect_inner(old, new, String::new(), max_recursion, &mut patches);
+    patches
+}
+
+fn diff_reflect_inner(
+    old: &dyn Reflect,
+    new: &dyn Reflect,
+    path: String,
+    max_recursion: i32,
+    patches: &mut Vec<ReflectPatch>,
+) {
+    if old.reflect_type_path() != new.reflect_type_path() || max_recursion < 0 {
+        push_if_changed(old, new, path, patches);
+        return;
+    }
+
+    match (old.reflect_ref(), new.reflect_ref()) {
+        (ReflectRef::Struct(old_s), ReflectRef::Struct(new_s)) => {
+            for idx in 0..old_s.field_len() {
+                let (Some(name), Some(old_field)) = (old_s.name_at(idx), old_s.field_at(idx))
+                else {
+                    continue;
+                };
+                let Some(new_field) = new_s.field(name) else {
+                    continue;
+                };
+                diff_reflect_inner(
+                    old_field,
+                    new_field,
+                    format!("{}.{}", path, name),
+                    max_recursion - 1,
+                    patches,
+                );
+            }
+        }
+        (ReflectRef::TupleStruct(old_s), ReflectRef::TupleStruct(new_s)) => {
+            for idx in 0..old_s.field_len() {
+                let (Some(old_field), Some(new_field)) = (old_s.field(idx), new_s.field(idx))
+                else {
+                    continue;
+                };
+                diff_reflect_inner(
+                    old_field,
+                    new_field,
+                    format!("{}.{}", path, idx),
+                    max_recursion - 1,
+                    patches,
+                );
+            }
+        }
+        (ReflectRef::Tuple(old_t), ReflectRef::Tuple(new_t)) => {
+            for idx in 0..old_t.field_len() {
+                let (Some(old_field), Some(new_field)) = (old_t.field(idx), new_t.field(idx))
+                else {
+                    continue;
+                };
+                diff_reflect_inner(
+                    old_field,
+                    new_field,
+                    format!("{}.{}", path, idx),
+                    max_recursion - 1,
+                    patches,
+                );
+            }
+        }
+        (ReflectRef::List(old_l), ReflectRef::List(new_l)) if old_l.len() == new_l.len() => {
+            for idx in 0..old_l.len() {
+                let (Some(old_item), Some(new_item)) = (old_l.get(idx), new_l.get(idx)) else {
+                    continue;
+                };
+                diff_reflect_inner(
+                    old_item,
+                    new_item,
+                    format!("{}[{}]", path, idx),
+                    max_recursion - 1,
+                    patches,
+                );
+            }
+        }
+        (ReflectRef::Array(old_a), ReflectRef::Array(new_a)) if old_a.len() == new_a.len() => {
+            for idx in 0..old_a.len() {
+                let (Some(old_item), Some(new_item)) = (old_a.get(idx), new_a.get(idx)) else {
+                    continue;
+                };
+                diff_reflect_inner(
+                    old_item,
+                    new_item,
+                    format!("{}[{}]", path, idx),
+                    max_recursion - 1,
+                    patches,
+                );
+            }
+        }
+        (ReflectRef::Enum(old_e), ReflectRef::Enum(new_e))
+            if old_e.variant_name() == new_e.variant_name() =>
+        {
+            for idx in 0..old_e.field_len() {
+                let (Some(old_field), Some(new_field)) = (old_e.field_at(idx), new_e.field_at(idx))
+                else {
+                    continue;
+                };
+                let field_path = match old_e.variant_type() {
+                    VariantType::Struct => {
+                        let Some(name) = old_e.name_at(idx) else {
+                            continue;
+                        };
+                        format!("{}.{}", path, name)
+                    }
+                    _ => format!("{}.{}", path, idx),
+                };
+                diff_reflect_inner(old_field, new_field, field_path, max_recursion - 1, patches);
+            }
+        }
+        // Maps, values and structural changes (resized lists, switched enum variants)
+        // are recorded as a whole
+        _ => push_if_changed(old, new, path, patches),
+    }
+}
+
+fn push_if_changed(
+    old: &dyn Reflect,
+    new: &dyn Reflect,
+    path: String,
+    patches: &mut Vec<ReflectPatch>,
+) {
+    // Types without reflected PartialEq are always treated as changed
+    if old.reflect_partial_eq(new) != Some(true) {
+        patches.push(ReflectPatch {
+            path,
+            old_value: old.clone_value(),
+            new_value: new.clone_value(),
+        });
+    }
+}
+
+/// Apply the old (`revert == true`) or new value of every patch to `target`
+pub fn apply_patches(
+    target: &mut dyn Reflect,
+    patches: &[ReflectPatch],
+    revert: bool,
+) -> Result<(), String> {
+    for patch in patches {
+        let value = if revert {
+            patch.old_value.as_ref()
+        } else {
+            patch.new_value.as_ref()
+        };
+        let field = if patch.path.is_empty() {
+            &mut *target
+        } else {
+            target
+                .reflect_path_mut(patch.path.as_str())
+                .map_err(|e| format!("Failed to find field `{}`: {}", patch.path, e))?
+        };
+        replace_reflect(field, value)
+            .map_err(|e| format!("Failed to apply field `{}`: {}", patch.path, e))?;
+    }
+    Ok(())
+}
+
+/// Make `target` equal to `value`. Unlike [`Reflect::try_apply`], list items and map entries
+/// missing in `value` are removed, so resized lists and maps are restored exactly
+pub fn replace_reflect(target: &mut dyn Reflect, value: &dyn Reflect) -> Result<(), String> {
+    match (target.reflect_mut(), value.reflect_ref()) {
+        (ReflectMut::List(target), ReflectRef::List(value)) => {
+            while target.len() > value.len() {
+                target.pop();
+            }
+            for (idx, item) in value.iter().enumerate() {
+                match target.get_mut(idx) {
+                    Some(target_item) => replace_reflect(target_item, item)?,
+                    None => target.push(item.clone_value()),
+                }
+            }
+        }
+        (ReflectMut::Map(target), ReflectRef::Map(value)) => {
+            let removed = target
+                .iter()
+                .filter(|(key, _)| value.get(*key).is_none())
+                .map(|(key, _)| key.clone_value())
+                .collect::<Vec<_>>();
+            for key in removed {
+                target.remove(key.as_ref());
+            }
+            for (key, item) in value.iter() {
+                match target.get_mut(key) {
+                    Some(target_item) => replace_reflect(target_item, item)?,
+                    None => {
+                        target.insert_boxed(key.clone_value(), item.clone_value());
+                    }
+                }
+            }
+        }
+        (ReflectMut::Struct(target), ReflectRef::Struct(value)) => {
+            for idx in 0..value.field_len() {
+                let (Some(name), Some(item)) = (value.name_at(idx), value.field_at(idx)) else {
+                    continue;
+                };
+                if let Some(target_item) = target.field_mut(name) {
+                    replace_reflect(target_item, item)?;
+                }
+            }
+        }
+        (ReflectMut::TupleStruct(target), ReflectRef::TupleStruct(value)) => {
+            for (idx, item) in value.iter_fields().enumerate() {
+                if let Some(target_item) = target.field_mut(idx) {
+                    replace_reflect(target_item, item)?;
+                }
+            }
+        }
+        (ReflectMut::Tuple(target), ReflectRef::Tuple(value)) => {
+            for (idx, item) in value.iter_fields().enumerate() {
+                if let Some(target_item) = target.field_mut(idx) {
+                    replace_reflect(target_item, item)?;
+                }
+            }
+        }
+        (ReflectMut::Array(target), ReflectRef::Array(value)) => {
+            for (idx, item) in value.iter().enumerate() {
+                if let Some(target_item) = target.get_mut(idx) {
+                    replace_reflect(target_item, item)?;
+                }
+            }
+        }
+        (ReflectMut::Enum(target), ReflectRef::Enum(value))
+            if target.variant_name() == value.variant_name() =>
+        {
+            for idx in 0..value.field_len() {
+                let Some(item) = value.field_at(idx) else {
+                    continue;
+                };
+                let target_item = match value.variant_type() {
+                    VariantType::Struct => {
+                        value.name_at(idx).and_then(|name| target.field_mut(name))
+                    }
+                    _ => target.field_at_mut(idx),
+                };
+                if let Some(target_item) = target_item {
+                    replace_reflect(target_item, item)?;
+                }
+            }
+        }
+        // Values and switched enum variants are replaced as a whole by apply
+        _ => target.try_apply(value).map_err(|e| e.to_string())?,
+    }
+    Ok(())
+}
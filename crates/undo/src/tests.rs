@@ -337,3 +337,369 @@ fn test_marker_sync() {
 
     assert!(app.world_mut().get::<UndoMarker>(id1).is_none());
 }
+
+#[test]
+fn test_reflected_change_stores_only_changed_fields() {
+    let old = Transform::from_xyz(1.0, 2.0, 3.0);
+    let new = Transform::from_xyz(2.5, 2.0, 3.0);
+
+    let change =
+        ReflectedComponentChange::<Transform>::from_diff(&old, &new, Entity::PLACEHOLDER).unwrap();
+    assert_eq!(change.patches().len(), 1);
+    assert_eq!(change.patches()[0].path, ".translation.x");
+    assert!(change
+        .debug_text()
+        .starts_with("Transform.translation.x: 1.0 → 2.5"));
+
+    assert!(
+        ReflectedComponentChange::<Transform>::from_diff(&old, &old, Entity::PLACEHOLDER).is_none()
+    );
+}
+
+#[test]
+fn test_reflected_undo_keeps_unchanged_fields() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Transform>();
+    app.update();
+
+    let test_id = app
+        .world_mut()
+        .spawn((Transform::default(), UndoMarker))
+        .id();
+    repeat_update(&mut app, 10);
+
+    app.world_mut()
+        .get_mut::<Transform>(test_id)
+        .unwrap()
+        .translation = Vec3::X;
+    repeat_update(&mut app, 10);
+
+    // Change made outside of undo tracking must survive the undo
+    app.world_mut()
+        .entity_mut(test_id)
+        .insert(OneFrameUndoIgnore::default());
+    app.world_mut().get_mut::<Transform>(test_id).unwrap().scale = Vec3::splat(2.0);
+    repeat_update(&mut app, 12);
+
+    app.world_mut().send_event(UndoRedo::Undo);
+    repeat_update(&mut app, 2);
+
+    let transform = app.world().get::<Transform>(test_id).unwrap();
+    assert_eq!(transform.translation, Vec3::ZERO);
+    assert_eq!(transform.scale, Vec3::splat(2.0));
+}
+
+#[test]
+fn test_resource_undo() {
+    let mut app = configure_app();
+    app.auto_resource_undo::<ChangeChainSettings>();
+
+    repeat_update(&mut app, 10);
+    assert!(app.world().resource::<ChangeChain>().changes.is_empty());
+
+    app.world_mut()
+        .resource_mut::<ChangeChainSettings>()
+        .max_change_chain_size = 100;
+    repeat_update(&mut app, 10);
+
+    let change_chain = app.world().resource::<ChangeChain>();
+    assert_eq!(change_chain.changes.len(), 1);
+    assert_eq!(
+        change_chain.changes[0].debug_text(),
+        "ChangeChainSettings.max_change_chain_size: 200 → 100"
+    );
+
+    app.world_mut().send_event(UndoRedo::Undo);
+    repeat_update(&mut app, 10);
+    assert_eq!(
+        app.world()
+            .resource::<ChangeChainSettings>()
+            .max_change_chain_size,
+        200
+    );
+    assert!(app.world().resource::<ChangeChain>().changes.is_empty());
+
+    app.world_mut().send_event(UndoRedo::Redo);
+    repeat_update(&mut app, 10);
+    assert_eq!(
+        app.world()
+            .resource::<ChangeChainSettings>()
+            .max_change_chain_size,
+        100
+    );
+    assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
+}
+
+#[test]
+fn test_committed_changes_have_records() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Transform>();
+
+    let test_id = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            UndoMarker,
+            OneFrameUndoIgnore::default(),
+        ))
+        .id();
+    repeat_update(&mut app, 10);
+    app.world_mut()
+        .resource_mut::<Events<ChangeCommitted>>()
+        .clear();
+
+    app.world_mut()
+        .get_mut::<Transform>(test_id)
+        .unwrap()
+        .translation
+        .y = 3.0;
+    repeat_update(&mut app, 10);
+
+    app.world_mut().send_event(UndoRedo::Undo);
+    app.update();
+
+    let events = app.world().resource::<Events<ChangeCommitted>>();
+    let records = events
+        .get_reader()
+        .read(events)
+        .filter_map(|event| event.change.record())
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 2);
+
+    let ChangeRecord::ComponentPatch {
+        entity, patches, ..
+    } = &records[0]
+    else {
+        panic!("Expected component patch record");
+    };
+    assert_eq!(*entity, test_id);
+    assert_eq!(patches[0].path, ".translation.y");
+
+    // Undo is committed as inverse change
+    let ChangeRecord::ComponentPatch { patches, .. } = &records[1] else {
+        panic!("Expected component patch record");
+    };
+    assert!(patches[0]
+        .new_value
+        .reflect_partial_eq(&0.0f32)
+        .unwrap_or_default());
+}
+
+#[test]
+fn test_change_labels() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Transform>();
+
+    let test_id = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Name::new("Crate"),
+            UndoMarker,
+            OneFrameUndoIgnore::default(),
+        ))
+        .id();
+    repeat_update(&mut app, 10);
+
+    app.world_mut()
+        .get_mut::<Transform>(test_id)
+        .unwrap()
+        .translation
+        .x = 2.0;
+    repeat_update(&mut app, 10);
+
+    let name_of = |entity| app.world().get::<Name>(entity).map(|name| name.to_string());
+    let chain = app.world().resource::<ChangeChain>();
+    assert_eq!(chain.undo_label(&name_of), Some("Move Crate".to_string()));
+    assert_eq!(chain.redo_label(&name_of), None);
+}
+
+#[test]
+fn test_many_changes_label_hides_component_changes_of_deleted_entity() {
+    let entity = Entity::from_raw(12);
+    let change = ManyChanges {
+        changes: vec![
+            Arc::new(LabeledChange::new("Delete Crate", RemovedEntity { entity })),
+            Arc::new(ReflectedRemovedComponent {
+                old_value: Transform::default(),
+                entity,
+            }),
+        ],
+    };
+    assert_eq!(change.label(&|_| None), "Delete Crate");
+
+    let change = ManyChanges {
+        changes: vec![
+            Arc::new(AddedEntity { entity }),
+            Arc::new(AddedEntity {
+                entity: Entity::from_raw(13),
+            }),
+        ],
+    };
+    assert_eq!(change.label(&|_| None), "Add Entity 12 and 1 more");
+}
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Component, Default)]
+struct Inventory {
+    items: Vec<u32>,
+    counts: bevy::utils::HashMap<String, u32>,
+}
+
+#[test]
+fn test_reflected_undo_of_resized_list_and_map() {
+    let mut app = configure_app();
+    app.auto_reflected_undo::<Inventory>();
+    app.update();
+
+    let test_id = app
+        .world_mut()
+        .spawn((
+            Inventory {
+                items: vec![1],
+                counts: [("a".to_string(), 1)].into_iter().collect(),
+            },
+            UndoMarker,
+        ))
+        .id();
+    repeat_update(&mut app, 10);
+    let initial = app.world().get::<Inventory>(test_id).unwrap().clone();
+
+    let inventory = |app: &App| app.world().get::<Inventory>(test_id).unwrap().clone();
+    let undo_redo = |app: &mut App, event: UndoRedo| {
+        app.world_mut().send_event(event);
+        // Wait until undo ignore of changed entity is over
+        repeat_update(app, 12);
+    };
+
+    // Grow list and map
+    app.world_mut()
+        .get_mut::<Inventory>(test_id)
+        .unwrap()
+        .items
+        .push(2);
+    repeat_update(&mut app, 10);
+    app.world_mut()
+        .get_mut::<Inventory>(test_id)
+        .unwrap()
+        .counts
+        .insert("b".to_string(), 2);
+    repeat_update(&mut app, 10);
+    let grown = inventory(&app);
+
+    undo_redo(&mut app, UndoRedo::Undo);
+    assert!(!inventory(&app).counts.contains_key("b"));
+    undo_redo(&mut app, UndoRedo::Undo);
+    assert_eq!(inventory(&app), initial);
+    undo_redo(&mut app, UndoRedo::Redo);
+    undo_redo(&mut app, UndoRedo::Redo);
+    assert_eq!(inventory(&app), grown);
+
+    // Shrink list and map
+    app.world_mut()
+        .get_mut::<Inventory>(test_id)
+        .unwrap()
+        .items
+        .clear();
+    repeat_update(&mut app, 10);
+    app.world_mut()
+        .get_mut::<Inventory>(test_id)
+        .unwrap()
+        .counts
+        .remove("a");
+    repeat_update(&mut app, 10);
+    let shrunk = inventory(&app);
+    assert!(shrunk.items.is_empty());
+
+    undo_redo(&mut app, UndoRedo::Undo);
+    undo_redo(&mut app, UndoRedo::Undo);
+    assert_eq!(inventory(&app), grown);
+    undo_redo(&mut app, UndoRedo::Redo);
+    undo_redo(&mut app, UndoRedo::Redo);
+    assert_eq!(inventory(&app), shrunk);
+
+    // Cache matches world after undo, so new change is recorded from restored value
+    undo_redo(&mut app, UndoRedo::Undo);
+    app.world_mut()
+        .get_mut::<Inventory>(test_id)
+        .unwrap()
+        .items
+        .push(5);
+    repeat_update(&mut app, 10);
+    undo_redo(&mut app, UndoRedo::Undo);
+    let restored = inventory(&app);
+    assert!(restored.items.is_empty());
+    assert!(restored.counts.contains_key("a"));
+}
+
+#[derive(Resource, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Resource, Default)]
+struct RecentFiles {
+    files: Vec<String>,
+}
+
+#[test]
+fn test_resource_undo_of_resized_list() {
+    let mut app = configure_app();
+    app.init_resource::<RecentFiles>()
+        .auto_resource_undo::<RecentFiles>();
+    repeat_update(&mut app, 10);
+
+    app.world_mut()
+        .resource_mut::<RecentFiles>()
+        .files
+        .push("a".to_string());
+    repeat_update(&mut app, 10);
+    assert_eq!(app.world().resource::<ChangeChain>().changes.len(), 1);
+
+    app.world_mut().send_event(UndoRedo::Undo);
+    repeat_update(&mut app, 10);
+    assert!(app.world().resource::<RecentFiles>().files.is_empty());
+
+    app.world_mut().send_event(UndoRedo::Redo);
+    repeat_update(&mut app, 10);
+    assert_eq!(app.world().resource::<RecentFiles>().files, vec!["a"]);
+}
+
+#[test]
+fn test_not_reflected_changes_are_marked_unrecorded() {
+    let entity = Entity::from_raw(1);
+    let change = ComponentChange {
+        old_value: Name::new("a"),
+        new_value: Name::new("b"),
+        entity,
+    };
+    assert!(matches!(change.record(), Some(ChangeRecord::Unrecorded(_))));
+
+    let change = RemovedComponent {
+        old_value: Name::new("a"),
+        entity,
+    };
+    assert!(matches!(
+        change.record(),
+        Some(ChangeRecord::RemovedComponent { entity: e, .. }) if e == entity
+    ));
+
+    let change = ManyChanges {
+        changes: vec![
+            Arc::new(AddedEntity { entity }),
+            Arc::new(AddedComponent {
+                new_value: Name::new("a"),
+                entity,
+            }),
+        ],
+    };
+    let Some(ChangeRecord::Many(records)) = change.record() else {
+        panic!("Expected many records");
+    };
+    assert!(matches!(records[1], ChangeRecord::Unrecorded(_)));
+
+    let change = ResourceChange::<ChangeChainSettings>::from_diff(
+        &ChangeChainSettings::default(),
+        &ChangeChainSettings {
+            max_change_chain_size: 1,
+        },
+    )
+    .unwrap();
+    assert!(change.record().is_none());
+}
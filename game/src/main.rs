@@ -3,6 +3,8 @@ use bevy::{
     window::{WindowMode, WindowResolution},
 };
 use game_lib::GamePlugin;
+#[cfg(debug_assertions)]
+use space_prefab::prelude::LiveLinkClientPlugin;
 use space_prefab::prelude::{PrefabBundle, PrefabPlugin};
 
 fn main() {
@@ -19,9 +21,11 @@ fn main() {
         }),
         ..default()
     }))
-    .add_plugins((PrefabPlugin, GamePlugin))
-    .add_systems(Startup, setup)
-    .run();
+    .add_plugins((PrefabPlugin, GamePlugin));
+    // Receives scene edits from running editor. Only debug builds of game listen to editor
+    #[cfg(debug_assertions)]
+    app.add_plugins(LiveLinkClientPlugin::default());
+    app.add_systems(Startup, setup).run();
 }
 
 fn setup(mut commands: Commands, _assets: Res<AssetServer>) {